@@ -0,0 +1,517 @@
+use ratatui::Frame;
+use ratatui::crossterm::event::KeyCode;
+use ratatui::crossterm::event::KeyEvent;
+use ratatui::crossterm::event::KeyEventKind;
+use ratatui::layout::Constraint;
+use ratatui::layout::Layout;
+use ratatui::style::Modifier;
+use ratatui::style::Style;
+use ratatui::text::Line;
+use ratatui::widgets::Block;
+use ratatui::widgets::Borders;
+use ratatui::widgets::List;
+use ratatui::widgets::ListItem;
+use ratatui::widgets::ListState;
+use ratatui::widgets::Paragraph;
+use uiautomation::Result;
+use uiautomation::patterns::UIPatternType;
+use uiautomation::snapshots::ElementSnapshot;
+use uiautomation::trees::TreeNavigator;
+
+use crate::format::Property;
+
+const HELP: &str = "↑↓ move  →← expand/collapse  i invoke  t toggle  e expand/collapse  v set value  c copy selector  r refresh  q quit";
+
+/// The control pattern actions which can be performed on the selected element.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Action {
+    /// Invokes by the Invoke pattern.
+    Invoke,
+    /// Toggles by the Toggle pattern.
+    Toggle,
+    /// Expands or collapses by the ExpandCollapse pattern.
+    ExpandCollapse,
+    /// Sets the value by the Value pattern.
+    SetValue(String)
+}
+
+impl Action {
+    fn get_name(&self) -> &'static str {
+        match self {
+            Action::Invoke => "invoke",
+            Action::Toggle => "toggle",
+            Action::ExpandCollapse => "expand/collapse",
+            Action::SetValue(_) => "set value",
+        }
+    }
+}
+
+/// The automation functions used by `Browser`, which walks the tree lazily by `TreeNavigator`.
+pub trait Backend: TreeNavigator {
+    /// Retrieves the root node.
+    fn get_root(&self) -> Result<Self::Node>;
+
+    /// Captures the properties of `node`, without its children.
+    fn get_snapshot(&self, node: &Self::Node) -> Result<ElementSnapshot>;
+
+    /// Retrieves the keys and text values of all the properties of `node`, including those not captured by snapshots.
+    fn get_properties(&self, node: &Self::Node) -> Result<Vec<(String, String)>>;
+
+    /// Retrieves the control patterns supported by `node`.
+    fn get_patterns(&self, node: &Self::Node) -> Result<Vec<UIPatternType>>;
+
+    /// Performs `action` on `node`.
+    fn perform(&self, node: &Self::Node, action: &Action) -> Result<()>;
+
+    /// Generates the selector of `node`.
+    fn get_selector(&self, node: &Self::Node) -> Result<String>;
+
+    /// Copies `text` to the clipboard.
+    fn copy_text(&self, text: &str) -> Result<()>;
+}
+
+/// A visible row of the tree.
+struct TreeItem<N> {
+    node: N,
+    depth: usize,
+    label: String,
+    expanded: bool
+}
+
+/// The properties and patterns of the selected element.
+#[derive(Default)]
+struct Details {
+    properties: Vec<(String, String)>,
+    patterns: Vec<UIPatternType>
+}
+
+/// An interactive tree browser, which handles the keys and renders to a terminal frame.
+pub struct Browser<B: Backend> {
+    backend: B,
+    items: Vec<TreeItem<B::Node>>,
+    selected: usize,
+    details: Details,
+    status: String,
+    input: Option<String>,
+    running: bool
+}
+
+impl<B: Backend> Browser<B> {
+    /// Creates a browser with the root and its children.
+    pub fn new(backend: B) -> Result<Self> {
+        let root = backend.get_root()?;
+        let label = get_label(&backend.get_snapshot(&root)?);
+
+        let mut browser = Self {
+            backend,
+            items: vec![TreeItem { node: root, depth: 0, label, expanded: false }],
+            selected: 0,
+            details: Details::default(),
+            status: String::new(),
+            input: None,
+            running: true
+        };
+        browser.expand();
+        browser.load_details();
+
+        Ok(browser)
+    }
+
+    /// Retrieves the backend.
+    pub fn get_backend(&self) -> &B {
+        &self.backend
+    }
+
+    /// Retrieves the visible rows as `(depth, label)`.
+    pub fn get_rows(&self) -> Vec<(usize, &str)> {
+        self.items.iter().map(|item| (item.depth, item.label.as_str())).collect()
+    }
+
+    /// Retrieves the index of the selected row.
+    pub fn get_selected(&self) -> usize {
+        self.selected
+    }
+
+    /// Retrieves the status message.
+    pub fn get_status(&self) -> &str {
+        &self.status
+    }
+
+    /// Returns `false` after the user quits.
+    pub fn is_running(&self) -> bool {
+        self.running
+    }
+
+    /// Handles a key press.
+    pub fn handle_key(&mut self, key: KeyEvent) {
+        if key.kind != KeyEventKind::Press {
+            return;
+        }
+
+        if let Some(ref mut input) = self.input {
+            match key.code {
+                KeyCode::Char(c) => input.push(c),
+                KeyCode::Backspace => {
+                    input.pop();
+                },
+                KeyCode::Enter => {
+                    let value = std::mem::take(input);
+                    self.input = None;
+                    self.perform(Action::SetValue(value));
+                },
+                KeyCode::Esc => self.input = None,
+                _ => {},
+            }
+            return;
+        }
+
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => self.running = false,
+            KeyCode::Up | KeyCode::Char('k') => self.select(self.selected.saturating_sub(1)),
+            KeyCode::Down | KeyCode::Char('j') => self.select(self.selected + 1),
+            KeyCode::Right | KeyCode::Char('l') | KeyCode::Enter => self.expand(),
+            KeyCode::Left | KeyCode::Char('h') => self.collapse(),
+            KeyCode::Char('i') => self.perform(Action::Invoke),
+            KeyCode::Char('t') => self.perform(Action::Toggle),
+            KeyCode::Char('e') => self.perform(Action::ExpandCollapse),
+            KeyCode::Char('v') => self.input = Some(String::new()),
+            KeyCode::Char('c') => self.copy_selector(),
+            KeyCode::Char('r') => self.refresh(),
+            _ => {},
+        }
+    }
+
+    fn select(&mut self, index: usize) {
+        let index = index.min(self.items.len() - 1);
+        if index != self.selected {
+            self.selected = index;
+            self.status.clear();
+            self.load_details();
+        }
+    }
+
+    /// Expands the selected row, loading its children.
+    fn expand(&mut self) {
+        let index = self.selected;
+        if self.items[index].expanded {
+            return;
+        }
+
+        match self.load_children(index) {
+            Ok(children) => {
+                self.items[index].expanded = true;
+                self.items.splice(index + 1..index + 1, children);
+            },
+            Err(e) => self.status = format!("failed to expand: {}", e),
+        }
+    }
+
+    fn load_children(&self, index: usize) -> Result<Vec<TreeItem<B::Node>>> {
+        let depth = self.items[index].depth + 1;
+        let mut children: Vec<TreeItem<B::Node>> = Vec::new();
+        let mut next = self.backend.get_first_child(&self.items[index].node)?;
+        while let Some(node) = next {
+            // the elements may disappear while walking.
+            let label = self.backend.get_snapshot(&node).map(|s| get_label(&s)).unwrap_or_else(|e| format!("<{}>", e));
+            next = self.backend.get_next_sibling(&node)?;
+            children.push(TreeItem { node, depth, label, expanded: false });
+        }
+        Ok(children)
+    }
+
+    /// Collapses the selected row, or selects its parent if it is collapsed.
+    fn collapse(&mut self) {
+        let index = self.selected;
+        let depth = self.items[index].depth;
+        if self.items[index].expanded {
+            let end = self.items[index + 1..].iter().position(|item| item.depth <= depth).map_or(self.items.len(), |p| index + 1 + p);
+            self.items.drain(index + 1..end);
+            self.items[index].expanded = false;
+        } else if let Some(parent) = self.items[..index].iter().rposition(|item| item.depth < depth) {
+            self.select(parent);
+        }
+    }
+
+    /// Reloads the children and details of the selected row.
+    fn refresh(&mut self) {
+        if self.items[self.selected].expanded {
+            self.collapse();
+            self.expand();
+        }
+        self.load_details();
+    }
+
+    fn perform(&mut self, action: Action) {
+        let node = &self.items[self.selected].node;
+        self.status = match self.backend.perform(node, &action) {
+            Ok(_) => format!("{} done", action.get_name()),
+            Err(e) => format!("failed to {}: {}", action.get_name(), e),
+        };
+        self.load_details();
+    }
+
+    fn copy_selector(&mut self) {
+        let node = &self.items[self.selected].node;
+        self.status = match self.backend.get_selector(node).and_then(|s| self.backend.copy_text(&s).map(|_| s)) {
+            Ok(selector) => format!("copied {}", selector),
+            Err(e) => format!("failed to copy selector: {}", e),
+        };
+    }
+
+    fn load_details(&mut self) {
+        let node = &self.items[self.selected].node;
+        self.details = match self.backend.get_properties(node) {
+            Ok(properties) => Details {
+                properties,
+                patterns: self.backend.get_patterns(node).unwrap_or_default()
+            },
+            Err(e) => {
+                self.status = format!("failed to read properties: {}", e);
+                Details::default()
+            },
+        };
+    }
+
+    /// Renders the tree, the details of the selected element and the status line.
+    pub fn render(&self, frame: &mut Frame) {
+        let [main, bottom] = Layout::vertical([Constraint::Min(1), Constraint::Length(1)]).areas(frame.area());
+        let [left, right] = Layout::horizontal([Constraint::Percentage(50), Constraint::Percentage(50)]).areas(main);
+
+        let rows: Vec<ListItem> = self.items.iter().map(|item| {
+            let marker = if item.expanded { "▾" } else { "▸" };
+            ListItem::new(format!("{}{} {}", "  ".repeat(item.depth), marker, item.label))
+        }).collect();
+        let tree = List::new(rows)
+            .block(Block::default().borders(Borders::ALL).title("Tree"))
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+        let mut state = ListState::default().with_selected(Some(self.selected));
+        frame.render_stateful_widget(tree, left, &mut state);
+
+        let mut lines: Vec<Line> = self.details.properties.iter().map(|(key, value)| Line::from(format!("{}: {}", key, value))).collect();
+        lines.push(Line::from(""));
+        lines.push(Line::from("patterns:"));
+        lines.extend(self.details.patterns.iter().map(|p| Line::from(format!("  {:?}", p))));
+        let details = Paragraph::new(lines).block(Block::default().borders(Borders::ALL).title("Properties"));
+        frame.render_widget(details, right);
+
+        let status = match self.input {
+            Some(ref input) => format!("value: {}_", input),
+            None if self.status.is_empty() => String::from(HELP),
+            None => self.status.clone(),
+        };
+        frame.render_widget(Paragraph::new(status), bottom);
+    }
+}
+
+/// Formats the tree label of an element, such as `Button "OK"`.
+fn get_label(snapshot: &ElementSnapshot) -> String {
+    format!("{} {}", Property::ControlType.get_value(snapshot).to_text(), Property::Name.get_value(snapshot).to_text())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+
+    use ratatui::Terminal;
+    use ratatui::backend::TestBackend;
+    use ratatui::crossterm::event::KeyCode;
+    use ratatui::crossterm::event::KeyEvent;
+    use ratatui::crossterm::event::KeyModifiers;
+    use uiautomation::Error;
+    use uiautomation::Result;
+    use uiautomation::controls::ControlType;
+    use uiautomation::errors::ERR_NOTFOUND;
+    use uiautomation::patterns::UIPatternType;
+    use uiautomation::selectors::Selector;
+    use uiautomation::snapshots::ElementSnapshot;
+    use uiautomation::trees::TreeNavigator;
+
+    use super::Action;
+    use super::Backend;
+    use super::Browser;
+
+    use crate::format::Property;
+
+    /// A fake backend over a snapshot, whose nodes are the paths of descendants.
+    struct FakeBackend {
+        root: ElementSnapshot,
+        log: RefCell<Vec<String>>
+    }
+
+    impl FakeBackend {
+        fn new() -> Self {
+            let root = ElementSnapshot::new().name("Desktop").control_type(ControlType::Pane)
+                .child(ElementSnapshot::new().name("Notepad").control_type(ControlType::Window)
+                    .child(ElementSnapshot::new().automation_id("15").control_type(ControlType::Edit))
+                    .child(ElementSnapshot::new().name("Save").control_type(ControlType::Button))
+                    .child(ElementSnapshot::new().name("Wrap").control_type(ControlType::CheckBox)))
+                .child(ElementSnapshot::new().name("Taskbar").control_type(ControlType::Pane));
+            Self {
+                root,
+                log: RefCell::new(Vec::new())
+            }
+        }
+
+        fn get(&self, path: &[usize]) -> Result<&ElementSnapshot> {
+            self.root.get_descendant(path).ok_or_else(|| Error::new(ERR_NOTFOUND, "no such node"))
+        }
+    }
+
+    impl TreeNavigator for FakeBackend {
+        type Node = Vec<usize>;
+
+        fn get_parent(&self, node: &Vec<usize>) -> Result<Option<Vec<usize>>> {
+            Ok(node.split_last().map(|(_, parent)| parent.to_vec()))
+        }
+
+        fn get_first_child(&self, node: &Vec<usize>) -> Result<Option<Vec<usize>>> {
+            let has_children = !self.get(node)?.children.is_empty();
+            Ok(has_children.then(|| [node.as_slice(), &[0]].concat()))
+        }
+
+        fn get_next_sibling(&self, node: &Vec<usize>) -> Result<Option<Vec<usize>>> {
+            let Some((index, parent)) = node.split_last() else {
+                return Ok(None);
+            };
+            let mut next = parent.to_vec();
+            next.push(index + 1);
+            Ok(self.root.get_descendant(&next).map(|_| next))
+        }
+    }
+
+    impl Backend for FakeBackend {
+        fn get_root(&self) -> Result<Vec<usize>> {
+            Ok(Vec::new())
+        }
+
+        fn get_snapshot(&self, node: &Vec<usize>) -> Result<ElementSnapshot> {
+            let mut snapshot = self.get(node)?.clone();
+            snapshot.children.clear();
+            Ok(snapshot)
+        }
+
+        fn get_properties(&self, node: &Vec<usize>) -> Result<Vec<(String, String)>> {
+            let mut properties = Property::get_texts(&self.get_snapshot(node)?);
+            properties.push((String::from("help_text"), format!("\"{} help\"", self.get(node)?.name)));
+            Ok(properties)
+        }
+
+        fn get_patterns(&self, node: &Vec<usize>) -> Result<Vec<UIPatternType>> {
+            Ok(match self.get(node)?.control_type {
+                Some(ControlType::Button) => vec![UIPatternType::Invoke],
+                Some(ControlType::CheckBox) => vec![UIPatternType::Toggle],
+                Some(ControlType::Edit) => vec![UIPatternType::Value, UIPatternType::Text],
+                _ => Vec::new(),
+            })
+        }
+
+        fn perform(&self, node: &Vec<usize>, action: &Action) -> Result<()> {
+            let supported = match action {
+                Action::Invoke => UIPatternType::Invoke,
+                Action::Toggle => UIPatternType::Toggle,
+                Action::ExpandCollapse => UIPatternType::ExpandCollapse,
+                Action::SetValue(_) => UIPatternType::Value,
+            };
+            if !self.get_patterns(node)?.contains(&supported) {
+                return Err(Error::new(ERR_NOTFOUND, "pattern not supported"));
+            }
+            self.log.borrow_mut().push(format!("{:?} {:?}", action, node));
+            Ok(())
+        }
+
+        fn get_selector(&self, node: &Vec<usize>) -> Result<String> {
+            Ok(Selector::from_snapshot(&self.root, node)?.to_string())
+        }
+
+        fn copy_text(&self, text: &str) -> Result<()> {
+            self.log.borrow_mut().push(format!("copy {}", text));
+            Ok(())
+        }
+    }
+
+    fn press(browser: &mut Browser<FakeBackend>, keys: &[KeyCode]) {
+        for key in keys {
+            browser.handle_key(KeyEvent::new(*key, KeyModifiers::NONE));
+        }
+    }
+
+    fn render(browser: &Browser<FakeBackend>) -> Vec<String> {
+        let mut terminal = Terminal::new(TestBackend::new(100, 20)).unwrap();
+        terminal.draw(|frame| browser.render(frame)).unwrap();
+        let buffer = terminal.backend().buffer();
+        (0..buffer.area.height)
+            .map(|y| (0..buffer.area.width).map(|x| buffer[(x, y)].symbol()).collect::<String>())
+            .collect()
+    }
+
+    #[test]
+    fn test_navigate() {
+        let mut browser = Browser::new(FakeBackend::new()).unwrap();
+        assert_eq!(browser.get_rows(), vec![(0, "Pane \"Desktop\""), (1, "Window \"Notepad\""), (1, "Pane \"Taskbar\"")]);
+
+        press(&mut browser, &[KeyCode::Down, KeyCode::Right]);
+        assert_eq!(browser.get_rows().len(), 6);
+        assert_eq!(browser.get_rows()[2], (2, "Edit \"\""));
+        assert_eq!(browser.get_rows()[5], (1, "Pane \"Taskbar\""));
+
+        // the leaf has no children to expand, and moving stops at the last row.
+        press(&mut browser, &[KeyCode::Down, KeyCode::Right]);
+        assert_eq!(browser.get_rows().len(), 6);
+        press(&mut browser, &[KeyCode::Down, KeyCode::Down, KeyCode::Down, KeyCode::Down]);
+        assert_eq!(browser.get_selected(), 5);
+
+        press(&mut browser, &[KeyCode::Up, KeyCode::Up, KeyCode::Left]);
+        assert_eq!(browser.get_selected(), 1);
+        press(&mut browser, &[KeyCode::Left]);
+        assert_eq!(browser.get_rows().len(), 3);
+        press(&mut browser, &[KeyCode::Left]);
+        assert_eq!(browser.get_selected(), 0);
+
+        assert!(browser.is_running());
+        press(&mut browser, &[KeyCode::Char('q')]);
+        assert!(!browser.is_running());
+    }
+
+    #[test]
+    fn test_actions() {
+        let mut browser = Browser::new(FakeBackend::new()).unwrap();
+        press(&mut browser, &[KeyCode::Down, KeyCode::Right, KeyCode::Down, KeyCode::Down, KeyCode::Char('i')]);
+        assert_eq!(browser.get_status(), "invoke done");
+
+        press(&mut browser, &[KeyCode::Char('t')]);
+        assert!(browser.get_status().starts_with("failed to toggle"));
+
+        press(&mut browser, &[KeyCode::Up, KeyCode::Char('v'), KeyCode::Char('a'), KeyCode::Char('x'), KeyCode::Backspace, KeyCode::Char('b')]);
+        assert!(render(&browser).last().unwrap().starts_with("value: ab_"));
+        press(&mut browser, &[KeyCode::Enter]);
+        assert_eq!(browser.get_status(), "set value done");
+
+        press(&mut browser, &[KeyCode::Char('c')]);
+        assert_eq!(browser.get_status(), "copied /Window[@name=\"Notepad\"]/*[@id=\"15\"]");
+
+        assert_eq!(*browser.get_backend().log.borrow(), vec![
+            String::from("Invoke [0, 1]"),
+            String::from("SetValue(\"ab\") [0, 0]"),
+            String::from("copy /Window[@name=\"Notepad\"]/*[@id=\"15\"]")
+        ]);
+    }
+
+    #[test]
+    fn test_render() {
+        let mut browser = Browser::new(FakeBackend::new()).unwrap();
+        press(&mut browser, &[KeyCode::Down, KeyCode::Right, KeyCode::Down]);
+
+        let screen = render(&browser);
+        let text = screen.join("\n");
+        assert!(screen[0].contains("Tree") && screen[0].contains("Properties"));
+        assert!(screen[1].contains("▾ Pane \"Desktop\""));
+        assert!(screen[2].contains("  ▾ Window \"Notepad\""));
+        assert!(screen[3].contains("    ▸ Edit \"\""));
+        assert!(text.contains("type: Edit"));
+        assert!(text.contains("id: \"15\""));
+        assert!(text.contains("help_text: \" help\""));
+        assert!(text.contains("  Value"));
+        assert!(text.contains("  Text"));
+        assert!(screen.last().unwrap().starts_with("↑↓ move"));
+    }
+}
@@ -0,0 +1,285 @@
+use std::fmt::Display;
+use std::str::FromStr;
+
+use uiautomation::snapshots::ElementSnapshot;
+
+/// The output format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum Format {
+    /// One line per element, indented by depth.
+    #[default]
+    Text,
+    /// JSON objects, with `children` in trees.
+    Json
+}
+
+/// The element properties which can be printed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Property {
+    ControlType,
+    Name,
+    AutomationId,
+    ClassName,
+    FrameworkId,
+    ProcessId,
+    Rect,
+    RuntimeId,
+    Enabled,
+    Offscreen
+}
+
+impl Property {
+    /// All the properties, in printing order.
+    pub const ALL: [Property; 10] = [
+        Property::ControlType,
+        Property::Name,
+        Property::AutomationId,
+        Property::ClassName,
+        Property::FrameworkId,
+        Property::ProcessId,
+        Property::Rect,
+        Property::RuntimeId,
+        Property::Enabled,
+        Property::Offscreen
+    ];
+
+    /// The properties printed by default.
+    pub const DEFAULT: [Property; 4] = [
+        Property::ControlType,
+        Property::Name,
+        Property::AutomationId,
+        Property::ClassName
+    ];
+
+    /// Retrieves the key of the property in outputs and arguments.
+    pub fn get_key(&self) -> &'static str {
+        match self {
+            Property::ControlType => "type",
+            Property::Name => "name",
+            Property::AutomationId => "id",
+            Property::ClassName => "class",
+            Property::FrameworkId => "framework",
+            Property::ProcessId => "pid",
+            Property::Rect => "rect",
+            Property::RuntimeId => "runtime_id",
+            Property::Enabled => "enabled",
+            Property::Offscreen => "offscreen",
+        }
+    }
+
+    /// Retrieves the value of the property from `snapshot`.
+    pub fn get_value(&self, snapshot: &ElementSnapshot) -> Value {
+        match self {
+            Property::ControlType => snapshot.control_type.map_or(Value::Null, |t| Value::Raw(format!("{:?}", t))),
+            Property::Name => Value::Text(snapshot.name.clone()),
+            Property::AutomationId => Value::Text(snapshot.automation_id.clone()),
+            Property::ClassName => Value::Text(snapshot.classname.clone()),
+            Property::FrameworkId => Value::Text(snapshot.framework_id.clone()),
+            Property::ProcessId => Value::Number(snapshot.process_id as i64),
+            Property::Rect => {
+                let rect = &snapshot.bounding_rectangle;
+                Value::Numbers(vec![rect.get_left() as i64, rect.get_top() as i64, rect.get_right() as i64, rect.get_bottom() as i64])
+            },
+            Property::RuntimeId => Value::Numbers(snapshot.runtime_id.iter().map(|id| *id as i64).collect()),
+            Property::Enabled => Value::Bool(snapshot.is_enabled),
+            Property::Offscreen => Value::Bool(snapshot.is_offscreen),
+        }
+    }
+}
+
+impl Property {
+    /// Retrieves the keys and text values of all the properties from `snapshot`.
+    pub fn get_texts(snapshot: &ElementSnapshot) -> Vec<(String, String)> {
+        Property::ALL.iter().map(|p| (String::from(p.get_key()), p.get_value(snapshot).to_text())).collect()
+    }
+}
+
+impl Display for Property {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.get_key())
+    }
+}
+
+impl FromStr for Property {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let key = s.trim();
+        Property::ALL.into_iter()
+            .find(|p| p.get_key() == key)
+            .ok_or_else(|| format!("unknown property '{}', expected one of: {}", key, Property::ALL.map(|p| p.get_key()).join(", ")))
+    }
+}
+
+/// A property value, which is formatted differently in text and JSON.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Null,
+    /// A string which is quoted in both formats.
+    Text(String),
+    /// A name which is quoted in JSON only.
+    Raw(String),
+    Number(i64),
+    Numbers(Vec<i64>),
+    Bool(bool)
+}
+
+impl Value {
+    /// Formats the value for the text output.
+    pub fn to_text(&self) -> String {
+        match self {
+            Value::Null => String::from("*"),
+            Value::Text(text) => quote(text),
+            Value::Raw(text) => text.clone(),
+            Value::Number(value) => value.to_string(),
+            Value::Numbers(values) => format!("({})", join_numbers(values)),
+            Value::Bool(value) => value.to_string(),
+        }
+    }
+
+    /// Formats the value for the JSON output.
+    pub fn to_json(&self) -> String {
+        match self {
+            Value::Null => String::from("null"),
+            Value::Text(text) | Value::Raw(text) => quote(text),
+            Value::Number(value) => value.to_string(),
+            Value::Numbers(values) => format!("[{}]", join_numbers(values)),
+            Value::Bool(value) => value.to_string(),
+        }
+    }
+}
+
+fn join_numbers(values: &[i64]) -> String {
+    values.iter().map(|v| v.to_string()).collect::<Vec<String>>().join(",")
+}
+
+/// Quotes the string with JSON escapes, which are also readable in text.
+fn quote(value: &str) -> String {
+    let mut quoted = String::with_capacity(value.len() + 2);
+    quoted.push('"');
+    for ch in value.chars() {
+        match ch {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c if (c as u32) < 0x20 => quoted.push_str(&format!("\\u{:04x}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Formats the properties of one element in one line, such as `type=Button name="OK"`.
+pub fn format_line(snapshot: &ElementSnapshot, properties: &[Property]) -> String {
+    properties.iter()
+        .map(|p| format!("{}={}", p.get_key(), p.get_value(snapshot).to_text()))
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+/// Formats the properties of one element as a JSON object, without children.
+pub fn format_object(snapshot: &ElementSnapshot, properties: &[Property]) -> String {
+    let fields: Vec<String> = properties.iter()
+        .map(|p| format!("{}:{}", quote(p.get_key()), p.get_value(snapshot).to_json()))
+        .collect();
+    format!("{{{}}}", fields.join(","))
+}
+
+/// Formats the snapshot and its captured descendants.
+pub fn format_tree(snapshot: &ElementSnapshot, properties: &[Property], format: Format) -> String {
+    match format {
+        Format::Text => {
+            let mut lines: Vec<String> = Vec::new();
+            write_text_tree(snapshot, properties, 0, &mut lines);
+            lines.join("\n")
+        },
+        Format::Json => format_json_tree(snapshot, properties),
+    }
+}
+
+fn write_text_tree(snapshot: &ElementSnapshot, properties: &[Property], level: usize, lines: &mut Vec<String>) {
+    lines.push(format!("{}{}", "  ".repeat(level), format_line(snapshot, properties)));
+    for child in &snapshot.children {
+        write_text_tree(child, properties, level + 1, lines);
+    }
+}
+
+fn format_json_tree(snapshot: &ElementSnapshot, properties: &[Property]) -> String {
+    let object = format_object(snapshot, properties);
+    let children: Vec<String> = snapshot.children.iter().map(|c| format_json_tree(c, properties)).collect();
+    let separator = if properties.is_empty() { "" } else { "," };
+    format!("{}{}\"children\":[{}]}}", &object[..object.len() - 1], separator, children.join(","))
+}
+
+/// Formats a list of elements without their children, one per line in text or as a JSON array.
+pub fn format_list(snapshots: &[ElementSnapshot], properties: &[Property], format: Format) -> String {
+    match format {
+        Format::Text => snapshots.iter().map(|s| format_line(s, properties)).collect::<Vec<String>>().join("\n"),
+        Format::Json => format!("[{}]", snapshots.iter().map(|s| format_object(s, properties)).collect::<Vec<String>>().join(",")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use uiautomation::controls::ControlType;
+    use uiautomation::snapshots::ElementSnapshot;
+    use uiautomation::types::Rect;
+
+    use super::Format;
+    use super::Property;
+    use super::format_line;
+    use super::format_list;
+    use super::format_tree;
+
+    fn notepad() -> ElementSnapshot {
+        ElementSnapshot::new().name("Untitled - Notepad").classname("Notepad").control_type(ControlType::Window)
+            .child(ElementSnapshot::new().automation_id("15").classname("Edit").control_type(ControlType::Document))
+            .child(ElementSnapshot::new().name("Say \"hi\"").control_type(ControlType::Button))
+    }
+
+    #[test]
+    fn test_parse_property() {
+        assert_eq!("id".parse::<Property>(), Ok(Property::AutomationId));
+        assert_eq!(" rect ".parse::<Property>(), Ok(Property::Rect));
+        assert!("color".parse::<Property>().unwrap_err().contains("runtime_id"));
+        for property in Property::ALL {
+            assert_eq!(property.to_string().parse::<Property>(), Ok(property));
+        }
+    }
+
+    #[test]
+    fn test_text() {
+        let root = notepad();
+        assert_eq!(format_line(&root, &Property::DEFAULT), r#"type=Window name="Untitled - Notepad" id="" class="Notepad""#);
+        assert_eq!(format_tree(&root, &[Property::ControlType, Property::Name], Format::Text), [
+            r#"type=Window name="Untitled - Notepad""#,
+            r#"  type=Document name="""#,
+            r#"  type=Button name="Say \"hi\"""#
+        ].join("\n"));
+
+        let mut element = ElementSnapshot::new();
+        element.bounding_rectangle = Rect::new(1, 2, 30, 40);
+        element.runtime_id = vec![42, 7];
+        assert_eq!(format_line(&element, &[Property::ControlType, Property::Rect, Property::RuntimeId, Property::Enabled]),
+            "type=* rect=(1,2,30,40) runtime_id=(42,7) enabled=false");
+    }
+
+    #[test]
+    fn test_json() {
+        let root = notepad();
+        assert_eq!(format_tree(&root, &[Property::ControlType, Property::AutomationId], Format::Json),
+            r#"{"type":"Window","id":"","children":[{"type":"Document","id":"15","children":[]},{"type":"Button","id":"","children":[]}]}"#);
+        assert_eq!(format_tree(&ElementSnapshot::new(), &[], Format::Json), r#"{"children":[]}"#);
+
+        let mut element = ElementSnapshot::new().name("a\nb");
+        element.process_id = 100;
+        element.bounding_rectangle = Rect::new(1, 2, 30, 40);
+        assert_eq!(format_list(&[element, ElementSnapshot::new()], &[Property::Name, Property::ProcessId, Property::Rect, Property::ControlType], Format::Json),
+            r#"[{"name":"a\nb","pid":100,"rect":[1,2,30,40],"type":null},{"name":"","pid":0,"rect":[0,0,0,0],"type":null}]"#);
+        assert_eq!(format_list(&[], &Property::DEFAULT, Format::Json), "[]");
+        assert_eq!(format_list(&[], &Property::DEFAULT, Format::Text), "");
+    }
+}
@@ -0,0 +1,3 @@
+pub mod format;
+#[cfg(feature = "tui")]
+pub mod browser;
@@ -0,0 +1,257 @@
+use std::thread::sleep;
+use std::time::Duration;
+
+use clap::Args;
+use clap::Parser;
+use clap::Subcommand;
+use uiautomation::Error;
+use uiautomation::Result;
+use uiautomation::UIAutomation;
+use uiautomation::UIElement;
+use uiautomation::controls::ControlType;
+use uiautomation::errors::ERR_FORMAT;
+use uiautomation::filters::Regex;
+use uiautomation::selectors::Selector;
+use uiautomation::snapshots::ElementSnapshot;
+use uiautomation::types::Point;
+
+use uia_inspect::format::Format;
+use uia_inspect::format::Property;
+use uia_inspect::format::format_list;
+use uia_inspect::format::format_tree;
+
+/// Inspects the UI Automation elements on Windows.
+#[derive(Debug, Parser)]
+#[command(name = "uia-inspect", version, about)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Dumps the element tree from the root, or from the element of a selector.
+    Tree {
+        /// The selector of the root element, such as `/Window[@name="Calculator"]`.
+        #[arg(short, long)]
+        selector: Option<String>,
+        /// The depth of the descendants to dump. The children are at depth 1.
+        #[arg(short, long, default_value_t = 3)]
+        depth: u32,
+        #[command(flatten)]
+        output: Output
+    },
+    /// Finds elements by a selector, or by the match options.
+    Find {
+        /// The selector of the element. The match options are ignored when it is set.
+        #[arg(short, long)]
+        selector: Option<String>,
+        /// Matches the name.
+        #[arg(long)]
+        name: Option<String>,
+        /// Matches the name by a regular expression.
+        #[arg(long)]
+        regex_name: Option<String>,
+        /// Matches the classname.
+        #[arg(long)]
+        classname: Option<String>,
+        /// Matches the automation id.
+        #[arg(long)]
+        id: Option<String>,
+        /// Matches the control type, such as `Button`.
+        #[arg(long = "type")]
+        control_type: Option<ControlType>,
+        /// The max depth to search.
+        #[arg(short, long, default_value_t = 7)]
+        depth: u32,
+        /// The max count of elements to find.
+        #[arg(short, long)]
+        limit: Option<usize>,
+        /// The timeout to wait for the elements in milliseconds.
+        #[arg(short, long, default_value_t = 0)]
+        timeout: u64,
+        #[command(flatten)]
+        output: Output
+    },
+    /// Prints the element at the screen point `x,y`.
+    At {
+        /// The screen point, such as `100,200` or `-100,200` on a monitor left of the primary one.
+        #[arg(value_parser = parse_point, allow_hyphen_values = true)]
+        point: Point,
+        #[command(flatten)]
+        output: Output
+    },
+    /// Prints the element which has the keyboard focus.
+    Focused {
+        #[command(flatten)]
+        output: Output
+    },
+    /// Prints the focused element whenever the focus changes, until it is interrupted.
+    Watch {
+        /// The interval to check the focus in milliseconds.
+        #[arg(short, long, default_value_t = 200)]
+        interval: u64,
+        #[command(flatten)]
+        output: Output
+    }
+}
+
+#[derive(Debug, Args)]
+struct Output {
+    /// The output format.
+    #[arg(short, long, value_enum, default_value_t = Format::Text)]
+    format: Format,
+    /// The properties to print, separated by commas, such as `type,name,rect`.
+    #[arg(short, long, value_delimiter = ',', default_values_t = Property::DEFAULT)]
+    props: Vec<Property>
+}
+
+impl Output {
+    fn print_tree(&self, snapshot: &ElementSnapshot) {
+        println!("{}", format_tree(snapshot, &self.props, self.format));
+    }
+
+    fn print_list(&self, snapshots: &[ElementSnapshot]) {
+        let text = format_list(snapshots, &self.props, self.format);
+        if !text.is_empty() {
+            println!("{}", text);
+        }
+    }
+
+    fn print_element(&self, element: &UIElement) -> Result<()> {
+        let snapshot = ElementSnapshot::from_element(element)?;
+        self.print_tree(&snapshot);
+        Ok(())
+    }
+}
+
+/// Parses the screen point as `x,y`.
+fn parse_point(text: &str) -> std::result::Result<Point, String> {
+    let (x, y) = text.split_once(',').ok_or_else(|| format!("expected 'x,y', but got '{}'", text))?;
+    let x: i32 = x.trim().parse().map_err(|_| format!("invalid x '{}'", x))?;
+    let y: i32 = y.trim().parse().map_err(|_| format!("invalid y '{}'", y))?;
+    Ok(Point::new(x, y))
+}
+
+fn run(command: Command) -> Result<()> {
+    let automation = UIAutomation::new()?;
+
+    match command {
+        Command::Tree { selector, depth, output } => {
+            let root = match selector {
+                Some(selector) => selector.parse::<Selector>()?.find(&automation)?,
+                None => automation.get_root_element()?,
+            };
+            let walker = automation.get_control_view_walker()?;
+            let snapshot = ElementSnapshot::capture(&root, &walker, depth)?;
+            output.print_tree(&snapshot);
+        },
+        Command::Find { selector, name, regex_name, classname, id, control_type, depth, limit, timeout, output } => {
+            let elements = if let Some(selector) = selector {
+                vec![selector.parse::<Selector>()?.find(&automation)?]
+            } else {
+                let mut matcher = automation.create_matcher().depth(depth).timeout(timeout);
+                if let Some(name) = name {
+                    matcher = matcher.name(name);
+                }
+                if let Some(regex) = regex_name {
+                    let regex = Regex::new(&regex).map_err(|e| Error::new(ERR_FORMAT, &e.to_string()))?;
+                    matcher = matcher.regex_name(regex);
+                }
+                if let Some(classname) = classname {
+                    matcher = matcher.classname(classname);
+                }
+                if let Some(id) = id {
+                    matcher = matcher.automation_id(id);
+                }
+                if let Some(control_type) = control_type {
+                    matcher = matcher.control_type(control_type);
+                }
+                if let Some(limit) = limit {
+                    matcher = matcher.limit(limit);
+                }
+                matcher.find_all()?
+            };
+
+            let snapshots = elements.iter().map(ElementSnapshot::from_element).collect::<Result<Vec<_>>>()?;
+            output.print_list(&snapshots);
+        },
+        Command::At { point, output } => {
+            output.print_element(&automation.element_from_point(point)?)?;
+        },
+        Command::Focused { output } => {
+            output.print_element(&automation.get_focused_element()?)?;
+        },
+        Command::Watch { interval, output } => {
+            let mut last: Option<Vec<i32>> = None;
+            loop {
+                // the focused element may disappear at any time, so the failures are skipped.
+                if let Ok(element) = automation.get_focused_element() {
+                    let runtime_id = element.get_runtime_id().ok();
+                    if runtime_id != last {
+                        if let Ok(snapshot) = ElementSnapshot::from_element(&element) {
+                            output.print_tree(&snapshot);
+                        }
+                        last = runtime_id;
+                    }
+                }
+                sleep(Duration::from_millis(interval));
+            }
+        },
+    }
+
+    Ok(())
+}
+
+fn main() {
+    let cli = Cli::parse();
+    if let Err(e) = run(cli.command) {
+        eprintln!("error: {}", e);
+        std::process::exit(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::CommandFactory;
+    use clap::Parser;
+    use uia_inspect::format::Format;
+    use uia_inspect::format::Property;
+    use uiautomation::controls::ControlType;
+    use uiautomation::types::Point;
+
+    use crate::Cli;
+    use crate::Command;
+    use crate::parse_point;
+
+    #[test]
+    fn test_cli() {
+        Cli::command().debug_assert();
+
+        let cli = Cli::try_parse_from(["uia-inspect", "tree", "-d", "2", "-f", "json", "-p", "type,name,rect"]).unwrap();
+        let Command::Tree { selector, depth, output } = cli.command else { panic!("expected tree") };
+        assert_eq!((selector, depth, output.format), (None, 2, Format::Json));
+        assert_eq!(output.props, vec![Property::ControlType, Property::Name, Property::Rect]);
+
+        let cli = Cli::try_parse_from(["uia-inspect", "find", "--type", "button", "--name", "OK"]).unwrap();
+        let Command::Find { control_type, name, output, .. } = cli.command else { panic!("expected find") };
+        assert_eq!((control_type, name), (Some(ControlType::Button), Some(String::from("OK"))));
+        assert_eq!(output.props, Property::DEFAULT.to_vec());
+
+        assert!(Cli::try_parse_from(["uia-inspect", "find", "--type", "Widget"]).is_err());
+
+        let cli = Cli::try_parse_from(["uia-inspect", "at", "-100,200"]).unwrap();
+        let Command::At { point, .. } = cli.command else { panic!("expected at") };
+        assert_eq!(point, Point::new(-100, 200));
+        assert!(Cli::try_parse_from(["uia-inspect", "at", "100"]).is_err());
+        assert!(Cli::try_parse_from(["uia-inspect", "focused", "-p", "color"]).is_err());
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(parse_point("100,-20"), Ok(Point::new(100, -20)));
+        assert_eq!(parse_point(" 1 , 2 "), Ok(Point::new(1, 2)));
+        assert!(parse_point("100").is_err());
+        assert!(parse_point("a,1").is_err());
+    }
+}
@@ -0,0 +1,151 @@
+use ratatui::crossterm::event;
+use ratatui::crossterm::event::Event;
+use uia_inspect::browser::Action;
+use uia_inspect::browser::Backend;
+use uia_inspect::browser::Browser;
+use uia_inspect::format::Property;
+use uia_inspect::format::Value;
+use uiautomation::Result;
+use uiautomation::UIAutomation;
+use uiautomation::UIElement;
+use uiautomation::UITreeWalker;
+use uiautomation::clipboard;
+use uiautomation::patterns::UIExpandCollapsePattern;
+use uiautomation::patterns::UIInvokePattern;
+use uiautomation::patterns::UIPatternType;
+use uiautomation::patterns::UITogglePattern;
+use uiautomation::patterns::UIValuePattern;
+use uiautomation::selectors::Selector;
+use uiautomation::snapshots::ElementSnapshot;
+use uiautomation::trees::ElementNavigator;
+use uiautomation::trees::TreeNavigator;
+use uiautomation::types::ExpandCollapseState;
+
+/// Browses the control view of the UI Automation tree.
+struct UIBackend {
+    automation: UIAutomation,
+    walker: UITreeWalker
+}
+
+impl UIBackend {
+    fn new() -> Result<Self> {
+        let automation = UIAutomation::new()?;
+        let walker = automation.get_control_view_walker()?;
+        Ok(Self {
+            automation,
+            walker
+        })
+    }
+}
+
+impl TreeNavigator for UIBackend {
+    type Node = UIElement;
+
+    fn get_parent(&self, node: &UIElement) -> Result<Option<UIElement>> {
+        ElementNavigator::new(&self.walker).get_parent(node)
+    }
+
+    fn get_first_child(&self, node: &UIElement) -> Result<Option<UIElement>> {
+        ElementNavigator::new(&self.walker).get_first_child(node)
+    }
+
+    fn get_next_sibling(&self, node: &UIElement) -> Result<Option<UIElement>> {
+        ElementNavigator::new(&self.walker).get_next_sibling(node)
+    }
+}
+
+impl Backend for UIBackend {
+    fn get_root(&self) -> Result<UIElement> {
+        self.automation.get_root_element()
+    }
+
+    fn get_snapshot(&self, node: &UIElement) -> Result<ElementSnapshot> {
+        ElementSnapshot::from_element(node)
+    }
+
+    fn get_properties(&self, node: &UIElement) -> Result<Vec<(String, String)>> {
+        let mut properties = Property::get_texts(&ElementSnapshot::from_element(node)?);
+        let extras = [
+            ("localized_type", node.get_localized_control_type().map(Value::Text)),
+            ("help_text", node.get_help_text().map(Value::Text)),
+            ("accelerator_key", node.get_accelerator_key().map(Value::Text)),
+            ("access_key", node.get_access_key().map(Value::Text)),
+            ("item_type", node.get_item_type().map(Value::Text)),
+            ("item_status", node.get_item_status().map(Value::Text)),
+            ("keyboard_focusable", node.is_keyboard_focusable().map(Value::Bool)),
+            ("keyboard_focus", node.has_keyboard_focus().map(Value::Bool)),
+            ("control_element", node.is_control_element().map(Value::Bool)),
+            ("content_element", node.is_content_element().map(Value::Bool)),
+            ("password", node.is_password().map(Value::Bool)),
+            ("required_for_form", node.is_required_for_form().map(Value::Bool)),
+            ("orientation", node.get_orientation().map(|o| Value::Raw(format!("{:?}", o)))),
+            ("culture", node.get_culture().map(|c| Value::Number(c as i64))),
+            ("handle", node.get_native_window_handle().map(|h| Value::Raw(h.to_string()))),
+            ("provider", node.get_provider_description().map(Value::Text)),
+        ];
+        // some providers fail to read the properties they do not support.
+        properties.extend(extras.into_iter().map(|(key, value)| (String::from(key), value.unwrap_or(Value::Null).to_text())));
+        Ok(properties)
+    }
+
+    fn get_patterns(&self, node: &UIElement) -> Result<Vec<UIPatternType>> {
+        let mut patterns: Vec<UIPatternType> = Vec::new();
+        for pattern_type in (UIPatternType::Invoke as i32..=UIPatternType::CustomNavigation as i32).filter_map(|id| UIPatternType::try_from(id).ok()) {
+            if node.is_pattern_available(pattern_type)? {
+                patterns.push(pattern_type);
+            }
+        }
+        Ok(patterns)
+    }
+
+    fn perform(&self, node: &UIElement, action: &Action) -> Result<()> {
+        match action {
+            Action::Invoke => node.get_pattern::<UIInvokePattern>()?.invoke(),
+            Action::Toggle => node.get_pattern::<UITogglePattern>()?.toggle(),
+            Action::ExpandCollapse => {
+                let pattern = node.get_pattern::<UIExpandCollapsePattern>()?;
+                if pattern.get_state()? == ExpandCollapseState::Collapsed {
+                    pattern.expand()
+                } else {
+                    pattern.collapse()
+                }
+            },
+            Action::SetValue(value) => node.get_pattern::<UIValuePattern>()?.set_value(value),
+        }
+    }
+
+    fn get_selector(&self, node: &UIElement) -> Result<String> {
+        Ok(Selector::from_element(&self.automation, node)?.to_string())
+    }
+
+    fn copy_text(&self, text: &str) -> Result<()> {
+        clipboard::set_text(text)
+    }
+}
+
+fn main() {
+    let mut browser = match UIBackend::new().and_then(Browser::new) {
+        Ok(browser) => browser,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    let mut terminal = ratatui::init();
+    let ret = (|| -> std::io::Result<()> {
+        while browser.is_running() {
+            terminal.draw(|frame| browser.render(frame))?;
+            if let Event::Key(key) = event::read()? {
+                browser.handle_key(key);
+            }
+        }
+        Ok(())
+    })();
+    ratatui::restore();
+
+    if let Err(e) = ret {
+        eprintln!("error: {}", e);
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,483 @@
+use std::ptr;
+use std::thread::sleep;
+use std::time::Duration;
+
+use windows::Win32::Foundation::GlobalFree;
+use windows::Win32::Foundation::HANDLE;
+use windows::Win32::Foundation::HGLOBAL;
+use windows::Win32::Foundation::HWND;
+use windows::Win32::System::DataExchange::CloseClipboard;
+use windows::Win32::System::DataExchange::EmptyClipboard;
+use windows::Win32::System::DataExchange::EnumClipboardFormats;
+use windows::Win32::System::DataExchange::GetClipboardData;
+use windows::Win32::System::DataExchange::IsClipboardFormatAvailable;
+use windows::Win32::System::DataExchange::OpenClipboard;
+use windows::Win32::System::DataExchange::RegisterClipboardFormatW;
+use windows::Win32::System::DataExchange::SetClipboardData;
+use windows::Win32::System::Memory::GMEM_MOVEABLE;
+use windows::Win32::System::Memory::GlobalAlloc;
+use windows::Win32::System::Memory::GlobalLock;
+use windows::Win32::System::Memory::GlobalSize;
+use windows::Win32::System::Memory::GlobalUnlock;
+use windows::Win32::System::Ole::CF_HDROP;
+use windows::Win32::System::Ole::CF_UNICODETEXT;
+use windows::core::w;
+
+use super::errors::ERR_FORMAT;
+use super::errors::ERR_NOTFOUND;
+use super::errors::Error;
+use super::errors::Result;
+
+/// The times to retry when the clipboard is opened by another window.
+const OPEN_RETRIES: u32 = 10;
+/// The size of the `DROPFILES` header.
+const DROPFILES_SIZE: usize = 20;
+
+/// Keeps the clipboard open, and closes it when dropped.
+struct ClipboardGuard;
+
+impl ClipboardGuard {
+    fn open() -> Result<Self> {
+        let mut retries = 0;
+        loop {
+            match unsafe { OpenClipboard(HWND::default()) } {
+                Ok(_) => return Ok(Self),
+                Err(_) if retries < OPEN_RETRIES => {
+                    retries += 1;
+                    sleep(Duration::from_millis(10));
+                },
+                Err(e) => return Err(e.into()),
+            }
+        }
+    }
+
+    fn get_data(&self, format: u32) -> Result<Vec<u8>> {
+        let handle = unsafe { GetClipboardData(format)? };
+        let hglobal = HGLOBAL(handle.0 as _);
+        unsafe {
+            let data = GlobalLock(hglobal);
+            if data.is_null() {
+                return Err(Error::last_os_error());
+            }
+
+            let size = GlobalSize(hglobal);
+            let bytes = std::slice::from_raw_parts(data as *const u8, size).to_vec();
+            let _ = GlobalUnlock(hglobal);
+
+            Ok(bytes)
+        }
+    }
+
+    fn set_data(&self, format: u32, bytes: &[u8]) -> Result<()> {
+        unsafe {
+            let hglobal = GlobalAlloc(GMEM_MOVEABLE, bytes.len().max(1))?;
+            let data = GlobalLock(hglobal);
+            if data.is_null() {
+                let _ = GlobalFree(hglobal);
+                return Err(Error::last_os_error());
+            }
+            ptr::copy_nonoverlapping(bytes.as_ptr(), data as *mut u8, bytes.len());
+            let _ = GlobalUnlock(hglobal);
+
+            // The system owns the memory after `SetClipboardData` succeeds.
+            if let Err(e) = SetClipboardData(format, HANDLE(hglobal.0 as _)) {
+                let _ = GlobalFree(hglobal);
+                return Err(e.into());
+            }
+        }
+
+        Ok(())
+    }
+
+    fn empty(&self) -> Result<()> {
+        unsafe { EmptyClipboard()? };
+        Ok(())
+    }
+}
+
+impl Drop for ClipboardGuard {
+    fn drop(&mut self) {
+        let _ = unsafe { CloseClipboard() };
+    }
+}
+
+/// Retrieves the identifier of the `HTML Format` clipboard format.
+pub fn get_html_format() -> u32 {
+    unsafe { RegisterClipboardFormatW(w!("HTML Format")) }
+}
+
+/// Returns `true` if the clipboard contains data in `format`.
+pub fn has_format(format: u32) -> bool {
+    unsafe { IsClipboardFormatAvailable(format) }.is_ok()
+}
+
+/// Removes all the data from the clipboard.
+pub fn clear() -> Result<()> {
+    ClipboardGuard::open()?.empty()
+}
+
+/// Retrieves the unicode text from the clipboard.
+pub fn get_text() -> Result<String> {
+    let clipboard = ClipboardGuard::open()?;
+    let bytes = clipboard.get_data(CF_UNICODETEXT.0 as _)?;
+    Ok(decode_wide_text(&bytes))
+}
+
+/// Replaces the clipboard contents with the unicode `text`.
+///
+/// # Examples
+/// ```
+/// use uiautomation::clipboard;
+///
+/// clipboard::set_text("Hello, clipboard!").unwrap();
+/// assert_eq!(clipboard::get_text().unwrap(), "Hello, clipboard!");
+/// ```
+pub fn set_text(text: &str) -> Result<()> {
+    let clipboard = ClipboardGuard::open()?;
+    clipboard.empty()?;
+    clipboard.set_data(CF_UNICODETEXT.0 as _, &encode_wide_text(text))
+}
+
+/// Retrieves the HTML fragment from the clipboard, which is copied from browsers or office applications.
+pub fn get_html() -> Result<HtmlFragment> {
+    let clipboard = ClipboardGuard::open()?;
+    let bytes = clipboard.get_data(get_html_format())?;
+    let len = bytes.iter().position(|b| *b == 0).unwrap_or(bytes.len());
+    parse_cf_html(&String::from_utf8_lossy(&bytes[..len]))
+}
+
+/// Replaces the clipboard contents with the HTML `fragment`, and `text` as the plain text alternative.
+pub fn set_html(fragment: &str, text: &str) -> Result<()> {
+    let clipboard = ClipboardGuard::open()?;
+    clipboard.empty()?;
+
+    let mut html = build_cf_html(fragment, None).into_bytes();
+    html.push(0);
+    clipboard.set_data(get_html_format(), &html)?;
+    clipboard.set_data(CF_UNICODETEXT.0 as _, &encode_wide_text(text))
+}
+
+/// Retrieves the file list from the clipboard, which is copied from the Explorer.
+pub fn get_files() -> Result<Vec<String>> {
+    let clipboard = ClipboardGuard::open()?;
+    let bytes = clipboard.get_data(CF_HDROP.0 as _)?;
+    parse_drop_files(&bytes)
+}
+
+/// Replaces the clipboard contents with the file list of `paths`, which can be pasted in the Explorer.
+pub fn set_files<S: AsRef<str>>(paths: &[S]) -> Result<()> {
+    let clipboard = ClipboardGuard::open()?;
+    clipboard.empty()?;
+    clipboard.set_data(CF_HDROP.0 as _, &build_drop_files(paths))
+}
+
+/// The saved clipboard contents, which can be restored later.
+#[derive(Debug, Clone, Default)]
+pub struct ClipboardSnapshot {
+    items: Vec<(u32, Vec<u8>)>
+}
+
+impl ClipboardSnapshot {
+    /// Saves the current clipboard contents.
+    ///
+    /// Only the formats stored in global memory are saved. Bitmap, metafile, palette, GDI and owner-display formats are skipped.
+    pub fn save() -> Result<Self> {
+        let clipboard = ClipboardGuard::open()?;
+
+        let mut items: Vec<(u32, Vec<u8>)> = Vec::new();
+        let mut format = unsafe { EnumClipboardFormats(0) };
+        while format != 0 {
+            if is_global_format(format) {
+                if let Ok(bytes) = clipboard.get_data(format) {
+                    items.push((format, bytes));
+                }
+            }
+            format = unsafe { EnumClipboardFormats(format) };
+        }
+
+        Ok(Self { items })
+    }
+
+    /// Retrieves the saved formats.
+    pub fn get_formats(&self) -> Vec<u32> {
+        self.items.iter().map(|(format, _)| *format).collect()
+    }
+
+    /// Returns `true` if there is no saved data.
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Replaces the clipboard contents with the saved data.
+    pub fn restore(&self) -> Result<()> {
+        let clipboard = ClipboardGuard::open()?;
+        clipboard.empty()?;
+        for (format, bytes) in &self.items {
+            clipboard.set_data(*format, bytes)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Runs `action` and restores the prior clipboard contents afterwards, even if `action` fails.
+///
+/// # Examples
+/// ```
+/// use uiautomation::clipboard;
+///
+/// clipboard::preserve(|| {
+///     clipboard::set_text("temporary text")
+/// }).unwrap();
+/// ```
+pub fn preserve<T, F>(action: F) -> Result<T> where F: FnOnce() -> Result<T> {
+    let snapshot = ClipboardSnapshot::save()?;
+    let ret = action();
+    snapshot.restore()?;
+    ret
+}
+
+/// Returns `true` if the data of `format` is stored in global memory, which can be copied as bytes.
+fn is_global_format(format: u32) -> bool {
+    const CF_BITMAP: u32 = 2;
+    const CF_METAFILEPICT: u32 = 3;
+    const CF_PALETTE: u32 = 9;
+    const CF_ENHMETAFILE: u32 = 14;
+
+    !matches!(format, CF_BITMAP | CF_METAFILEPICT | CF_PALETTE | CF_ENHMETAFILE | 0x0080..=0x008F | 0x0300..=0x03FF)
+}
+
+fn encode_wide_text(text: &str) -> Vec<u8> {
+    text.encode_utf16().chain(std::iter::once(0)).flat_map(|c| c.to_le_bytes()).collect()
+}
+
+fn decode_wide_text(bytes: &[u8]) -> String {
+    let chars: Vec<u16> = bytes.chunks_exact(2).map(|c| u16::from_le_bytes([c[0], c[1]])).take_while(|c| *c != 0).collect();
+    String::from_utf16_lossy(&chars)
+}
+
+/// The HTML data in the clipboard.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct HtmlFragment {
+    /// The whole HTML document.
+    pub html: String,
+    /// The copied fragment in the document.
+    pub fragment: String,
+    /// The URL of the source document.
+    pub source_url: Option<String>
+}
+
+const START_FRAGMENT: &str = "<!--StartFragment-->";
+const END_FRAGMENT: &str = "<!--EndFragment-->";
+
+/// Formats the HTML `fragment` as the `CF_HTML` clipboard format, whose header describes the byte offsets of the HTML and the fragment.
+///
+/// # Examples
+/// ```
+/// use uiautomation::clipboard::build_cf_html;
+/// use uiautomation::clipboard::parse_cf_html;
+///
+/// let data = build_cf_html("<b>bold</b>", None);
+/// assert!(data.starts_with("Version:0.9\r\n"));
+/// assert_eq!(parse_cf_html(&data).unwrap().fragment, "<b>bold</b>");
+/// ```
+pub fn build_cf_html(fragment: &str, source_url: Option<&str>) -> String {
+    let source = match source_url {
+        Some(url) => format!("SourceURL:{}\r\n", url),
+        None => String::new(),
+    };
+
+    // Every offset is formatted as 10 digits, so the header length is fixed.
+    let header_len = "Version:0.9\r\nStartHTML:0000000000\r\nEndHTML:0000000000\r\nStartFragment:0000000000\r\nEndFragment:0000000000\r\n".len() + source.len();
+    let prefix = format!("<html>\r\n<body>\r\n{}", START_FRAGMENT);
+    let suffix = format!("{}\r\n</body>\r\n</html>", END_FRAGMENT);
+
+    let start_html = header_len;
+    let start_fragment = start_html + prefix.len();
+    let end_fragment = start_fragment + fragment.len();
+    let end_html = end_fragment + suffix.len();
+
+    format!("Version:0.9\r\nStartHTML:{:010}\r\nEndHTML:{:010}\r\nStartFragment:{:010}\r\nEndFragment:{:010}\r\n{}{}{}{}",
+        start_html, end_html, start_fragment, end_fragment, source, prefix, fragment, suffix)
+}
+
+/// Parses the `CF_HTML` clipboard format.
+///
+/// The offsets in the header are preferred. The `<!--StartFragment-->` and `<!--EndFragment-->` comments are used when the offsets are missing or invalid.
+pub fn parse_cf_html(data: &str) -> Result<HtmlFragment> {
+    let mut start_html: Option<usize> = None;
+    let mut end_html: Option<usize> = None;
+    let mut start_fragment: Option<usize> = None;
+    let mut end_fragment: Option<usize> = None;
+    let mut source_url: Option<String> = None;
+    let mut header_end = 0;
+
+    for line in data.split_inclusive('\n') {
+        let Some((key, value)) = line.split_once(':') else {
+            break;
+        };
+        if key.is_empty() || key.contains('<') || !key.chars().all(|c| c.is_ascii_alphanumeric()) {
+            break;
+        }
+
+        let value = value.trim_end_matches(['\r', '\n']);
+        let offset = value.trim().parse::<usize>().ok();
+        match key {
+            "StartHTML" => start_html = offset,
+            "EndHTML" => end_html = offset,
+            "StartFragment" => start_fragment = offset,
+            "EndFragment" => end_fragment = offset,
+            "SourceURL" => source_url = Some(value.to_string()),
+            _ => (),
+        }
+        header_end += line.len();
+    }
+
+    if header_end == 0 {
+        return Err(Error::new(ERR_FORMAT, "invalid CF_HTML header"));
+    }
+
+    let slice = |start: Option<usize>, end: Option<usize>| -> Option<&str> {
+        match (start, end) {
+            (Some(start), Some(end)) if start <= end => data.get(start..end),
+            _ => None,
+        }
+    };
+
+    let html = slice(start_html, end_html).unwrap_or(&data[header_end..]);
+    let fragment = match slice(start_fragment, end_fragment) {
+        Some(fragment) => fragment,
+        None => {
+            let start = html.find(START_FRAGMENT).map(|i| i + START_FRAGMENT.len());
+            let end = html.rfind(END_FRAGMENT);
+            match (start, end) {
+                (Some(start), Some(end)) if start <= end => &html[start..end],
+                _ => return Err(Error::new(ERR_NOTFOUND, "can not find the HTML fragment")),
+            }
+        }
+    };
+
+    Ok(HtmlFragment {
+        html: html.to_string(),
+        fragment: fragment.to_string(),
+        source_url
+    })
+}
+
+/// Builds the `CF_HDROP` data, which is a `DROPFILES` header followed by the double-null terminated wide file list.
+fn build_drop_files<S: AsRef<str>>(paths: &[S]) -> Vec<u8> {
+    let mut bytes: Vec<u8> = Vec::new();
+    bytes.extend((DROPFILES_SIZE as u32).to_le_bytes());    // pFiles
+    bytes.extend(0i32.to_le_bytes());                       // pt.x
+    bytes.extend(0i32.to_le_bytes());                       // pt.y
+    bytes.extend(0i32.to_le_bytes());                       // fNC
+    bytes.extend(1i32.to_le_bytes());                       // fWide
+
+    for path in paths {
+        bytes.extend(encode_wide_text(path.as_ref()));
+    }
+    if paths.is_empty() {
+        bytes.extend([0, 0]);
+    }
+    bytes.extend([0, 0]);
+
+    bytes
+}
+
+/// Parses the `CF_HDROP` data.
+fn parse_drop_files(bytes: &[u8]) -> Result<Vec<String>> {
+    if bytes.len() < DROPFILES_SIZE {
+        return Err(Error::new(ERR_FORMAT, "invalid DROPFILES data"));
+    }
+
+    let offset = u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as usize;
+    let wide = i32::from_le_bytes([bytes[16], bytes[17], bytes[18], bytes[19]]) != 0;
+    let Some(list) = bytes.get(offset..) else {
+        return Err(Error::new(ERR_FORMAT, "invalid DROPFILES data"));
+    };
+
+    let paths: Vec<String> = if wide {
+        let chars: Vec<u16> = list.chunks_exact(2).map(|c| u16::from_le_bytes([c[0], c[1]])).collect();
+        chars.split(|c| *c == 0).take_while(|path| !path.is_empty()).map(String::from_utf16_lossy).collect()
+    } else {
+        list.split(|b| *b == 0).take_while(|path| !path.is_empty()).map(|path| String::from_utf8_lossy(path).into_owned()).collect()
+    };
+
+    Ok(paths)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::build_cf_html;
+    use super::build_drop_files;
+    use super::decode_wide_text;
+    use super::encode_wide_text;
+    use super::is_global_format;
+    use super::parse_cf_html;
+    use super::parse_drop_files;
+
+    #[test]
+    fn test_build_cf_html() {
+        let data = build_cf_html("<td>中文</td>", Some("https://example.com/"));
+        let offset = |key: &str| -> usize {
+            let line = data.lines().find(|line| line.starts_with(key)).unwrap();
+            line[key.len() + 1..].parse().unwrap()
+        };
+
+        assert_eq!(&data[offset("StartFragment")..offset("EndFragment")], "<td>中文</td>");
+        assert!(data[offset("StartHTML")..].starts_with("<html>"));
+        assert_eq!(offset("EndHTML"), data.len());
+        assert!(data.contains("SourceURL:https://example.com/\r\n"));
+    }
+
+    #[test]
+    fn test_parse_cf_html() {
+        let data = build_cf_html("<b>bold</b> text", Some("about:blank"));
+        let html = parse_cf_html(&data).unwrap();
+        assert_eq!(html.fragment, "<b>bold</b> text");
+        assert_eq!(html.source_url.as_deref(), Some("about:blank"));
+        assert!(html.html.starts_with("<html>") && html.html.ends_with("</html>"));
+    }
+
+    #[test]
+    fn test_parse_cf_html_markers() {
+        let data = "Version:1.0\r\nStartHTML:-1\r\nEndHTML:-1\r\nStartFragment:9999\r\nEndFragment:99999\r\n<html><body><!--StartFragment--><p>x</p><!--EndFragment--></body></html>";
+        let html = parse_cf_html(data).unwrap();
+        assert_eq!(html.fragment, "<p>x</p>");
+        assert!(html.html.starts_with("<html>"));
+
+        assert!(parse_cf_html("<html></html>").is_err());
+        assert!(parse_cf_html("Version:0.9\r\n<html></html>").is_err());
+    }
+
+    #[test]
+    fn test_drop_files() {
+        let paths = ["C:\\a.txt", "D:\\目录\\b.png"];
+        let bytes = build_drop_files(&paths);
+        assert_eq!(&bytes[bytes.len() - 4..], &[0, 0, 0, 0]);
+        assert_eq!(parse_drop_files(&bytes).unwrap(), paths);
+
+        let empty: [&str; 0] = [];
+        assert!(parse_drop_files(&build_drop_files(&empty)).unwrap().is_empty());
+        assert!(parse_drop_files(&[0; 8]).is_err());
+
+        let mut ansi = build_drop_files(&empty)[..20].to_vec();
+        ansi[16] = 0;
+        ansi.extend(b"C:\\x.txt\0C:\\y.txt\0\0");
+        assert_eq!(parse_drop_files(&ansi).unwrap(), ["C:\\x.txt", "C:\\y.txt"]);
+    }
+
+    #[test]
+    fn test_wide_text() {
+        assert_eq!(decode_wide_text(&encode_wide_text("Hello, 世界")), "Hello, 世界");
+        assert_eq!(decode_wide_text(&[b'a', 0, 0, 0, b'b', 0]), "a");
+    }
+
+    #[test]
+    fn test_global_formats() {
+        assert!(is_global_format(13));
+        assert!(is_global_format(0xC0FE));
+        assert!(!is_global_format(2));
+        assert!(!is_global_format(0x0301));
+    }
+}
@@ -0,0 +1,328 @@
+use std::collections::HashSet;
+
+use super::core::UIElement;
+use super::errors::Result;
+use super::patterns::UIItemContainerPattern;
+use super::patterns::UIScrollItemPattern;
+use super::patterns::UIVirtualizedItemPattern;
+
+/// A container whose items may be virtualized, such as a large list or grid.
+pub trait VirtualContainer {
+    /// The item type.
+    type Item: Clone;
+
+    /// Retrieves the item following `start_after`, or the first item when `start_after` is `None`.
+    fn get_next_item(&self, start_after: Option<&Self::Item>) -> Result<Option<Self::Item>>;
+
+    /// Returns `true` if `item` is a virtualized placeholder, which needs to be realized before use.
+    fn is_placeholder(&self, item: &Self::Item) -> bool;
+
+    /// Realizes the placeholder `item`.
+    fn realize(&self, item: &Self::Item) -> Result<()>;
+
+    /// Scrolls `item` into the visible area of the container.
+    fn scroll_into_view(&self, item: &Self::Item) -> Result<()>;
+
+    /// Retrieves the unique identifier of `item`.
+    fn get_runtime_id(&self, item: &Self::Item) -> Result<Vec<i32>>;
+}
+
+impl VirtualContainer for UIItemContainerPattern {
+    type Item = UIElement;
+
+    fn get_next_item(&self, start_after: Option<&UIElement>) -> Result<Option<UIElement>> {
+        UIItemContainerPattern::get_next_item(self, start_after)
+    }
+
+    fn is_placeholder(&self, item: &UIElement) -> bool {
+        item.get_pattern::<UIVirtualizedItemPattern>().is_ok()
+    }
+
+    fn realize(&self, item: &UIElement) -> Result<()> {
+        let pattern: UIVirtualizedItemPattern = item.get_pattern()?;
+        pattern.realize()
+    }
+
+    fn scroll_into_view(&self, item: &UIElement) -> Result<()> {
+        let pattern: UIScrollItemPattern = item.get_pattern()?;
+        pattern.scroll_into_view()
+    }
+
+    fn get_runtime_id(&self, item: &UIElement) -> Result<Vec<i32>> {
+        item.get_runtime_id()
+    }
+}
+
+impl<C: VirtualContainer> VirtualContainer for &C {
+    type Item = C::Item;
+
+    fn get_next_item(&self, start_after: Option<&Self::Item>) -> Result<Option<Self::Item>> {
+        (*self).get_next_item(start_after)
+    }
+
+    fn is_placeholder(&self, item: &Self::Item) -> bool {
+        (*self).is_placeholder(item)
+    }
+
+    fn realize(&self, item: &Self::Item) -> Result<()> {
+        (*self).realize(item)
+    }
+
+    fn scroll_into_view(&self, item: &Self::Item) -> Result<()> {
+        (*self).scroll_into_view(item)
+    }
+
+    fn get_runtime_id(&self, item: &Self::Item) -> Result<Vec<i32>> {
+        (*self).get_runtime_id(item)
+    }
+}
+
+/// An iterator over all the items of a virtualized container, including the items out of view.
+///
+/// The placeholders are realized, or scrolled into view if they can not be realized. The items with the same runtime id are returned only once.
+/// The iteration stops when the container wraps or cycles, returning more seen items in a row than the count of the seen items.
+#[derive(Debug)]
+pub struct VirtualItems<C: VirtualContainer> {
+    container: C,
+    last: Option<C::Item>,
+    last_id: Option<Vec<i32>>,
+    seen: HashSet<Vec<i32>>,
+    duplicates: usize,
+    scroll: bool,
+    limit: usize,
+    count: usize,
+    done: bool
+}
+
+impl<C: VirtualContainer> VirtualItems<C> {
+    /// Creates an iterator over the items of `container`.
+    pub fn new(container: C) -> Self {
+        Self {
+            container,
+            last: None,
+            last_id: None,
+            seen: HashSet::new(),
+            duplicates: 0,
+            scroll: false,
+            limit: usize::MAX,
+            count: 0,
+            done: false
+        }
+    }
+
+    /// Sets whether to scroll every item into view. The default value is `false`.
+    pub fn scroll_into_view(mut self, scroll: bool) -> Self {
+        self.scroll = scroll;
+        self
+    }
+
+    /// Sets the max count of items to return.
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = limit;
+        self
+    }
+
+    fn next_item(&mut self) -> Result<Option<C::Item>> {
+        while self.count < self.limit {
+            let Some(item) = self.container.get_next_item(self.last.as_ref())? else {
+                return Ok(None);
+            };
+
+            let realized = !self.container.is_placeholder(&item) || self.container.realize(&item).is_ok();
+            if self.scroll || !realized {
+                self.container.scroll_into_view(&item)?;
+            }
+
+            let id = self.container.get_runtime_id(&item)?;
+            // The container makes no progress, which may be caused by a broken provider.
+            if self.last_id.as_ref() == Some(&id) {
+                return Ok(None);
+            }
+
+            self.last = Some(item.clone());
+            self.last_id = Some(id.clone());
+            if self.seen.insert(id) {
+                self.duplicates = 0;
+                self.count += 1;
+                return Ok(Some(item));
+            }
+
+            // all the seen items are returned again, so the container is cycling.
+            self.duplicates += 1;
+            if self.duplicates > self.seen.len() {
+                return Ok(None);
+            }
+        }
+
+        Ok(None)
+    }
+}
+
+impl<C: VirtualContainer> Iterator for VirtualItems<C> {
+    type Item = Result<C::Item>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        let next = self.next_item().transpose();
+        if !matches!(next, Some(Ok(_))) {
+            self.done = true;
+        }
+        next
+    }
+}
+
+impl UIElement {
+    /// Iterates over all the items of the container, including the virtualized items out of view.
+    ///
+    /// The element must support `ItemContainerPattern`.
+    ///
+    /// # Examples
+    /// ```
+    /// use uiautomation::UIAutomation;
+    ///
+    /// let automation = UIAutomation::new().unwrap();
+    /// let root = automation.get_root_element().unwrap();
+    /// if let Ok(items) = root.virtual_items() {
+    ///     for item in items.limit(10) {
+    ///         println!("{}", item.unwrap());
+    ///     }
+    /// }
+    /// ```
+    pub fn virtual_items(&self) -> Result<VirtualItems<UIItemContainerPattern>> {
+        let pattern: UIItemContainerPattern = self.get_pattern()?;
+        Ok(VirtualItems::new(pattern))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+    use std::cell::RefCell;
+
+    use crate::errors::ERR_NOTFOUND;
+    use crate::errors::Error;
+    use crate::errors::Result;
+
+    use super::VirtualContainer;
+    use super::VirtualItems;
+
+    /// A fake virtualized list, whose items are the positions in `ids`. Only the first `visible` items are realized.
+    struct FakeList {
+        ids: Vec<i32>,
+        realized: RefCell<Vec<bool>>,
+        realizable: bool,
+        scrolled: RefCell<Vec<usize>>,
+        stuck: Cell<bool>,
+        wrapping: bool
+    }
+
+    impl FakeList {
+        fn new(ids: &[i32], visible: usize) -> Self {
+            Self {
+                ids: ids.to_vec(),
+                realized: RefCell::new((0..ids.len()).map(|i| i < visible).collect()),
+                realizable: true,
+                scrolled: RefCell::new(Vec::new()),
+                stuck: Cell::new(false),
+                wrapping: false
+            }
+        }
+
+        fn get_ids(&self) -> Vec<i32> {
+            VirtualItems::new(self).map(|item| self.ids[item.unwrap()]).collect()
+        }
+    }
+
+    impl VirtualContainer for FakeList {
+        type Item = usize;
+
+        fn get_next_item(&self, start_after: Option<&usize>) -> Result<Option<usize>> {
+            let index = match start_after {
+                Some(index) if self.stuck.get() => *index,
+                Some(index) if self.wrapping => (index + 1) % self.ids.len(),
+                Some(index) => index + 1,
+                None => 0,
+            };
+            Ok(if index < self.ids.len() { Some(index) } else { None })
+        }
+
+        fn is_placeholder(&self, item: &usize) -> bool {
+            !self.realized.borrow()[*item]
+        }
+
+        fn realize(&self, item: &usize) -> Result<()> {
+            if self.realizable {
+                self.realized.borrow_mut()[*item] = true;
+                Ok(())
+            } else {
+                Err(Error::new(ERR_NOTFOUND, "not realizable"))
+            }
+        }
+
+        fn scroll_into_view(&self, item: &usize) -> Result<()> {
+            self.scrolled.borrow_mut().push(*item);
+            self.realized.borrow_mut()[*item] = true;
+            Ok(())
+        }
+
+        fn get_runtime_id(&self, item: &usize) -> Result<Vec<i32>> {
+            Ok(vec![42, self.ids[*item]])
+        }
+    }
+
+    #[test]
+    fn test_realize_all() {
+        let list = FakeList::new(&[1, 2, 3, 4, 5], 2);
+        assert_eq!(list.get_ids(), vec![1, 2, 3, 4, 5]);
+        assert!(list.realized.borrow().iter().all(|r| *r));
+        assert!(list.scrolled.borrow().is_empty());
+    }
+
+    #[test]
+    fn test_scroll_fallback() {
+        let mut list = FakeList::new(&[1, 2, 3], 1);
+        list.realizable = false;
+        assert_eq!(list.get_ids(), vec![1, 2, 3]);
+        assert_eq!(*list.scrolled.borrow(), vec![1, 2]);
+
+        let list = FakeList::new(&[1, 2], 2);
+        assert_eq!(VirtualItems::new(&list).scroll_into_view(true).count(), 2);
+        assert_eq!(*list.scrolled.borrow(), vec![0, 1]);
+    }
+
+    #[test]
+    fn test_dedup() {
+        let list = FakeList::new(&[1, 2, 3, 1, 2, 4], 3);
+        assert_eq!(list.get_ids(), vec![1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn test_no_progress() {
+        let list = FakeList::new(&[1, 2, 3], 3);
+        list.stuck.set(true);
+        assert_eq!(list.get_ids(), vec![1]);
+    }
+
+    #[test]
+    fn test_wrapping() {
+        let mut list = FakeList::new(&[1, 2, 3], 3);
+        list.wrapping = true;
+        assert_eq!(list.get_ids(), vec![1, 2, 3]);
+        assert_eq!(VirtualItems::new(&list).limit(5).count(), 3);
+
+        // a cycle after some duplicates also stops.
+        let mut list = FakeList::new(&[1, 2, 1, 3], 4);
+        list.wrapping = true;
+        assert_eq!(list.get_ids(), vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_limit() {
+        let list = FakeList::new(&[1, 2, 3, 4], 4);
+        let items: Vec<usize> = VirtualItems::new(&list).limit(3).map(|i| i.unwrap()).collect();
+        assert_eq!(items, vec![0, 1, 2]);
+    }
+}
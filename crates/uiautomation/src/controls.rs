@@ -1,4 +1,5 @@
 use std::fmt::Display;
+use std::str::FromStr;
 
 use uiautomation_derive::*;
 use windows::Win32::UI::WindowsAndMessaging::SetForegroundWindow;
@@ -7,6 +8,7 @@ use super::actions::*;
 use super::Error;
 use super::Result;
 use super::UIElement;
+use super::errors::ERR_FORMAT;
 use super::errors::ERR_TYPE;
 use super::patterns::*;
 
@@ -136,6 +138,18 @@ impl Into<windows::Win32::UI::Accessibility::UIA_CONTROLTYPE_ID> for ControlType
     }
 }
 
+impl FromStr for ControlType {
+    type Err = Error;
+
+    /// Parses the control type by its name, such as `Button` or `ListItem`. The name is case-insensitive.
+    fn from_str(s: &str) -> Result<Self> {
+        (ControlType::Button as i32..=ControlType::AppBar as i32)
+            .filter_map(|id| ControlType::try_from(id).ok())
+            .find(|t| format!("{:?}", t).eq_ignore_ascii_case(s))
+            .ok_or_else(|| Error::new(ERR_FORMAT, &format!("unknown control type '{}'", s)))
+    }
+}
+
 /// `Control` is the trait for ui element control.
 pub trait Control {
     /// Defines the control type id.
@@ -2030,4 +2044,20 @@ impl Display for WindowControl {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "Window({})", self.control.get_name().unwrap_or_default())
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::errors::ERR_FORMAT;
+
+    use super::ControlType;
+
+    #[test]
+    fn test_parse_control_type() {
+        assert_eq!("ListItem".parse::<ControlType>(), Ok(ControlType::ListItem));
+        assert_eq!("button".parse::<ControlType>(), Ok(ControlType::Button));
+        assert_eq!("AppBar".parse::<ControlType>(), Ok(ControlType::AppBar));
+        assert_eq!("Widget".parse::<ControlType>().unwrap_err().code(), ERR_FORMAT);
+        assert!("".parse::<ControlType>().is_err());
+    }
+}
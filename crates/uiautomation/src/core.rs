@@ -1,9 +1,7 @@
+use std::collections::HashSet;
 use std::fmt::Debug;
 use std::fmt::Display;
-use std::thread::sleep;
-use std::time::Duration;
 
-use chrono::Local;
 use windows::core::Param;
 use windows::Win32::System::Com::CLSCTX_ALL;
 use windows::Win32::System::Com::COINIT_MULTITHREADED;
@@ -28,7 +26,24 @@ use windows::core::Interface;
 use crate::controls::ControlType;
 use crate::filters::FnFilter;
 use crate::inputs::Mouse;
+use crate::locators::UILocator;
+use crate::names::NameDictionary;
 use crate::patterns::UIPatternType;
+use crate::processes::find_processes;
+use crate::processes::get_process_windows;
+use crate::timers::CancellationToken;
+use crate::timers::RetrySchedule;
+use crate::timers::retry;
+use crate::trees::Ancestors;
+use crate::trees::Descendants;
+use crate::trees::ElementNavigator;
+use crate::trees::TraversalOrder;
+#[cfg(feature = "async")]
+use crate::timers::AsyncTimer;
+#[cfg(feature = "async")]
+use crate::timers::DefaultTimer;
+#[cfg(feature = "async")]
+use crate::timers::poll_until;
 use crate::types::ElementMode;
 use crate::types::OrientationType;
 use crate::types::PropertyConditionFlags;
@@ -36,16 +51,32 @@ use crate::types::TreeScope;
 use crate::types::UIProperty;
 use crate::variants::SafeArray;
 
+use super::filters::AutomationIdFilter;
 use super::filters::ClassNameFilter;
+use super::filters::EnabledFilter;
+use super::filters::FocusableFilter;
+use super::filters::FrameworkIdFilter;
+use super::filters::FuzzyNameFilter;
+use super::filters::HelpTextFilter;
+use super::filters::LocalizedNameFilter;
 use super::filters::MatcherFilter;
 use super::filters::ControlTypeFilter;
 use super::filters::NameFilter;
+use super::filters::OffscreenFilter;
+use super::filters::PatternFilter;
+use super::filters::ProcessIdFilter;
+use super::filters::PropertyFilter;
+use super::filters::Regex;
+use super::filters::RegexClassNameFilter;
+use super::filters::RegexNameFilter;
 use super::errors::ERR_NOTFOUND;
 use super::errors::ERR_TIMEOUT;
 use super::errors::Error;
 use super::errors::Result;
+use super::highlights::Highlighter;
 use super::inputs::Keyboard;
 use super::patterns::UIPattern;
+use super::types::Color;
 use super::types::Handle;
 use super::types::Rect;
 use super::types::Point;
@@ -252,6 +283,16 @@ impl UIAutomation {
         UIMatcher::new(self.clone())
     }
 
+    /// Retrieves the top-level elements of the running processes whose executable name is `process_name`, such as `notepad` or `notepad.exe`.
+    pub fn attach(&self, process_name: &str) -> Result<Vec<UIElement>> {
+        let pids: HashSet<u32> = find_processes(process_name)?.iter().map(|p| p.process_id).collect();
+        if pids.is_empty() {
+            return Err(Error::new(ERR_NOTFOUND, "can not find process"));
+        }
+
+        get_process_windows(self, &pids)
+    }
+
     /// Retrieves a predefined condition that selects all elements.
     pub fn create_true_condition(&self) -> Result<UICondition> {
         let condition = unsafe {
@@ -957,6 +998,20 @@ impl UIElement {
         T::try_from(pattern)
     }
 
+    /// Checks whether the element supports the control pattern of `pattern_type`.
+    pub fn is_pattern_available(&self, pattern_type: UIPatternType) -> Result<bool> {
+        let pattern = unsafe {
+            self.element.GetCurrentPattern(pattern_type.into())
+        };
+
+        match pattern {
+            Ok(_) => Ok(true),
+            // A null pattern is returned when the pattern is not supported.
+            Err(e) if e.code().is_ok() => Ok(false),
+            Err(e) => Err(e.into()),
+        }
+    }
+
     /// Retrieves a point on the element that can be clicked.
     pub fn get_clickable_point(&self) -> Result<Option<Point>> {
         let mut point = Point::default();
@@ -1060,6 +1115,14 @@ impl UIElement {
         kb.end_hold_keys()
     }
 
+    /// Draws a frame of `color` around the element for `duration` milliseconds, and blocks until the frame is removed.
+    ///
+    /// The frame is topmost and click-through, which is used to debug and inspect elements.
+    pub fn highlight(&self, duration: u64, color: Color) -> Result<()> {
+        let rect = self.get_bounding_rectangle()?;
+        Highlighter::new().color(color).show(rect, duration)
+    }
+
     /// Simulates mouse left click event on the element.
     pub fn click(&self) -> Result<()> {
         self.try_focus();
@@ -1433,6 +1496,9 @@ pub enum UIMatcherMode {
     Content
 }
 
+/// The max milliseconds to highlight each candidate in `UIMatcher`, which blocks the search.
+const MAX_MATCHER_HIGHLIGHT: u64 = 1000;
+
 /// Defines filter conditions to match specific UI Element.
 /// 
 /// `UIMatcher` can find first element or find all elements.
@@ -1440,12 +1506,18 @@ pub struct UIMatcher {
     automation: UIAutomation,
     mode: UIMatcherMode,
     depth: u32,
+    order: TraversalOrder,
+    limit: usize,
+    nth: usize,
+    reading_order: bool,
     from: Option<UIElement>,
     // condition: Option<Box<dyn Condition>>,
     filters: Vec<Box<dyn MatcherFilter>>,
     timeout: u64,
     interval: u64,
-    debug: bool
+    cancel: Option<CancellationToken>,
+    debug: bool,
+    highlight: u64
 }
 
 impl UIMatcher {
@@ -1455,11 +1527,17 @@ impl UIMatcher {
             automation,
             mode: UIMatcherMode::Control,
             depth: 7,
+            order: TraversalOrder::DepthFirst,
+            limit: usize::MAX,
+            nth: 0,
+            reading_order: false,
             from: None,
             filters: Vec::new(),
             timeout: 3000,
             interval: 100,
-            debug: false
+            cancel: None,
+            debug: false,
+            highlight: 0
         }
     }
 
@@ -1491,6 +1569,36 @@ impl UIMatcher {
         self
     }
 
+    /// Sets the order to search the tree. The default order is `TraversalOrder::DepthFirst`.
+    /// 
+    /// With `TraversalOrder::BreadthFirst`, the matched elements nearer to the root are found first.
+    pub fn order(mut self, order: TraversalOrder) -> Self {
+        self.order = order;
+        self
+    }
+
+    /// Sets the max count of elements returned by `find_all()`. The searching stops when enough elements are matched.
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = limit;
+        self
+    }
+
+    /// Sets `find_first()` to return the matched element at zero-based `index`, such as `nth(2)` for the third one.
+    pub fn nth(mut self, index: usize) -> Self {
+        self.nth = index;
+        self
+    }
+
+    /// Set `reading_order` as `true` to sort the matched elements by their positions, from top to bottom, then from left to right.
+    /// 
+    /// The elements whose vertical spans mostly overlap are in the same row, even if their tops differ by a few pixels.
+    /// The elements without bounding rectangles are placed after the others in the searched order.
+    /// All the matched elements are searched before sorting, then `nth()` and `limit()` are applied.
+    pub fn reading_order(mut self, reading_order: bool) -> Self {
+        self.reading_order = reading_order;
+        self
+    }
+
     /// Sets the the time in millionseconds for matching element. The default timeout is 3000 millionseconds(3 seconds).
     /// 
     /// The `UIMatcher` will not retry to find when you set `timeout` to `0`.
@@ -1507,6 +1615,12 @@ impl UIMatcher {
         self
     }
 
+    /// Sets the token to cancel the searching. An `ERR_CANCELLED` error will occur when the `token` is cancelled.
+    pub fn cancellation(mut self, token: CancellationToken) -> Self {
+        self.cancel = Some(token);
+        self
+    }
+
     /// Appends a filter condition which is used as `and` logic.
      pub fn filter(mut self, filter: Box<dyn MatcherFilter>) -> Self {
         self.filters.push(filter);
@@ -1586,6 +1700,86 @@ impl UIMatcher {
         self.filter(Box::new(condition))
     }
 
+    /// Filters by name matching the regular expression.
+    pub fn regex_name(self, regex: Regex) -> Self {
+        self.filter(Box::new(RegexNameFilter { regex }))
+    }
+
+    /// Filters by classname matching the regular expression.
+    pub fn regex_classname(self, regex: Regex) -> Self {
+        self.filter(Box::new(RegexClassNameFilter { regex }))
+    }
+
+    /// Filters by name similar to `name`. `threshold` is the min similarity score in `[0.0, 1.0]`, such as `0.8`.
+    pub fn fuzzy_name<S: Into<String>>(self, name: S, threshold: f64) -> Self {
+        self.filter(Box::new(FuzzyNameFilter { value: name.into(), threshold }))
+    }
+
+    /// Filters by the name of `key` in `dictionary`, localized for the culture of elements.
+    /// 
+    /// # Examples:
+    /// 
+    /// ```
+    /// use uiautomation::UIAutomation;
+    /// use uiautomation::names::LANG_NEUTRAL;
+    /// use uiautomation::names::NameDictionary;
+    /// 
+    /// let dictionary = NameDictionary::new()
+    ///     .name("taskbar", LANG_NEUTRAL, "Taskbar")
+    ///     .name("taskbar", 0x0804, "任务栏");
+    /// let automation = UIAutomation::new().unwrap();
+    /// let taskbar = automation.create_matcher().localized_name(&dictionary, "taskbar").timeout(0).find_first();
+    /// ```
+    pub fn localized_name(self, dictionary: &NameDictionary, key: &str) -> Self {
+        let names = dictionary.get(key).cloned().unwrap_or_default();
+        self.filter(Box::new(LocalizedNameFilter { names, lcid: None, partial: false }))
+    }
+
+    /// Filters by automation id.
+    pub fn automation_id<S: Into<String>>(self, automation_id: S) -> Self {
+        self.filter(Box::new(AutomationIdFilter { automation_id: automation_id.into() }))
+    }
+
+    /// Filters by framework id, such as `Win32`, `WPF` or `WinForm`.
+    pub fn framework_id<S: Into<String>>(self, framework_id: S) -> Self {
+        self.filter(Box::new(FrameworkIdFilter { framework_id: framework_id.into() }))
+    }
+
+    /// Filters by process id.
+    pub fn process_id(self, process_id: i32) -> Self {
+        self.filter(Box::new(ProcessIdFilter { process_id }))
+    }
+
+    /// Filters by help text.
+    pub fn help_text<S: Into<String>>(self, help_text: S) -> Self {
+        self.filter(Box::new(HelpTextFilter { help_text: help_text.into() }))
+    }
+
+    /// Filters by enabled state.
+    pub fn enabled(self, enabled: bool) -> Self {
+        self.filter(Box::new(EnabledFilter { enabled }))
+    }
+
+    /// Filters by offscreen state.
+    pub fn offscreen(self, offscreen: bool) -> Self {
+        self.filter(Box::new(OffscreenFilter { offscreen }))
+    }
+
+    /// Filters by keyboard focusable state.
+    pub fn focusable(self, focusable: bool) -> Self {
+        self.filter(Box::new(FocusableFilter { focusable }))
+    }
+
+    /// Filters by the value of `property`.
+    pub fn property<V: Into<Variant>>(self, property: UIProperty, value: V) -> Self {
+        self.filter(Box::new(PropertyFilter::new(property, value.into())))
+    }
+
+    /// Filters the elements which support the control pattern of `pattern_type`.
+    pub fn pattern(self, pattern_type: UIPatternType) -> Self {
+        self.filter(Box::new(PatternFilter { pattern_type }))
+    }
+
     /// Clears all filters.
     pub fn reset(mut self) -> Self {
         // self.condition = None;
@@ -1599,9 +1793,30 @@ impl UIMatcher {
         self
     }
 
-    /// Finds first element.
+    /// Highlights each candidate for `duration` milliseconds in debug mode, green if matched or red if not.
+    ///
+    /// The highlighting is disabled when `duration` is `0`, which is by default.
+    /// The search is blocked while each candidate is highlighted, so `duration` is capped at 1000 milliseconds, and
+    /// the search may take much longer than `timeout()` with many candidates.
+    pub fn highlight(mut self, duration: u64) -> Self {
+        self.highlight = duration.min(MAX_MATCHER_HIGHLIGHT);
+        self
+    }
+
+    /// Converts the matcher into a `UILocator`, which re-resolves the element when it becomes stale.
+    pub fn locator(self) -> UILocator {
+        UILocator::new(self)
+    }
+
+    /// Converts the matcher into an `AsyncUIMatcher`.
+    #[cfg(feature = "async")]
+    pub fn into_async(self) -> AsyncUIMatcher {
+        AsyncUIMatcher::new(self)
+    }
+
+    /// Finds first element, or the element set by `nth()`.
     pub fn find_first(&self) -> Result<UIElement> {
-        let elements = self.find(true)?;
+        let elements = self.find(|| self.try_find(true))?;
 
         if elements.is_empty() {
             Err(Error::new(ERR_NOTFOUND, "can not find element"))
@@ -1612,7 +1827,7 @@ impl UIMatcher {
 
     /// Finds all elements.
     pub fn find_all(&self) -> Result<Vec<UIElement>> {
-        let elements = self.find(false)?;
+        let elements = self.find(|| self.try_find(false))?;
 
         if elements.is_empty() {
             Err(Error::new(ERR_NOTFOUND, "can not find element"))
@@ -1621,30 +1836,76 @@ impl UIMatcher {
         }
     }
 
-    fn find(&self, first_only: bool) -> Result<Vec<UIElement>> {
-        let mut elements: Vec<UIElement> = Vec::new();
-        let start = Local::now().timestamp_millis();
-        loop {
-            if self.debug {
-                println!("Try to match element...")
-            }
-            
-            let (root, walker) = self.prepare()?;
-            self.search(&walker, &root, &mut elements, 1, first_only)?;
+    /// Finds the nearest ancestor of the `from` element, which matches the filters.
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// use uiautomation::UIAutomation;
+    /// use uiautomation::controls::ControlType;
+    /// 
+    /// let automation = UIAutomation::new().unwrap();
+    /// if let Ok(focused) = automation.get_focused_element() {
+    ///     let window = automation.create_matcher().from(focused).control_type(ControlType::Window).timeout(0).find_ancestor();
+    /// }
+    /// ```
+    pub fn find_ancestor(&self) -> Result<UIElement> {
+        let elements = self.find(|| self.try_find_ancestor())?;
 
-            if !elements.is_empty() || self.timeout <= 0 {
-                break;
-            }
+        if elements.is_empty() {
+            Err(Error::new(ERR_NOTFOUND, "can not find element"))
+        } else {
+            Ok(elements[0].clone())
+        }
+    }
 
-            let now = Local::now().timestamp_millis();
-            if now - start >= self.timeout as i64 {
-                return Err(Error::new(ERR_TIMEOUT, "find element time out"));
-            }
+    fn find<F>(&self, attempt: F) -> Result<Vec<UIElement>> where F: Fn() -> Result<Vec<UIElement>> {
+        let ret = retry(self.get_schedule(), self.cancel.as_ref(), || {
+            let elements = attempt()?;
+            Ok(if elements.is_empty() { None } else { Some(elements) })
+        });
+
+        match ret {
+            Ok(elements) => Ok(elements.unwrap_or_default()),
+            Err(e) if e.code() == ERR_TIMEOUT => Err(Error::new(ERR_TIMEOUT, "find element time out")),
+            Err(e) => Err(e),
+        }
+    }
+
+    pub(crate) fn get_schedule(&self) -> RetrySchedule {
+        RetrySchedule::new(self.timeout, self.interval)
+    }
+
+    pub(crate) fn try_find(&self, first_only: bool) -> Result<Vec<UIElement>> {
+        if self.debug {
+            println!("Try to match element...")
+        }
 
-            sleep(Duration::from_millis(self.interval));
-        } 
+        let (root, walker) = self.prepare()?;
+        let wanted = if self.reading_order {
+            usize::MAX
+        } else if first_only {
+            self.nth.saturating_add(1)
+        } else {
+            self.limit
+        };
+
+        let mut elements = self.search(&walker, &root, wanted)?;
+        if self.reading_order {
+            elements = sort_by_reading_order(elements);
+        }
 
-        Ok(elements)
+        Ok(select_matches(elements, first_only, self.nth, self.limit))
+    }
+
+    pub(crate) fn try_find_ancestor(&self) -> Result<Vec<UIElement>> {
+        let Some(ref from) = self.from else {
+            return Err(Error::new(ERR_NOTFOUND, "the element to search from is not set"));
+        };
+
+        let (_, walker) = self.prepare()?;
+        let ancestors = Ancestors::new(ElementNavigator::new(&walker), from.clone()).map_while(|e| e.ok());
+        collect_matched(ancestors, |e| self.check_matched(e), 1)
     }
 
     fn prepare(&self) -> Result<(UIElement, UITreeWalker)> {
@@ -1662,37 +1923,27 @@ impl UIMatcher {
         Ok((root, walker))
     }
 
-    fn search(&self, walker: &UITreeWalker, element: &UIElement, elements: &mut Vec<UIElement>, depth: u32, first_only: bool) -> Result<()> {
-        if self.is_matched(element)? {
-            elements.push(element.clone());
-
-            if first_only {
-                return Ok(());
-            }
-        }
+    fn search(&self, walker: &UITreeWalker, root: &UIElement, wanted: usize) -> Result<Vec<UIElement>> {
+        // the root is not matched when it is specified by `from`.
+        let root_matchable = self.from.is_none();
+        let descendants = Descendants::new(ElementNavigator::new(walker), root.clone())
+            .order(self.order)
+            .depth(self.depth.saturating_sub(1))
+            .filter_map(|e| e.ok());
+        let candidates = std::iter::once(root.clone()).filter(|_| root_matchable).chain(descendants);
 
-        if depth < self.depth {
-            let mut next = walker.get_first_child(element);
-            while let Ok(ref child) = next {
-                self.search(walker, child, elements, depth + 1, first_only)?;
-                if first_only && !elements.is_empty() {
-                    return Ok(());
-                }
+        collect_matched(candidates, |e| self.check_matched(e), wanted)
+    }
 
-                next = walker.get_next_sibling(child);
-            }
+    fn check_matched(&self, element: &UIElement) -> Result<bool> {
+        if let Some(ref token) = self.cancel {
+            token.check()?;
         }
 
-        Ok(())
+        self.is_matched(element)
     }
 
     fn is_matched(&self, element: &UIElement) -> Result<bool> {
-        if let Some(ref root) = self.from {
-            if self.automation.compare_elements(root, element)? {
-                return Ok(false);
-            }
-        }
-
         // let ret = if let Some(ref condition) = self.condition {
         //     condition.judge(element)?
         // } else {
@@ -1709,6 +1960,11 @@ impl UIMatcher {
 
         if self.debug {
             println!("{:?} -> {}", element, ret);
+
+            if self.highlight > 0 {
+                let color = if ret { Color::new(0, 255, 0) } else { Color::new(255, 0, 0) };
+                let _ = element.highlight(self.highlight, color);
+            }
         }
 
         Ok(ret)
@@ -1721,15 +1977,211 @@ impl Debug for UIMatcher {
             .field("automation", &self.automation)
             .field("mode", &self.mode)
             .field("depth", &self.depth)
+            .field("order", &self.order)
+            .field("limit", &self.limit)
+            .field("nth", &self.nth)
+            .field("reading_order", &self.reading_order)
             .field("from", &self.from)
             .field("filters", &format!("({} filers)", self.filters.len()))
             .field("timeout", &self.timeout)
             .field("interval", &self.interval)
+            .field("cancel", &self.cancel)
             .field("debug", &self.debug)
+            .field("highlight", &self.highlight)
         .finish()
     }
 }
 
+/// Collects the nodes matched by `is_matched`, stopping when `wanted` nodes are collected.
+fn collect_matched<T, I, F>(nodes: I, mut is_matched: F, wanted: usize) -> Result<Vec<T>>
+where
+    I: Iterator<Item = T>,
+    F: FnMut(&T) -> Result<bool>
+{
+    let mut matches: Vec<T> = Vec::new();
+    if wanted == 0 {
+        return Ok(matches);
+    }
+
+    for node in nodes {
+        if is_matched(&node)? {
+            matches.push(node);
+            if matches.len() >= wanted {
+                break;
+            }
+        }
+    }
+
+    Ok(matches)
+}
+
+/// Selects the `nth` match when `first_only` is `true`, otherwise the first `limit` matches.
+fn select_matches<T>(mut matches: Vec<T>, first_only: bool, nth: usize, limit: usize) -> Vec<T> {
+    if first_only {
+        if nth < matches.len() {
+            vec![matches.swap_remove(nth)]
+        } else {
+            Vec::new()
+        }
+    } else {
+        matches.truncate(limit);
+        matches
+    }
+}
+
+/// Returns `true` if the vertical spans of two rectangles overlap by at least a half of the shorter height.
+fn is_same_row(a: &Rect, b: &Rect) -> bool {
+    let overlap = a.get_bottom().min(b.get_bottom()) - a.get_top().max(b.get_top()) + 1;
+    let height = a.get_height().min(b.get_height()).max(1);
+    overlap * 2 >= height
+}
+
+/// Sorts the indexes of the rectangles by their positions, from top to bottom, then from left to right.
+/// 
+/// The rectangles are grouped into rows by the first rectangle of each row, so the rows do not chain over the
+/// rectangles which are a little lower than their neighbours.
+fn get_reading_order(rects: &[Rect]) -> Vec<usize> {
+    let mut indexes: Vec<usize> = (0..rects.len()).collect();
+    indexes.sort_by(|&a, &b| rects[a].get_top().cmp(&rects[b].get_top()).then(rects[a].get_left().cmp(&rects[b].get_left())));
+
+    let mut ordered: Vec<usize> = Vec::with_capacity(indexes.len());
+    let mut start = 0;
+    while start < indexes.len() {
+        let first = &rects[indexes[start]];
+        let mut end = start + 1;
+        while end < indexes.len() && is_same_row(first, &rects[indexes[end]]) {
+            end += 1;
+        }
+
+        let mut row = indexes[start..end].to_vec();
+        row.sort_by_key(|&index| rects[index].get_left());
+        ordered.extend(row);
+        start = end;
+    }
+
+    ordered
+}
+
+fn sort_by_reading_order(elements: Vec<UIElement>) -> Vec<UIElement> {
+    let mut rects: Vec<Rect> = Vec::with_capacity(elements.len());
+    let mut placed: Vec<UIElement> = Vec::with_capacity(elements.len());
+    let mut unplaced: Vec<UIElement> = Vec::new();
+    for element in elements {
+        match element.get_bounding_rectangle() {
+            Ok(rect) => {
+                rects.push(rect);
+                placed.push(element);
+            },
+            Err(_) => unplaced.push(element),
+        }
+    }
+
+    let mut items: Vec<Option<UIElement>> = placed.into_iter().map(Some).collect();
+    let mut sorted: Vec<UIElement> = get_reading_order(&rects).into_iter().filter_map(|index| items[index].take()).collect();
+    sorted.extend(unplaced);
+    sorted
+}
+
+/// The async wrapper of `UIMatcher`, which waits between retries without blocking the thread.
+/// 
+/// # Examples
+/// 
+/// ```
+/// use uiautomation::Result;
+/// use uiautomation::UIAutomation;
+/// use uiautomation::UIElement;
+/// 
+/// async fn find_taskbar() -> Result<UIElement> {
+///     let automation = UIAutomation::new()?;
+///     let matcher = automation.create_matcher().classname("Shell_TrayWnd").timeout(1000).into_async();
+///     matcher.find_first().await
+/// }
+/// ```
+#[cfg(feature = "async")]
+#[derive(Debug)]
+pub struct AsyncUIMatcher<T: AsyncTimer = DefaultTimer> {
+    matcher: UIMatcher,
+    timer: T
+}
+
+#[cfg(feature = "async")]
+impl AsyncUIMatcher {
+    /// Creates an async matcher with the `DefaultTimer`.
+    pub fn new(matcher: UIMatcher) -> Self {
+        Self {
+            matcher,
+            timer: DefaultTimer::new()
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+impl<T: AsyncTimer> AsyncUIMatcher<T> {
+    /// Replaces the timer used to wait between retries.
+    pub fn timer<U: AsyncTimer>(self, timer: U) -> AsyncUIMatcher<U> {
+        AsyncUIMatcher {
+            matcher: self.matcher,
+            timer
+        }
+    }
+
+    /// Retrieves the wrapped matcher.
+    pub fn get_matcher(&self) -> &UIMatcher {
+        &self.matcher
+    }
+
+    /// Finds first element, or the element set by `nth()`.
+    pub async fn find_first(&self) -> Result<UIElement> {
+        let mut elements = self.find(|| self.matcher.try_find(true)).await?;
+        if elements.is_empty() {
+            Err(Error::new(ERR_NOTFOUND, "can not find element"))
+        } else {
+            Ok(elements.remove(0))
+        }
+    }
+
+    /// Finds all elements.
+    pub async fn find_all(&self) -> Result<Vec<UIElement>> {
+        let elements = self.find(|| self.matcher.try_find(false)).await?;
+        if elements.is_empty() {
+            Err(Error::new(ERR_NOTFOUND, "can not find element"))
+        } else {
+            Ok(elements)
+        }
+    }
+
+    /// Finds the nearest ancestor of the `from` element, which matches the filters.
+    pub async fn find_ancestor(&self) -> Result<UIElement> {
+        let mut elements = self.find(|| self.matcher.try_find_ancestor()).await?;
+        if elements.is_empty() {
+            Err(Error::new(ERR_NOTFOUND, "can not find element"))
+        } else {
+            Ok(elements.remove(0))
+        }
+    }
+
+    /// Waits until no element is matched.
+    pub async fn wait_until_gone(&self) -> Result<()> {
+        let matcher = &self.matcher;
+        crate::timers::wait_until(&self.timer, matcher.timeout, matcher.interval, || {
+            Ok(matcher.try_find(true)?.is_empty())
+        }).await
+    }
+
+    async fn find<F>(&self, attempt: F) -> Result<Vec<UIElement>> where F: Fn() -> Result<Vec<UIElement>> {
+        let ret = poll_until(&self.timer, self.matcher.get_schedule(), || {
+            let elements = attempt()?;
+            Ok(if elements.is_empty() { None } else { Some(elements) })
+        }).await;
+
+        match ret {
+            Ok(elements) => Ok(elements.unwrap_or_default()),
+            Err(e) if e.code() == ERR_TIMEOUT => Err(Error::new(ERR_TIMEOUT, "find element time out")),
+            Err(e) => Err(e),
+        }
+    }
+}
+
 /// This is the trait for conditions used in filtering when searching for elements in the UI Automation tree.
 pub trait IUICondition<T: Interface>: Sized + From<T> + Into<T> + AsRef<T> {
 }
@@ -2177,6 +2629,7 @@ mod tests {
     use crate::controls::ControlType;
     use crate::filters::MatcherFilter;
     use crate::types::Handle;
+    use crate::types::Rect;
     use crate::types::TreeScope;
 
     fn print_element(element: &UIElement) {
@@ -2353,4 +2806,45 @@ mod tests {
         let auto = UIAutomation::new().unwrap();
         let _ = auto.element_from_handle(Handle::from(0x2006C6));
     }
+
+    #[test]
+    fn test_collect_matched() {
+        let even = |n: &i32| -> crate::Result<bool> { Ok(n % 2 == 0) };
+        assert_eq!(super::collect_matched(1..10, even, usize::MAX).unwrap(), vec![2, 4, 6, 8]);
+        assert_eq!(super::collect_matched(1..10, even, 2).unwrap(), vec![2, 4]);
+        assert!(super::collect_matched(1..10, even, 0).unwrap().is_empty());
+
+        let mut visited = 0;
+        let _ = super::collect_matched(1..10, |n| { visited += 1; even(n) }, 1);
+        assert_eq!(visited, 2);
+
+        let failed = super::collect_matched(1..10, |n| if *n < 3 { Ok(false) } else { Err(crate::Error::new(crate::errors::ERR_NOTFOUND, "failed")) }, 1);
+        assert!(failed.is_err());
+    }
+
+    #[test]
+    fn test_select_matches() {
+        assert_eq!(super::select_matches(vec![1, 2, 3], true, 0, usize::MAX), vec![1]);
+        assert_eq!(super::select_matches(vec![1, 2, 3], true, 2, usize::MAX), vec![3]);
+        assert!(super::select_matches(vec![1, 2, 3], true, 3, usize::MAX).is_empty());
+        assert_eq!(super::select_matches(vec![1, 2, 3], false, 2, 2), vec![1, 2]);
+        assert_eq!(super::select_matches(vec![1, 2, 3], false, 0, usize::MAX), vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_reading_order() {
+        let rects = [
+            Rect::new(100, 52, 150, 72),
+            Rect::new(0, 50, 50, 70),
+            Rect::new(200, 10, 250, 30),
+            Rect::new(0, 12, 50, 32),
+            Rect::new(60, 26, 90, 46),
+        ];
+        let positions: Vec<(i32, i32)> = super::get_reading_order(&rects).iter().map(|&i| (rects[i].get_left(), rects[i].get_top())).collect();
+        assert_eq!(positions, vec![(0, 12), (200, 10), (60, 26), (0, 50), (100, 52)]);
+
+        assert!(super::is_same_row(&Rect::new(0, 10, 10, 29), &Rect::new(20, 18, 30, 29)));
+        assert!(!super::is_same_row(&Rect::new(0, 10, 10, 29), &Rect::new(20, 21, 30, 40)));
+        assert!(super::get_reading_order(&[]).is_empty());
+    }
 }
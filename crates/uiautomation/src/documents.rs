@@ -0,0 +1,590 @@
+use super::errors::Result;
+use super::patterns::TextAttributeValue;
+use super::patterns::UITextPattern;
+use super::patterns::UITextRange;
+use super::types::Color;
+use super::types::StyleType;
+use super::types::TextAttribute;
+use super::types::TextDecorationLineStyle;
+use super::types::TextPatternRangeEndpoint;
+use super::types::TextUnit;
+
+/// The font weight of bold text, which is the same with `FW_BOLD` in GDI.
+const BOLD_WEIGHT: i32 = 700;
+
+/// The character format of a text run.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct TextFormat {
+    /// The font name.
+    pub font_name: Option<String>,
+    /// The font size in points.
+    pub font_size: Option<f64>,
+    /// The font weight, such as `400` for normal and `700` for bold.
+    pub font_weight: Option<i32>,
+    /// Whether the text is italic.
+    pub italic: bool,
+    /// Whether the text is underlined.
+    pub underline: bool,
+    /// Whether the text is struck through.
+    pub strikethrough: bool,
+    /// Whether the text is subscript.
+    pub subscript: bool,
+    /// Whether the text is superscript.
+    pub superscript: bool,
+    /// The foreground color.
+    pub foreground: Option<Color>,
+    /// The background color.
+    pub background: Option<Color>
+}
+
+impl TextFormat {
+    /// Returns `true` if the font weight is bold or heavier.
+    pub fn is_bold(&self) -> bool {
+        self.font_weight.is_some_and(|weight| weight >= BOLD_WEIGHT)
+    }
+}
+
+/// A piece of text with the same format.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct TextRun {
+    /// The text.
+    pub text: String,
+    /// The format of the text.
+    pub format: TextFormat
+}
+
+impl TextRun {
+    /// Creates a text run.
+    pub fn new(text: &str, format: TextFormat) -> Self {
+        Self {
+            text: text.to_string(),
+            format
+        }
+    }
+}
+
+/// The kind of a block, which is decided by the paragraph style.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BlockKind {
+    /// A normal paragraph.
+    #[default]
+    Paragraph,
+    /// A heading with the level from `1` to `9`.
+    Heading(u8),
+    /// An item of a bulleted or numbered list.
+    ListItem { ordered: bool },
+    /// A quotation.
+    Quote
+}
+
+impl From<StyleType> for BlockKind {
+    fn from(style: StyleType) -> Self {
+        match style {
+            StyleType::Title | StyleType::Heading1 => BlockKind::Heading(1),
+            StyleType::Subtitle | StyleType::Heading2 => BlockKind::Heading(2),
+            StyleType::Heading3 => BlockKind::Heading(3),
+            StyleType::Heading4 => BlockKind::Heading(4),
+            StyleType::Heading5 => BlockKind::Heading(5),
+            StyleType::Heading6 => BlockKind::Heading(6),
+            StyleType::Heading7 => BlockKind::Heading(7),
+            StyleType::Heading8 => BlockKind::Heading(8),
+            StyleType::Heading9 => BlockKind::Heading(9),
+            StyleType::BulletedList => BlockKind::ListItem { ordered: false },
+            StyleType::NumberedList => BlockKind::ListItem { ordered: true },
+            StyleType::Quote => BlockKind::Quote,
+            _ => BlockKind::Paragraph,
+        }
+    }
+}
+
+/// A paragraph of the document.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Block {
+    /// The kind of the block.
+    pub kind: BlockKind,
+    /// The paragraph style reported by the control.
+    pub style: Option<StyleType>,
+    /// The text runs in the block.
+    pub runs: Vec<TextRun>
+}
+
+impl Block {
+    /// Creates a block.
+    pub fn new(kind: BlockKind, runs: Vec<TextRun>) -> Self {
+        Self {
+            kind,
+            style: None,
+            runs
+        }
+    }
+
+    /// Retrieves the plain text of the block.
+    pub fn get_text(&self) -> String {
+        self.runs.iter().map(|run| run.text.as_str()).collect()
+    }
+
+    /// Merges the adjacent runs with the same format.
+    fn merged_runs(&self) -> Vec<TextRun> {
+        let mut runs: Vec<TextRun> = Vec::new();
+        for run in &self.runs {
+            if run.text.is_empty() {
+                continue;
+            }
+
+            match runs.last_mut() {
+                Some(prev) if prev.format == run.format => prev.text.push_str(&run.text),
+                _ => runs.push(run.clone()),
+            }
+        }
+        runs
+    }
+}
+
+/// A structured document exported from a text control.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Document {
+    /// The blocks of the document.
+    pub blocks: Vec<Block>
+}
+
+impl Document {
+    /// Creates a document.
+    pub fn new(blocks: Vec<Block>) -> Self {
+        Self {
+            blocks
+        }
+    }
+
+    /// Retrieves the plain text of the document, one line per block.
+    pub fn get_text(&self) -> String {
+        self.blocks.iter().map(|block| block.get_text()).collect::<Vec<String>>().join("\n")
+    }
+
+    /// Renders the document as Markdown.
+    ///
+    /// Bold, italic and strikethrough are rendered as inline styles. Other formats are dropped.
+    ///
+    /// # Examples
+    /// ```
+    /// use uiautomation::documents::*;
+    ///
+    /// let bold = TextFormat { font_weight: Some(700), ..Default::default() };
+    /// let doc = Document::new(vec![
+    ///     Block::new(BlockKind::Heading(1), vec![TextRun::new("Title", TextFormat::default())]),
+    ///     Block::new(BlockKind::Paragraph, vec![TextRun::new("Some ", TextFormat::default()), TextRun::new("bold", bold)]),
+    /// ]);
+    /// assert_eq!(doc.to_markdown(), "# Title\n\nSome **bold**\n");
+    /// ```
+    pub fn to_markdown(&self) -> String {
+        let mut markdown = String::new();
+        let mut prev: Option<BlockKind> = None;
+        for block in &self.blocks {
+            if let Some(prev) = prev {
+                let same_list = prev == block.kind && matches!(prev, BlockKind::ListItem { .. });
+                markdown.push_str(if same_list { "\n" } else { "\n\n" });
+            }
+
+            let prefix = match block.kind {
+                BlockKind::Paragraph => String::new(),
+                BlockKind::Heading(level) => format!("{} ", "#".repeat(level.clamp(1, 6) as usize)),
+                BlockKind::ListItem { ordered: false } => "- ".to_string(),
+                BlockKind::ListItem { ordered: true } => "1. ".to_string(),
+                BlockKind::Quote => "> ".to_string(),
+            };
+            markdown.push_str(&prefix);
+
+            for run in block.merged_runs() {
+                markdown.push_str(&render_markdown_run(&run));
+            }
+
+            prev = Some(block.kind);
+        }
+
+        if !markdown.is_empty() {
+            markdown.push('\n');
+        }
+        markdown
+    }
+
+    /// Renders the document as an HTML fragment.
+    ///
+    /// When `styled` is `true`, the font, size and colors are kept as inline CSS styles.
+    ///
+    /// # Examples
+    /// ```
+    /// use uiautomation::documents::*;
+    ///
+    /// let doc = Document::new(vec![
+    ///     Block::new(BlockKind::ListItem { ordered: false }, vec![TextRun::new("a < b", TextFormat::default())]),
+    /// ]);
+    /// assert_eq!(doc.to_html(false), "<ul>\n<li>a &lt; b</li>\n</ul>\n");
+    /// ```
+    pub fn to_html(&self, styled: bool) -> String {
+        let mut html = String::new();
+        let mut list: Option<bool> = None;
+        for block in &self.blocks {
+            let ordered = match block.kind {
+                BlockKind::ListItem { ordered } => Some(ordered),
+                _ => None,
+            };
+            if list.is_some() && list != ordered {
+                html.push_str(if list == Some(true) { "</ol>\n" } else { "</ul>\n" });
+                list = None;
+            }
+            if list.is_none() {
+                if let Some(ordered) = ordered {
+                    html.push_str(if ordered { "<ol>\n" } else { "<ul>\n" });
+                    list = Some(ordered);
+                }
+            }
+
+            let tag = match block.kind {
+                BlockKind::Paragraph => "p".to_string(),
+                BlockKind::Heading(level) => format!("h{}", level.clamp(1, 6)),
+                BlockKind::ListItem { .. } => "li".to_string(),
+                BlockKind::Quote => "blockquote".to_string(),
+            };
+
+            html.push_str(&format!("<{}>", tag));
+            for run in block.merged_runs() {
+                html.push_str(&render_html_run(&run, styled));
+            }
+            html.push_str(&format!("</{}>\n", tag));
+        }
+
+        if let Some(ordered) = list {
+            html.push_str(if ordered { "</ol>\n" } else { "</ul>\n" });
+        }
+        html
+    }
+}
+
+fn escape_markdown(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '\\' | '*' | '_' | '`' | '#' | '[' | ']' | '<' | '>' | '~' | '|' => {
+                escaped.push('\\');
+                escaped.push(ch);
+            },
+            '\n' => escaped.push_str("  \n"),
+            _ => escaped.push(ch),
+        }
+    }
+    escaped
+}
+
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\n' => escaped.push_str("<br>"),
+            _ => escaped.push(ch),
+        }
+    }
+    escaped
+}
+
+/// Splits the leading and trailing whitespaces, which must be put outside of the inline markers.
+fn split_spaces(text: &str) -> (&str, &str, &str) {
+    let trimmed = text.trim_start();
+    let leading = &text[..text.len() - trimmed.len()];
+    let content = trimmed.trim_end();
+    let trailing = &trimmed[content.len()..];
+    (leading, content, trailing)
+}
+
+fn render_markdown_run(run: &TextRun) -> String {
+    let (leading, content, trailing) = split_spaces(&run.text);
+    if content.is_empty() {
+        return run.text.clone();
+    }
+
+    let mut marker = String::new();
+    if run.format.is_bold() {
+        marker.push_str("**");
+    }
+    if run.format.italic {
+        marker.push('*');
+    }
+    if run.format.strikethrough {
+        marker.push_str("~~");
+    }
+    let closing: String = marker.chars().rev().collect();
+
+    format!("{}{}{}{}{}", escape_markdown(leading), marker, escape_markdown(content), closing, escape_markdown(trailing))
+}
+
+fn render_html_run(run: &TextRun, styled: bool) -> String {
+    let format = &run.format;
+    let mut tags: Vec<&str> = Vec::new();
+    if format.is_bold() {
+        tags.push("strong");
+    }
+    if format.italic {
+        tags.push("em");
+    }
+    if format.underline {
+        tags.push("u");
+    }
+    if format.strikethrough {
+        tags.push("s");
+    }
+    if format.subscript {
+        tags.push("sub");
+    }
+    if format.superscript {
+        tags.push("sup");
+    }
+
+    let mut styles: Vec<String> = Vec::new();
+    if styled {
+        if let Some(ref font_name) = format.font_name {
+            styles.push(format!("font-family:'{}'", escape_html(font_name)));
+        }
+        if let Some(font_size) = format.font_size {
+            styles.push(format!("font-size:{}pt", font_size));
+        }
+        if let Some(color) = format.foreground {
+            styles.push(format!("color:{}", color));
+        }
+        if let Some(color) = format.background {
+            styles.push(format!("background-color:{}", color));
+        }
+    }
+
+    let mut html = String::new();
+    if !styles.is_empty() {
+        html.push_str(&format!("<span style=\"{}\">", styles.join(";")));
+    }
+    for tag in &tags {
+        html.push_str(&format!("<{}>", tag));
+    }
+    html.push_str(&escape_html(&run.text));
+    for tag in tags.iter().rev() {
+        html.push_str(&format!("</{}>", tag));
+    }
+    if !styles.is_empty() {
+        html.push_str("</span>");
+    }
+    html
+}
+
+/// Exports the text and formats of a `UITextPattern` document into a `Document`.
+///
+/// The document range is walked by `TextUnit::Paragraph` into blocks, and each paragraph is walked by `TextUnit::Format` into runs.
+///
+/// # Examples
+/// ```
+/// use uiautomation::UIAutomation;
+/// use uiautomation::documents::DocumentExporter;
+/// use uiautomation::patterns::UITextPattern;
+///
+/// let automation = UIAutomation::new().unwrap();
+/// let element = automation.get_focused_element().unwrap();
+/// if let Ok(pattern) = element.get_pattern::<UITextPattern>() {
+///     let doc = DocumentExporter::new().export(&pattern).unwrap();
+///     println!("{}", doc.to_markdown());
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct DocumentExporter {
+    max_blocks: usize,
+    max_runs: usize
+}
+
+impl Default for DocumentExporter {
+    fn default() -> Self {
+        Self {
+            max_blocks: 10000,
+            max_runs: 1000
+        }
+    }
+}
+
+impl DocumentExporter {
+    /// Creates an exporter.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the max count of blocks to export. The default value is `10000`.
+    pub fn max_blocks(mut self, max_blocks: usize) -> Self {
+        self.max_blocks = max_blocks;
+        self
+    }
+
+    /// Sets the max count of runs in a block. The default value is `1000`.
+    pub fn max_runs(mut self, max_runs: usize) -> Self {
+        self.max_runs = max_runs;
+        self
+    }
+
+    /// Exports the whole document of `pattern`.
+    pub fn export(&self, pattern: &UITextPattern) -> Result<Document> {
+        let range = pattern.get_document_range()?;
+        self.export_range(pattern, &range)
+    }
+
+    /// Exports the paragraphs in `range`, which belongs to the document of `pattern`.
+    /// 
+    /// The parts of `range` outside the document are not exported.
+    pub fn export_range(&self, pattern: &UITextPattern, range: &UITextRange) -> Result<Document> {
+        let document = pattern.get_document_range()?;
+        let range = range.clone_range()?;
+        if range.compare_endpoints(TextPatternRangeEndpoint::Start, &document, TextPatternRangeEndpoint::Start)? < 0 {
+            range.move_endpoint_by_range(TextPatternRangeEndpoint::Start, &document, TextPatternRangeEndpoint::Start)?;
+        }
+        if range.compare_endpoints(TextPatternRangeEndpoint::End, &document, TextPatternRangeEndpoint::End)? > 0 {
+            range.move_endpoint_by_range(TextPatternRangeEndpoint::End, &document, TextPatternRangeEndpoint::End)?;
+        }
+
+        let mut blocks: Vec<Block> = Vec::new();
+        for paragraph in range.paragraphs()?.take(self.max_blocks) {
+            let paragraph = paragraph?;
+
+            let mut block = Block::new(BlockKind::Paragraph, self.read_runs(&paragraph)?);
+            trim_line_break(&mut block.runs);
+            if let TextAttributeValue::Style(style) = get_attribute(&paragraph, TextAttribute::StyleId) {
+                block.kind = style.into();
+                block.style = Some(style);
+            }
+            blocks.push(block);
+        }
+
+        Ok(Document::new(blocks))
+    }
+
+    fn read_runs(&self, paragraph: &UITextRange) -> Result<Vec<TextRun>> {
+        let mut runs: Vec<TextRun> = Vec::new();
+        for run in paragraph.iter_units(TextUnit::Format)?.take(self.max_runs) {
+            let run = run?;
+            let text = run.get_text(-1)?;
+            if !text.is_empty() {
+                runs.push(TextRun {
+                    text,
+                    format: read_format(&run)
+                });
+            }
+        }
+
+        Ok(runs)
+    }
+}
+
+/// Reads the attribute value, returning `NotSupported` if it can not be read.
+fn get_attribute(range: &UITextRange, attr: TextAttribute) -> TextAttributeValue {
+    range.get_attribute(attr).unwrap_or(TextAttributeValue::NotSupported)
+}
+
+fn read_format(range: &UITextRange) -> TextFormat {
+    let is_set = |attr: TextAttribute| get_attribute(range, attr).as_bool().unwrap_or_default();
+    let has_line = |attr: TextAttribute| matches!(get_attribute(range, attr), TextAttributeValue::LineStyle(style) if style != TextDecorationLineStyle::None);
+
+    TextFormat {
+        font_name: get_attribute(range, TextAttribute::FontName).as_str().map(String::from),
+        font_size: get_attribute(range, TextAttribute::FontSize).as_f64(),
+        font_weight: get_attribute(range, TextAttribute::FontWeight).as_i32(),
+        italic: is_set(TextAttribute::IsItalic),
+        underline: has_line(TextAttribute::UnderlineStyle),
+        strikethrough: has_line(TextAttribute::StrikethroughStyle),
+        subscript: is_set(TextAttribute::IsSubscript),
+        superscript: is_set(TextAttribute::IsSuperscript),
+        foreground: get_attribute(range, TextAttribute::ForegroundColor).as_color(),
+        background: get_attribute(range, TextAttribute::BackgroundColor).as_color()
+    }
+}
+
+/// Removes the paragraph mark at the end of the last run.
+fn trim_line_break(runs: &mut Vec<TextRun>) {
+    while let Some(run) = runs.last_mut() {
+        let len = run.text.trim_end_matches(['\r', '\n', '\u{2029}']).len();
+        run.text.truncate(len);
+        if run.text.is_empty() {
+            runs.pop();
+        } else {
+            break;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::types::Color;
+    use crate::types::StyleType;
+
+    use super::Block;
+    use super::BlockKind;
+    use super::Document;
+    use super::TextFormat;
+    use super::TextRun;
+    use super::trim_line_break;
+
+    fn plain(text: &str) -> TextRun {
+        TextRun::new(text, TextFormat::default())
+    }
+
+    fn bold(text: &str) -> TextRun {
+        TextRun::new(text, TextFormat { font_weight: Some(700), ..Default::default() })
+    }
+
+    fn italic(text: &str) -> TextRun {
+        TextRun::new(text, TextFormat { italic: true, ..Default::default() })
+    }
+
+    fn sample() -> Document {
+        Document::new(vec![
+            Block::new(BlockKind::Heading(2), vec![plain("Notes")]),
+            Block::new(BlockKind::Paragraph, vec![plain("This is "), bold("very "), bold("bold "), plain("and "), italic("italic"), plain(".")]),
+            Block::new(BlockKind::ListItem { ordered: false }, vec![plain("first")]),
+            Block::new(BlockKind::ListItem { ordered: false }, vec![plain("second")]),
+            Block::new(BlockKind::ListItem { ordered: true }, vec![plain("one")]),
+            Block::new(BlockKind::Quote, vec![plain("1 * 2 < 3")]),
+        ])
+    }
+
+    #[test]
+    fn test_markdown() {
+        assert_eq!(sample().to_markdown(), "## Notes\n\nThis is **very bold** and *italic*.\n\n- first\n- second\n\n1. one\n\n> 1 \\* 2 \\< 3\n");
+        assert_eq!(Document::default().to_markdown(), "");
+    }
+
+    #[test]
+    fn test_html() {
+        assert_eq!(sample().to_html(false), "<h2>Notes</h2>\n<p>This is <strong>very bold </strong>and <em>italic</em>.</p>\n<ul>\n<li>first</li>\n<li>second</li>\n</ul>\n<ol>\n<li>one</li>\n</ol>\n<blockquote>1 * 2 &lt; 3</blockquote>\n");
+    }
+
+    #[test]
+    fn test_styled_html() {
+        let format = TextFormat {
+            font_name: Some("Arial".to_string()),
+            font_size: Some(10.5),
+            underline: true,
+            foreground: Some(Color::new(255, 0, 0)),
+            ..Default::default()
+        };
+        let doc = Document::new(vec![Block::new(BlockKind::Paragraph, vec![TextRun::new("red", format)])]);
+        assert_eq!(doc.to_html(true), "<p><span style=\"font-family:'Arial';font-size:10.5pt;color:#FF0000\"><u>red</u></span></p>\n");
+        assert_eq!(doc.to_html(false), "<p><u>red</u></p>\n");
+    }
+
+    #[test]
+    fn test_block_kind() {
+        assert_eq!(BlockKind::from(StyleType::Heading3), BlockKind::Heading(3));
+        assert_eq!(BlockKind::from(StyleType::Title), BlockKind::Heading(1));
+        assert_eq!(BlockKind::from(StyleType::NumberedList), BlockKind::ListItem { ordered: true });
+        assert_eq!(BlockKind::from(StyleType::Normal), BlockKind::Paragraph);
+    }
+
+    #[test]
+    fn test_trim_line_break() {
+        let mut runs = vec![plain("text"), bold("end\r"), plain("\r\n")];
+        trim_line_break(&mut runs);
+        assert_eq!(runs, vec![plain("text"), bold("end")]);
+        assert_eq!(sample().get_text().lines().count(), 6);
+    }
+}
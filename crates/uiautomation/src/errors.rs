@@ -21,6 +21,10 @@ pub const ERR_FORMAT: i32 = 6;
 pub const ERR_INVALID_OBJECT: i32 = 7;
 /// Error already running.
 pub const ERR_ALREADY_RUNNING: i32 = 8;
+/// Error occurs when the operation is cancelled by a `CancellationToken`.
+pub const ERR_CANCELLED: i32 = 9;
+/// Error occurs when the result does not match the expected value.
+pub const ERR_MISMATCH: i32 = 10;
 
 #[derive(Debug, PartialEq, Eq)]
 pub struct Error {
@@ -1,6 +1,18 @@
 use std::fmt::Debug;
+use std::ops::BitAnd;
+use std::ops::BitOr;
+use std::ops::Not;
+
+pub use regex::Regex;
 
 use crate::controls::ControlType;
+use crate::names::LocalizedName;
+use crate::names::get_ui_language;
+use crate::names::similarity;
+use crate::patterns::UIPatternType;
+use crate::types::UIProperty;
+use crate::variants::Value;
+use crate::variants::Variant;
 
 use super::core::UIElement;
 use super::errors::Result;
@@ -120,4 +132,367 @@ impl<F> MatcherFilter for FnFilter<F> where F: Fn(&UIElement) -> Result<bool> {
     fn judge(&self, element: &UIElement) -> Result<bool> {
         (self.filter)(element)
     }
-}
\ No newline at end of file
+}
+
+/// Matches the elements whose name matches the regular expression.
+#[derive(Debug)]
+pub struct RegexNameFilter {
+    pub regex: Regex
+}
+
+impl MatcherFilter for RegexNameFilter {
+    fn judge(&self, element: &UIElement) -> Result<bool> {
+        let name = element.get_name()?;
+        Ok(self.regex.is_match(&name))
+    }
+}
+
+/// Matches the elements whose classname matches the regular expression.
+#[derive(Debug)]
+pub struct RegexClassNameFilter {
+    pub regex: Regex
+}
+
+impl MatcherFilter for RegexClassNameFilter {
+    fn judge(&self, element: &UIElement) -> Result<bool> {
+        let classname = element.get_classname()?;
+        Ok(self.regex.is_match(&classname))
+    }
+}
+
+/// Matches the elements whose name is similar to the value, scored by `names::similarity()`.
+#[derive(Debug, Default)]
+pub struct FuzzyNameFilter {
+    pub value: String,
+    /// The min similarity score in `[0.0, 1.0]`.
+    pub threshold: f64
+}
+
+impl MatcherFilter for FuzzyNameFilter {
+    fn judge(&self, element: &UIElement) -> Result<bool> {
+        let name = element.get_name()?;
+        Ok(similarity(&name, &self.value) >= self.threshold)
+    }
+}
+
+/// Matches the elements whose name equals to the localized name for the element's culture.
+/// 
+/// The culture is `lcid` if it is set, otherwise the culture of the element, or the user's UI language if the element
+/// does not report its culture. If there is no name for the culture, the names in all locales are matched.
+#[derive(Debug, Default)]
+pub struct LocalizedNameFilter {
+    pub names: LocalizedName,
+    pub lcid: Option<u32>,
+    pub partial: bool
+}
+
+impl MatcherFilter for LocalizedNameFilter {
+    fn judge(&self, element: &UIElement) -> Result<bool> {
+        let lcid = match self.lcid {
+            Some(lcid) => lcid,
+            None => match element.get_culture()? {
+                0 => get_ui_language(),
+                culture => culture as u32,
+            },
+        };
+        let names = match self.names.get_name(lcid) {
+            Some(name) => vec![name],
+            None => self.names.get_names(),
+        };
+
+        let element_name = element.get_name()?;
+        Ok(names.iter().any(|name| {
+            if self.partial {
+                element_name.contains(name)
+            } else {
+                element_name == *name
+            }
+        }))
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct AutomationIdFilter {
+    pub automation_id: String
+}
+
+impl MatcherFilter for AutomationIdFilter {
+    fn judge(&self, element: &UIElement) -> Result<bool> {
+        let automation_id = element.get_automation_id()?;
+        Ok(self.automation_id == automation_id)
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct FrameworkIdFilter {
+    pub framework_id: String
+}
+
+impl MatcherFilter for FrameworkIdFilter {
+    fn judge(&self, element: &UIElement) -> Result<bool> {
+        let framework_id = element.get_framework_id()?;
+        Ok(self.framework_id == framework_id)
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct ProcessIdFilter {
+    pub process_id: i32
+}
+
+impl MatcherFilter for ProcessIdFilter {
+    fn judge(&self, element: &UIElement) -> Result<bool> {
+        let process_id = element.get_process_id()?;
+        Ok(self.process_id == process_id)
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct HelpTextFilter {
+    pub help_text: String
+}
+
+impl MatcherFilter for HelpTextFilter {
+    fn judge(&self, element: &UIElement) -> Result<bool> {
+        let help_text = element.get_help_text()?;
+        Ok(self.help_text == help_text)
+    }
+}
+
+#[derive(Debug)]
+pub struct EnabledFilter {
+    pub enabled: bool
+}
+
+impl MatcherFilter for EnabledFilter {
+    fn judge(&self, element: &UIElement) -> Result<bool> {
+        Ok(self.enabled == element.is_enabled()?)
+    }
+}
+
+#[derive(Debug)]
+pub struct OffscreenFilter {
+    pub offscreen: bool
+}
+
+impl MatcherFilter for OffscreenFilter {
+    fn judge(&self, element: &UIElement) -> Result<bool> {
+        Ok(self.offscreen == element.is_offscreen()?)
+    }
+}
+
+#[derive(Debug)]
+pub struct FocusableFilter {
+    pub focusable: bool
+}
+
+impl MatcherFilter for FocusableFilter {
+    fn judge(&self, element: &UIElement) -> Result<bool> {
+        Ok(self.focusable == element.is_keyboard_focusable()?)
+    }
+}
+
+/// Matches the elements whose property equals to the value.
+/// 
+/// The numbers are compared by value, so `Variant::from(1i32)` matches a property of `1u32`.
+pub struct PropertyFilter {
+    pub property: UIProperty,
+    pub value: Variant
+}
+
+impl PropertyFilter {
+    pub fn new(property: UIProperty, value: Variant) -> Self {
+        Self {
+            property,
+            value
+        }
+    }
+}
+
+impl MatcherFilter for PropertyFilter {
+    fn judge(&self, element: &UIElement) -> Result<bool> {
+        let actual = element.get_property_value(self.property)?.get_value()?;
+        let expected = self.value.get_value()?;
+        Ok(is_same_value(&actual, &expected))
+    }
+}
+
+/// Matches the elements which support the control pattern.
+#[derive(Debug)]
+pub struct PatternFilter {
+    pub pattern_type: UIPatternType
+}
+
+impl MatcherFilter for PatternFilter {
+    fn judge(&self, element: &UIElement) -> Result<bool> {
+        element.is_pattern_available(self.pattern_type)
+    }
+}
+
+pub struct NotFilter {
+    pub filter: Box<dyn MatcherFilter>
+}
+
+impl NotFilter {
+    pub fn new(filter: Box<dyn MatcherFilter>) -> Self {
+        Self {
+            filter
+        }
+    }
+}
+
+impl MatcherFilter for NotFilter {
+    fn judge(&self, element: &UIElement) -> Result<bool> {
+        Ok(!self.filter.judge(element)?)
+    }
+}
+
+/// Matches the elements which match all the filters. An empty `AllFilter` matches all elements.
+pub struct AllFilter {
+    pub filters: Vec<Box<dyn MatcherFilter>>
+}
+
+impl AllFilter {
+    pub fn new(filters: Vec<Box<dyn MatcherFilter>>) -> Self {
+        Self {
+            filters
+        }
+    }
+}
+
+impl MatcherFilter for AllFilter {
+    fn judge(&self, element: &UIElement) -> Result<bool> {
+        for filter in &self.filters {
+            if !filter.judge(element)? {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+}
+
+/// Matches the elements which match any of the filters. An empty `AnyFilter` matches no element.
+pub struct AnyFilter {
+    pub filters: Vec<Box<dyn MatcherFilter>>
+}
+
+impl AnyFilter {
+    pub fn new(filters: Vec<Box<dyn MatcherFilter>>) -> Self {
+        Self {
+            filters
+        }
+    }
+}
+
+impl MatcherFilter for AnyFilter {
+    fn judge(&self, element: &UIElement) -> Result<bool> {
+        for filter in &self.filters {
+            if filter.judge(element)? {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+}
+
+/// Combines two filters by `AndFilter`.
+/// 
+/// # Examples
+/// 
+/// ```
+/// use uiautomation::controls::ControlType;
+/// use uiautomation::filters::ControlTypeFilter;
+/// use uiautomation::filters::MatcherFilter;
+/// use uiautomation::filters::NameFilter;
+/// 
+/// let button: Box<dyn MatcherFilter> = Box::new(ControlTypeFilter { control_type: ControlType::Button });
+/// let ok: Box<dyn MatcherFilter> = Box::new(NameFilter { value: "OK".into(), ..Default::default() });
+/// let filter = button & !ok;
+/// ```
+impl BitAnd for Box<dyn MatcherFilter> {
+    type Output = Box<dyn MatcherFilter>;
+
+    fn bitand(self, rhs: Self) -> Self::Output {
+        Box::new(AndFilter::new(self, rhs))
+    }
+}
+
+/// Combines two filters by `OrFilter`.
+impl BitOr for Box<dyn MatcherFilter> {
+    type Output = Box<dyn MatcherFilter>;
+
+    fn bitor(self, rhs: Self) -> Self::Output {
+        Box::new(OrFilter::new(self, rhs))
+    }
+}
+
+/// Negates the filter by `NotFilter`.
+impl Not for Box<dyn MatcherFilter> {
+    type Output = Box<dyn MatcherFilter>;
+
+    fn not(self) -> Self::Output {
+        Box::new(NotFilter::new(self))
+    }
+}
+
+/// Converts the numeric value into `f64` for comparing.
+fn as_number(value: &Value) -> Option<f64> {
+    match value {
+        Value::I1(v) => Some(*v as f64),
+        Value::I2(v) => Some(*v as f64),
+        Value::I4(v) | Value::INT(v) => Some(*v as f64),
+        Value::I8(v) => Some(*v as f64),
+        Value::UI1(v) => Some(*v as f64),
+        Value::UI2(v) => Some(*v as f64),
+        Value::UI4(v) | Value::UINT(v) => Some(*v as f64),
+        Value::UI8(v) => Some(*v as f64),
+        Value::R4(v) => Some(*v as f64),
+        Value::R8(v) => Some(*v),
+        _ => None,
+    }
+}
+
+/// Compares the values of properties. Only the numbers, strings, booleans and arrays are comparable.
+fn is_same_value(a: &Value, b: &Value) -> bool {
+    if let (Some(a), Some(b)) = (as_number(a), as_number(b)) {
+        return a == b;
+    }
+
+    match (a, b) {
+        (Value::EMPTY, Value::EMPTY) => true,
+        (Value::STRING(a), Value::STRING(b)) => a == b,
+        (Value::BOOL(a), Value::BOOL(b)) => a == b,
+        (Value::ArrayBool(a), Value::ArrayBool(b)) => a == b,
+        (Value::ArrayR8(a), Value::ArrayR8(b)) => a == b,
+        (Value::ArrayI2(a), Value::ArrayI2(b)) => a == b,
+        (Value::ArrayI4(a), Value::ArrayI4(b)) => a == b,
+        (Value::ArrayI8(a), Value::ArrayI8(b)) => a == b,
+        (Value::ArrayUI2(a), Value::ArrayUI2(b)) => a == b,
+        (Value::ArrayUI4(a), Value::ArrayUI4(b)) => a == b,
+        (Value::ArrayUI8(a), Value::ArrayUI8(b)) => a == b,
+        (Value::ArrayString(a), Value::ArrayString(b)) => a == b,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::variants::Value;
+
+    use super::is_same_value;
+
+    #[test]
+    fn test_same_value() {
+        assert!(is_same_value(&Value::I4(1), &Value::UI4(1)));
+        assert!(is_same_value(&Value::R8(2.0), &Value::I2(2)));
+        assert!(!is_same_value(&Value::I4(1), &Value::I4(2)));
+        assert!(is_same_value(&Value::STRING("OK".into()), &Value::STRING("OK".into())));
+        assert!(!is_same_value(&Value::STRING("1".into()), &Value::I4(1)));
+        assert!(is_same_value(&Value::BOOL(true), &Value::BOOL(true)));
+        assert!(!is_same_value(&Value::BOOL(true), &Value::I4(1)));
+        assert!(is_same_value(&Value::ArrayI4(vec![42, 1]), &Value::ArrayI4(vec![42, 1])));
+        assert!(!is_same_value(&Value::ArrayI4(vec![1]), &Value::ArrayI8(vec![1])));
+        assert!(is_same_value(&Value::EMPTY, &Value::EMPTY));
+        assert!(!is_same_value(&Value::NULL, &Value::NULL));
+    }
+}
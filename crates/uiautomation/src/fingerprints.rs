@@ -0,0 +1,475 @@
+use std::fmt::Display;
+
+use super::controls::ControlType;
+use super::core::UIAutomation;
+use super::core::UIElement;
+use super::core::UITreeWalker;
+use super::errors::ERR_NOTFOUND;
+use super::errors::Error;
+use super::errors::Result;
+use super::names::similarity;
+use super::snapshots::ElementSnapshot;
+use super::types::Rect;
+
+/// The attribute similarity below which the attribute is reported as drifted.
+const DRIFT_TOLERANCE: f64 = 0.95;
+
+/// The attributes of a `Fingerprint`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FingerprintAttribute {
+    AutomationId,
+    Name,
+    ClassName,
+    ControlType,
+    /// The index among the siblings.
+    Position,
+    /// The control types, classnames and names of the ancestors.
+    ParentChain,
+    /// The bounding rectangle relative to the top level window.
+    Proportions
+}
+
+impl Display for FingerprintAttribute {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            FingerprintAttribute::AutomationId => "automation id",
+            FingerprintAttribute::Name => "name",
+            FingerprintAttribute::ClassName => "classname",
+            FingerprintAttribute::ControlType => "control type",
+            FingerprintAttribute::Position => "position",
+            FingerprintAttribute::ParentChain => "parent chain",
+            FingerprintAttribute::Proportions => "proportions",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// The weights of the attributes when scoring a candidate.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FingerprintWeights {
+    pub automation_id: f64,
+    pub name: f64,
+    pub classname: f64,
+    pub control_type: f64,
+    pub position: f64,
+    pub parent_chain: f64,
+    pub proportions: f64
+}
+
+impl Default for FingerprintWeights {
+    fn default() -> Self {
+        Self {
+            automation_id: 5.0,
+            name: 3.0,
+            classname: 2.0,
+            control_type: 2.0,
+            position: 1.0,
+            parent_chain: 2.0,
+            proportions: 1.0
+        }
+    }
+}
+
+impl FingerprintWeights {
+    /// Retrieves the weight of `attribute`.
+    pub fn get_weight(&self, attribute: FingerprintAttribute) -> f64 {
+        match attribute {
+            FingerprintAttribute::AutomationId => self.automation_id,
+            FingerprintAttribute::Name => self.name,
+            FingerprintAttribute::ClassName => self.classname,
+            FingerprintAttribute::ControlType => self.control_type,
+            FingerprintAttribute::Position => self.position,
+            FingerprintAttribute::ParentChain => self.parent_chain,
+            FingerprintAttribute::Proportions => self.proportions,
+        }
+    }
+}
+
+/// The identifying attributes of an ancestor in a `Fingerprint`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AncestorInfo {
+    pub control_type: Option<ControlType>,
+    pub classname: String,
+    pub name: String
+}
+
+impl AncestorInfo {
+    fn similarity(&self, other: &AncestorInfo) -> f64 {
+        let control_type = if self.control_type == other.control_type { 1.0 } else { 0.0 };
+        let classname = if self.classname == other.classname { 1.0 } else { 0.0 };
+        (control_type + classname + similarity(&self.name, &other.name)) / 3.0
+    }
+}
+
+impl From<&ElementSnapshot> for AncestorInfo {
+    fn from(snapshot: &ElementSnapshot) -> Self {
+        Self {
+            control_type: snapshot.control_type,
+            classname: snapshot.classname.clone(),
+            name: snapshot.name.clone()
+        }
+    }
+}
+
+/// The weighted attributes of an element, which relocate the element after the UI changes.
+///
+/// Unlike a `Selector`, a fingerprint tolerates the drift of some attributes, such as a renamed button or a moved control.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Fingerprint {
+    pub automation_id: String,
+    pub name: String,
+    pub classname: String,
+    pub control_type: Option<ControlType>,
+    /// The index among the siblings.
+    pub sibling_index: usize,
+    /// The count of the siblings, including the element.
+    pub sibling_count: usize,
+    /// The ancestors from the parent to the top level window, excluding the root element.
+    pub parent_chain: Vec<AncestorInfo>,
+    /// The left, top, width and height of the bounding rectangle, in proportion to the top level window.
+    pub proportions: [f64; 4]
+}
+
+impl Fingerprint {
+    /// Creates the fingerprint of `node`, with its ancestors from the parent to the top level window, and its siblings.
+    pub fn new(node: &ElementSnapshot, ancestors: &[&ElementSnapshot], siblings: &[ElementSnapshot], sibling_index: usize) -> Self {
+        let window = ancestors.last().map_or(node.bounding_rectangle, |w| w.bounding_rectangle);
+        Self {
+            automation_id: node.automation_id.clone(),
+            name: node.name.clone(),
+            classname: node.classname.clone(),
+            control_type: node.control_type,
+            sibling_index,
+            sibling_count: siblings.len(),
+            parent_chain: ancestors.iter().map(|a| AncestorInfo::from(*a)).collect(),
+            proportions: get_proportions(&node.bounding_rectangle, &window)
+        }
+    }
+
+    /// Creates the fingerprint of the descendant of `root` at `path`, which lists the indexes of children from `root`.
+    pub fn from_snapshot(root: &ElementSnapshot, path: &[usize]) -> Result<Self> {
+        let Some((index, parent_path)) = path.split_last() else {
+            return Err(Error::new(ERR_NOTFOUND, "the root element has no fingerprint"));
+        };
+
+        let mut ancestors: Vec<&ElementSnapshot> = Vec::with_capacity(parent_path.len());
+        let mut parent = root;
+        for i in parent_path {
+            parent = parent.children.get(*i).ok_or_else(|| Error::new(ERR_NOTFOUND, "invalid path"))?;
+            ancestors.push(parent);
+        }
+        ancestors.reverse();
+
+        let node = parent.children.get(*index).ok_or_else(|| Error::new(ERR_NOTFOUND, "invalid path"))?;
+        Ok(Self::new(node, &ancestors, &parent.children, *index))
+    }
+
+    /// Creates the fingerprint of `element`, walking the control view.
+    pub fn from_element(automation: &UIAutomation, element: &UIElement) -> Result<Self> {
+        let walker = automation.get_control_view_walker()?;
+        let mut ancestors: Vec<UIElement> = element.ancestors(&walker).collect::<Result<Vec<UIElement>>>()?;
+        // the last ancestor is the root element.
+        if ancestors.pop().is_none() {
+            return Err(Error::new(ERR_NOTFOUND, "the root element has no fingerprint"));
+        }
+
+        let node = ElementSnapshot::from_element(element)?;
+        let parent = match ancestors.first() {
+            Some(parent) => parent.clone(),
+            None => automation.get_root_element()?,
+        };
+        let siblings: Vec<ElementSnapshot> = parent.children(&walker)
+            .map(|child| ElementSnapshot::from_element(&child?))
+            .collect::<Result<Vec<ElementSnapshot>>>()?;
+        let Some(index) = siblings.iter().position(|s| s.runtime_id == node.runtime_id) else {
+            return Err(Error::new(ERR_NOTFOUND, "the element is not found among its siblings"));
+        };
+        let ancestors: Vec<ElementSnapshot> = ancestors.iter().map(ElementSnapshot::from_element).collect::<Result<Vec<ElementSnapshot>>>()?;
+        let ancestors: Vec<&ElementSnapshot> = ancestors.iter().collect();
+
+        Ok(Self::new(&node, &ancestors, &siblings, index))
+    }
+
+    /// Calculates the similarity of each attribute to `other` in `[0.0, 1.0]`.
+    ///
+    /// The automation id is skipped when both are empty, and the proportions are skipped when any of them is unknown.
+    pub fn compare(&self, other: &Fingerprint) -> Vec<(FingerprintAttribute, f64)> {
+        let same = |equal: bool| if equal { 1.0 } else { 0.0 };
+
+        let mut scores: Vec<(FingerprintAttribute, f64)> = Vec::with_capacity(7);
+        if !self.automation_id.is_empty() || !other.automation_id.is_empty() {
+            scores.push((FingerprintAttribute::AutomationId, same(self.automation_id == other.automation_id)));
+        }
+        scores.push((FingerprintAttribute::Name, similarity(&self.name, &other.name)));
+        scores.push((FingerprintAttribute::ClassName, same(self.classname == other.classname)));
+        scores.push((FingerprintAttribute::ControlType, same(self.control_type == other.control_type)));
+
+        let count = self.sibling_count.max(other.sibling_count).max(1) as f64;
+        let offset = self.sibling_index.abs_diff(other.sibling_index) as f64;
+        scores.push((FingerprintAttribute::Position, 1.0 - offset / count));
+
+        let levels = self.parent_chain.len().max(other.parent_chain.len());
+        let chain = if levels == 0 {
+            1.0
+        } else {
+            let sum: f64 = self.parent_chain.iter().zip(&other.parent_chain).map(|(a, b)| a.similarity(b)).sum();
+            sum / levels as f64
+        };
+        scores.push((FingerprintAttribute::ParentChain, chain));
+
+        let known = |p: &[f64; 4]| p[2] > 0.0 && p[3] > 0.0;
+        if known(&self.proportions) && known(&other.proportions) {
+            let diff: f64 = self.proportions.iter().zip(&other.proportions).map(|(a, b)| (a - b).abs()).sum();
+            scores.push((FingerprintAttribute::Proportions, (1.0 - diff / 4.0).max(0.0)));
+        }
+
+        scores
+    }
+}
+
+/// Calculates the proportions of `rect` relative to `window`. The proportions are all zeros if any rectangle is empty.
+fn get_proportions(rect: &Rect, window: &Rect) -> [f64; 4] {
+    let width = window.get_width() as f64;
+    let height = window.get_height() as f64;
+    if *rect == Rect::default() || *window == Rect::default() || width <= 0.0 || height <= 0.0 {
+        return [0.0; 4];
+    }
+
+    [
+        (rect.get_left() - window.get_left()) as f64 / width,
+        (rect.get_top() - window.get_top()) as f64 / height,
+        rect.get_width() as f64 / width,
+        rect.get_height() as f64 / height
+    ]
+}
+
+/// The result of scoring a candidate against a fingerprint.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FingerprintMatch {
+    /// The weighted score in `[0.0, 1.0]`.
+    pub score: f64,
+    /// The attributes which differ from the fingerprint.
+    pub drifted: Vec<FingerprintAttribute>
+}
+
+/// Relocates elements by their fingerprints, picking the best scored candidate above the threshold.
+///
+/// # Examples
+/// ```
+/// use uiautomation::UIAutomation;
+/// use uiautomation::fingerprints::Fingerprint;
+/// use uiautomation::fingerprints::Relocator;
+///
+/// let automation = UIAutomation::new().unwrap();
+/// if let Ok(element) = automation.get_focused_element() {
+///     let fingerprint = Fingerprint::from_element(&automation, &element).unwrap();
+///     // ... after the UI changes
+///     if let Ok((element, matched)) = Relocator::new().relocate(&automation, &fingerprint) {
+///         println!("{} (score {:.2}, drifted {:?})", element, matched.score, matched.drifted);
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Relocator {
+    weights: FingerprintWeights,
+    threshold: f64,
+    depth: u32
+}
+
+impl Default for Relocator {
+    fn default() -> Self {
+        Self {
+            weights: FingerprintWeights::default(),
+            threshold: 0.6,
+            depth: 7
+        }
+    }
+}
+
+impl Relocator {
+    /// Creates a relocator with the default weights and threshold.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the weights of the attributes.
+    pub fn weights(mut self, weights: FingerprintWeights) -> Self {
+        self.weights = weights;
+        self
+    }
+
+    /// Sets the min score of the relocated element. The default threshold is `0.6`.
+    pub fn threshold(mut self, threshold: f64) -> Self {
+        self.threshold = threshold;
+        self
+    }
+
+    /// Sets the depth to search candidates in `relocate()`. The default depth is `7`.
+    pub fn depth(mut self, depth: u32) -> Self {
+        self.depth = depth;
+        self
+    }
+
+    /// Scores `candidate` against `fingerprint`.
+    pub fn score(&self, fingerprint: &Fingerprint, candidate: &Fingerprint) -> FingerprintMatch {
+        let scores = fingerprint.compare(candidate);
+
+        let total: f64 = scores.iter().map(|(attr, _)| self.weights.get_weight(*attr)).sum();
+        let score: f64 = scores.iter().map(|(attr, s)| self.weights.get_weight(*attr) * s).sum();
+        let drifted = scores.iter().filter(|(_, s)| *s < DRIFT_TOLERANCE).map(|(attr, _)| *attr).collect();
+
+        FingerprintMatch {
+            score: if total > 0.0 { score / total } else { 0.0 },
+            drifted
+        }
+    }
+
+    /// Picks the best scored candidate above the threshold. The first one wins when the scores are equal.
+    pub fn pick<T, I>(&self, fingerprint: &Fingerprint, candidates: I) -> Option<(T, FingerprintMatch)>
+    where
+        I: IntoIterator<Item = (T, Fingerprint)>
+    {
+        let mut best: Option<(T, FingerprintMatch)> = None;
+        for (item, candidate) in candidates {
+            let matched = self.score(fingerprint, &candidate);
+            if matched.score >= self.threshold && best.as_ref().is_none_or(|(_, b)| matched.score > b.score) {
+                best = Some((item, matched));
+            }
+        }
+        best
+    }
+
+    /// Relocates the element in the snapshot of the root element, and returns its path.
+    pub fn relocate_in_snapshot(&self, root: &ElementSnapshot, fingerprint: &Fingerprint) -> Option<(Vec<usize>, FingerprintMatch)> {
+        let mut candidates: Vec<(Vec<usize>, Fingerprint)> = Vec::new();
+        collect_fingerprints(root, &mut Vec::new(), &mut Vec::new(), &mut candidates);
+        self.pick(fingerprint, candidates)
+    }
+
+    /// Relocates the element from the root element.
+    ///
+    /// The control view is captured once until `depth`, and all the captured elements are candidates, so an element
+    /// whose control type drifted can still be relocated.
+    pub fn relocate(&self, automation: &UIAutomation, fingerprint: &Fingerprint) -> Result<(UIElement, FingerprintMatch)> {
+        let walker = automation.get_control_view_walker()?;
+        let root = automation.get_root_element()?;
+        let snapshot = ElementSnapshot::capture(&root, &walker, self.depth)?;
+
+        let Some((path, matched)) = self.relocate_in_snapshot(&snapshot, fingerprint) else {
+            return Err(Error::new(ERR_NOTFOUND, "no element matches the fingerprint"));
+        };
+        let element = get_element_at(&root, &walker, &snapshot, &path)?;
+        Ok((element, matched))
+    }
+}
+
+/// Retrieves the element at `path` of the `snapshot` captured from `root`.
+///
+/// The children are matched by their runtime ids, because the snapshot skips the children which fail to be captured.
+fn get_element_at(root: &UIElement, walker: &UITreeWalker, snapshot: &ElementSnapshot, path: &[usize]) -> Result<UIElement> {
+    let mut element = root.clone();
+    let mut node = snapshot;
+    for index in path {
+        node = node.children.get(*index).ok_or_else(|| Error::new(ERR_NOTFOUND, "invalid path"))?;
+        let child = element.children(walker)
+            .map_while(|child| child.ok())
+            .find(|child| child.get_runtime_id().is_ok_and(|id| id == node.runtime_id));
+        element = child.ok_or_else(|| Error::new(ERR_NOTFOUND, "the relocated element disappeared"))?;
+    }
+    Ok(element)
+}
+
+/// Collects the fingerprints of all the descendants of `node`.
+fn collect_fingerprints<'a>(node: &'a ElementSnapshot, path: &mut Vec<usize>, ancestors: &mut Vec<&'a ElementSnapshot>, fingerprints: &mut Vec<(Vec<usize>, Fingerprint)>) {
+    for (index, child) in node.children.iter().enumerate() {
+        path.push(index);
+
+        let chain: Vec<&ElementSnapshot> = ancestors.iter().rev().copied().collect();
+        fingerprints.push((path.clone(), Fingerprint::new(child, &chain, &node.children, index)));
+
+        ancestors.push(child);
+        collect_fingerprints(child, path, ancestors, fingerprints);
+        ancestors.pop();
+
+        path.pop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::controls::ControlType;
+    use crate::snapshots::ElementSnapshot;
+    use crate::types::Rect;
+
+    use super::Fingerprint;
+    use super::FingerprintAttribute;
+    use super::Relocator;
+    use super::get_proportions;
+
+    fn button(name: &str, id: &str, left: i32) -> ElementSnapshot {
+        let mut button = ElementSnapshot::new().name(name).automation_id(id).classname("Button").control_type(ControlType::Button);
+        button.bounding_rectangle = Rect::new(left, 500, left + 100, 530);
+        button
+    }
+
+    fn window(buttons: Vec<ElementSnapshot>) -> ElementSnapshot {
+        let mut window = ElementSnapshot::new().name("Setup").classname("Dialog").control_type(ControlType::Window);
+        window.bounding_rectangle = Rect::new(0, 0, 800, 600);
+        window.children = buttons;
+        ElementSnapshot::new().name("Desktop").child(window)
+    }
+
+    #[test]
+    fn test_proportions() {
+        let window = Rect::new(100, 100, 499, 299);
+        assert_eq!(get_proportions(&Rect::new(300, 200, 499, 299), &window), [0.5, 0.5, 0.5, 0.5]);
+        assert_eq!(get_proportions(&Rect::new(1, 1, 2, 2), &Rect::default()), [0.0; 4]);
+        assert_eq!(get_proportions(&Rect::default(), &window), [0.0; 4]);
+    }
+
+    #[test]
+    fn test_fingerprint() {
+        let root = window(vec![button("Back", "back", 500), button("Next", "next", 650)]);
+        let fingerprint = Fingerprint::from_snapshot(&root, &[0, 1]).unwrap();
+        assert_eq!(fingerprint.automation_id, "next");
+        assert_eq!(fingerprint.sibling_index, 1);
+        assert_eq!(fingerprint.sibling_count, 2);
+        assert_eq!(fingerprint.parent_chain.len(), 1);
+        assert_eq!(fingerprint.parent_chain[0].name, "Setup");
+        assert_eq!(fingerprint.proportions[0], 650.0 / 801.0);
+
+        assert!(Fingerprint::from_snapshot(&root, &[]).is_err());
+        assert!(Fingerprint::from_snapshot(&root, &[0, 2]).is_err());
+
+        let relocator = Relocator::new();
+        let exact = relocator.score(&fingerprint, &fingerprint);
+        assert_eq!(exact.score, 1.0);
+        assert!(exact.drifted.is_empty());
+    }
+
+    #[test]
+    fn test_relocate() {
+        let root = window(vec![button("Back", "back", 500), button("Next", "next", 650)]);
+        let fingerprint = Fingerprint::from_snapshot(&root, &[0, 1]).unwrap();
+        let relocator = Relocator::new();
+
+        // renamed and moved, with a new sibling inserted before it.
+        let changed = window(vec![button("Help", "help", 50), button("Back", "back", 500), button("Next >", "next", 680)]);
+        let (path, matched) = relocator.relocate_in_snapshot(&changed, &fingerprint).unwrap();
+        assert_eq!(path, vec![0, 2]);
+        assert!(matched.score > 0.8);
+        assert!(matched.drifted.contains(&FingerprintAttribute::Position));
+        assert!(!matched.drifted.contains(&FingerprintAttribute::AutomationId));
+
+        // the automation id is gone, but the other attributes still match.
+        let changed = window(vec![button("Back", "", 500), button("Next", "", 650)]);
+        let (path, matched) = relocator.relocate_in_snapshot(&changed, &fingerprint).unwrap();
+        assert_eq!(path, vec![0, 1]);
+        assert_eq!(matched.drifted, vec![FingerprintAttribute::AutomationId]);
+
+        // nothing similar enough.
+        let changed = window(vec![button("Cancel", "cancel", 100)]);
+        assert!(relocator.relocate_in_snapshot(&changed, &fingerprint).is_none());
+        assert!(relocator.clone().threshold(0.3).relocate_in_snapshot(&changed, &fingerprint).is_some());
+    }
+}
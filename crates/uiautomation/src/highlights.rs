@@ -0,0 +1,317 @@
+use std::sync::Once;
+use std::thread::sleep;
+use std::time::Duration;
+use std::time::Instant;
+
+use windows::core::w;
+use windows::Win32::Foundation::COLORREF;
+use windows::Win32::Foundation::HINSTANCE;
+use windows::Win32::Foundation::HWND;
+use windows::Win32::Foundation::LPARAM;
+use windows::Win32::Foundation::LRESULT;
+use windows::Win32::Foundation::RECT;
+use windows::Win32::Foundation::WPARAM;
+use windows::Win32::Graphics::Gdi::CreateSolidBrush;
+use windows::Win32::Graphics::Gdi::DeleteObject;
+use windows::Win32::Graphics::Gdi::FillRect;
+use windows::Win32::Graphics::Gdi::HBRUSH;
+use windows::Win32::Graphics::Gdi::HDC;
+use windows::Win32::Graphics::Gdi::MONITOR_DEFAULTTONEAREST;
+use windows::Win32::Graphics::Gdi::MonitorFromRect;
+use windows::Win32::System::LibraryLoader::GetModuleHandleW;
+use windows::Win32::UI::HiDpi::DPI_AWARENESS_CONTEXT;
+use windows::Win32::UI::HiDpi::DPI_AWARENESS_CONTEXT_PER_MONITOR_AWARE_V2;
+use windows::Win32::UI::HiDpi::GetDpiForMonitor;
+use windows::Win32::UI::HiDpi::MDT_EFFECTIVE_DPI;
+use windows::Win32::UI::HiDpi::SetThreadDpiAwarenessContext;
+use windows::Win32::UI::WindowsAndMessaging::CreateWindowExW;
+use windows::Win32::UI::WindowsAndMessaging::DefWindowProcW;
+use windows::Win32::UI::WindowsAndMessaging::DestroyWindow;
+use windows::Win32::UI::WindowsAndMessaging::DispatchMessageW;
+use windows::Win32::UI::WindowsAndMessaging::GWLP_USERDATA;
+use windows::Win32::UI::WindowsAndMessaging::GetClientRect;
+use windows::Win32::UI::WindowsAndMessaging::GetWindowLongPtrW;
+use windows::Win32::UI::WindowsAndMessaging::LWA_ALPHA;
+use windows::Win32::UI::WindowsAndMessaging::MSG;
+use windows::Win32::UI::WindowsAndMessaging::PM_REMOVE;
+use windows::Win32::UI::WindowsAndMessaging::PeekMessageW;
+use windows::Win32::UI::WindowsAndMessaging::RegisterClassExW;
+use windows::Win32::UI::WindowsAndMessaging::SW_SHOWNOACTIVATE;
+use windows::Win32::UI::WindowsAndMessaging::SetLayeredWindowAttributes;
+use windows::Win32::UI::WindowsAndMessaging::SetWindowLongPtrW;
+use windows::Win32::UI::WindowsAndMessaging::ShowWindow;
+use windows::Win32::UI::WindowsAndMessaging::TranslateMessage;
+use windows::Win32::UI::WindowsAndMessaging::WM_ERASEBKGND;
+use windows::Win32::UI::WindowsAndMessaging::WNDCLASSEXW;
+use windows::Win32::UI::WindowsAndMessaging::WS_EX_LAYERED;
+use windows::Win32::UI::WindowsAndMessaging::WS_EX_NOACTIVATE;
+use windows::Win32::UI::WindowsAndMessaging::WS_EX_TOOLWINDOW;
+use windows::Win32::UI::WindowsAndMessaging::WS_EX_TOPMOST;
+use windows::Win32::UI::WindowsAndMessaging::WS_EX_TRANSPARENT;
+use windows::Win32::UI::WindowsAndMessaging::WS_POPUP;
+
+use super::errors::Error;
+use super::errors::Result;
+use super::types::Color;
+use super::types::Rect;
+
+/// The DPI at 100% scale.
+pub const DEFAULT_DPI: u32 = 96;
+
+/// The default border width of the highlight frame at 100% scale.
+pub const DEFAULT_BORDER: i32 = 3;
+
+/// The interval to pump the messages of the frame windows.
+const PUMP_INTERVAL: u64 = 10;
+
+/// Scales the `border` width at 100% scale to `dpi`, rounded and at least 1 pixel.
+pub fn scale_border(border: i32, dpi: u32) -> i32 {
+    let scaled = (border as i64 * dpi as i64 + DEFAULT_DPI as i64 / 2) / DEFAULT_DPI as i64;
+    scaled.max(1) as i32
+}
+
+/// Computes the top, bottom, left and right border rectangles of a frame of `border` width, which surrounds `rect`
+/// without covering it.
+///
+/// The rectangles do not overlap each other, and the top and bottom ones cover the corners.
+pub fn get_frame_rects(rect: Rect, border: i32) -> [Rect; 4] {
+    let border = border.max(1);
+    let left = rect.get_left() - border;
+    let top = rect.get_top() - border;
+    let right = rect.get_right() + border;
+    let bottom = rect.get_bottom() + border;
+
+    [
+        Rect::new(left, top, right, rect.get_top() - 1),
+        Rect::new(left, rect.get_bottom() + 1, right, bottom),
+        Rect::new(left, rect.get_top(), rect.get_left() - 1, rect.get_bottom()),
+        Rect::new(rect.get_right() + 1, rect.get_top(), right, rect.get_bottom())
+    ]
+}
+
+/// Retrieves the effective DPI of the monitor which `rect` is mostly on.
+pub fn get_monitor_dpi(rect: Rect) -> u32 {
+    let rect: RECT = rect.into();
+    let mut dpi_x: u32 = DEFAULT_DPI;
+    let mut dpi_y: u32 = DEFAULT_DPI;
+    unsafe {
+        let monitor = MonitorFromRect(&rect, MONITOR_DEFAULTTONEAREST);
+        if GetDpiForMonitor(monitor, MDT_EFFECTIVE_DPI, &mut dpi_x, &mut dpi_y).is_err() {
+            return DEFAULT_DPI;
+        }
+    }
+    dpi_x
+}
+
+/// Draws a click-through topmost frame around a rectangle on the screen, which is used to debug and inspect elements.
+///
+/// # Examples
+/// ```no_run
+/// use uiautomation::highlights::Highlighter;
+/// use uiautomation::types::Color;
+/// use uiautomation::types::Rect;
+///
+/// Highlighter::new().color(Color::new(0, 255, 0)).border(2).show(Rect::new(100, 100, 300, 200), 1000).unwrap();
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Highlighter {
+    color: Color,
+    border: i32
+}
+
+impl Default for Highlighter {
+    fn default() -> Self {
+        Self {
+            color: Color::new(255, 0, 0),
+            border: DEFAULT_BORDER
+        }
+    }
+}
+
+impl Highlighter {
+    /// Creates a highlighter with a red frame of `DEFAULT_BORDER` width.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the color of the frame.
+    pub fn color(mut self, color: Color) -> Self {
+        self.color = color;
+        self
+    }
+
+    /// Sets the border width of the frame at 100% scale, which is scaled by the DPI of the monitor.
+    pub fn border(mut self, border: i32) -> Self {
+        self.border = border;
+        self
+    }
+
+    /// Shows the frame around `rect` for `duration` milliseconds, and blocks until the frame is removed.
+    pub fn show(&self, rect: Rect, duration: u64) -> Result<()> {
+        register_class();
+        let _awareness = DpiAwareness::per_monitor();
+
+        let border = scale_border(self.border, get_monitor_dpi(rect));
+        let brush = unsafe { CreateSolidBrush(COLORREF::from(self.color)) };
+        if brush.is_invalid() {
+            return Err(Error::last_os_error());
+        }
+
+        let mut frames: Vec<HWND> = Vec::with_capacity(4);
+        let mut ret = Ok(());
+        for border_rect in get_frame_rects(rect, border) {
+            match create_frame(border_rect, brush) {
+                Ok(frame) => frames.push(frame),
+                Err(e) => {
+                    ret = Err(e);
+                    break;
+                }
+            }
+        }
+
+        if ret.is_ok() {
+            pump_messages(duration);
+        }
+
+        unsafe {
+            for frame in frames {
+                let _ = DestroyWindow(frame);
+            }
+            let _ = DeleteObject(brush);
+        }
+
+        ret
+    }
+}
+
+/// Switches the current thread to per-monitor DPI awareness, and restores the previous awareness when dropped.
+///
+/// The element rectangles are in physical pixels, so the DPI lookup and the frame windows must not be scaled.
+struct DpiAwareness(DPI_AWARENESS_CONTEXT);
+
+impl DpiAwareness {
+    fn per_monitor() -> Self {
+        Self(unsafe { SetThreadDpiAwarenessContext(DPI_AWARENESS_CONTEXT_PER_MONITOR_AWARE_V2) })
+    }
+}
+
+impl Drop for DpiAwareness {
+    fn drop(&mut self) {
+        // the previous context is null if the awareness was not changed.
+        if self.0.0 != 0 {
+            unsafe { SetThreadDpiAwarenessContext(self.0) };
+        }
+    }
+}
+
+const CLASS_NAME: windows::core::PCWSTR = w!("UIAutomationHighlightFrame");
+
+fn register_class() {
+    static REGISTER: Once = Once::new();
+    REGISTER.call_once(|| unsafe {
+        let class = WNDCLASSEXW {
+            cbSize: std::mem::size_of::<WNDCLASSEXW>() as u32,
+            lpfnWndProc: Some(frame_proc),
+            hInstance: get_instance(),
+            lpszClassName: CLASS_NAME,
+            ..Default::default()
+        };
+        RegisterClassExW(&class);
+    });
+}
+
+fn get_instance() -> HINSTANCE {
+    unsafe {
+        GetModuleHandleW(None).map(|module| module.into()).unwrap_or_default()
+    }
+}
+
+fn create_frame(rect: Rect, brush: HBRUSH) -> Result<HWND> {
+    unsafe {
+        let frame = CreateWindowExW(WS_EX_LAYERED | WS_EX_TRANSPARENT | WS_EX_TOPMOST | WS_EX_TOOLWINDOW | WS_EX_NOACTIVATE,
+            CLASS_NAME, None, WS_POPUP,
+            rect.get_left(), rect.get_top(), rect.get_width(), rect.get_height(),
+            None, None, get_instance(), None);
+        if frame.0 == 0 {
+            return Err(Error::last_os_error());
+        }
+
+        SetWindowLongPtrW(frame, GWLP_USERDATA, brush.0);
+        if let Err(e) = SetLayeredWindowAttributes(frame, COLORREF(0), 255, LWA_ALPHA) {
+            let _ = DestroyWindow(frame);
+            return Err(e.into());
+        }
+        let _ = ShowWindow(frame, SW_SHOWNOACTIVATE);
+
+        Ok(frame)
+    }
+}
+
+fn pump_messages(duration: u64) {
+    let deadline = Instant::now() + Duration::from_millis(duration);
+    let mut msg = MSG::default();
+    loop {
+        unsafe {
+            while PeekMessageW(&mut msg, None, 0, 0, PM_REMOVE).as_bool() {
+                let _ = TranslateMessage(&msg);
+                DispatchMessageW(&msg);
+            }
+        }
+
+        let now = Instant::now();
+        if now >= deadline {
+            break;
+        }
+        sleep((deadline - now).min(Duration::from_millis(PUMP_INTERVAL)));
+    }
+}
+
+/// Fills the frame window with the brush saved in its user data.
+extern "system" fn frame_proc(hwnd: HWND, msg: u32, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
+    unsafe {
+        if msg == WM_ERASEBKGND {
+            let mut rect = RECT::default();
+            if GetClientRect(hwnd, &mut rect).is_ok() {
+                let brush = HBRUSH(GetWindowLongPtrW(hwnd, GWLP_USERDATA));
+                FillRect(HDC(wparam.0 as isize), &rect, brush);
+            }
+            return LRESULT(1);
+        }
+
+        DefWindowProcW(hwnd, msg, wparam, lparam)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::types::Rect;
+
+    use super::get_frame_rects;
+    use super::scale_border;
+
+    #[test]
+    fn test_scale_border() {
+        assert_eq!(scale_border(3, 96), 3);
+        assert_eq!(scale_border(3, 120), 4);
+        assert_eq!(scale_border(3, 144), 5);
+        assert_eq!(scale_border(3, 192), 6);
+        assert_eq!(scale_border(0, 96), 1);
+    }
+
+    #[test]
+    fn test_frame_rects() {
+        let [top, bottom, left, right] = get_frame_rects(Rect::new(10, 20, 109, 69), 2);
+        assert_eq!(top, Rect::new(8, 18, 111, 19));
+        assert_eq!(bottom, Rect::new(8, 70, 111, 71));
+        assert_eq!(left, Rect::new(8, 20, 9, 69));
+        assert_eq!(right, Rect::new(110, 20, 111, 69));
+
+        assert_eq!((top.get_width(), top.get_height()), (104, 2));
+        assert_eq!((left.get_width(), left.get_height()), (2, 50));
+
+        // the border area equals the outer area minus the inner area.
+        let area = |r: &Rect| r.get_width() * r.get_height();
+        let frame: i32 = [top, bottom, left, right].iter().map(area).sum();
+        assert_eq!(frame, 104 * 54 - 100 * 50);
+    }
+}
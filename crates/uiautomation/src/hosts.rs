@@ -0,0 +1,629 @@
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::sync::mpsc::Sender;
+use std::sync::mpsc::channel;
+use std::sync::mpsc::sync_channel;
+use std::thread::JoinHandle;
+use std::thread::spawn;
+
+use windows::Win32::System::Com::COINIT;
+use windows::Win32::System::Com::COINIT_APARTMENTTHREADED;
+use windows::Win32::System::Com::COINIT_MULTITHREADED;
+use windows::Win32::System::Com::CoInitializeEx;
+use windows::Win32::System::Com::CoUninitialize;
+
+use super::core::UIAutomation;
+use super::core::UIElement;
+use super::errors::ERR_INVALID_OBJECT;
+use super::errors::ERR_NOTFOUND;
+use super::errors::Error;
+use super::errors::Result;
+use super::types::Handle;
+use super::types::Point;
+
+/// Defines the concurrency model of the COM library on the host thread.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ComApartment {
+    /// Initializes the thread for multithreaded object concurrency(MTA).
+    #[default]
+    MultiThreaded,
+    /// Initializes the thread for apartment-threaded object concurrency(STA).
+    ApartmentThreaded
+}
+
+impl From<ComApartment> for COINIT {
+    fn from(apartment: ComApartment) -> Self {
+        match apartment {
+            ComApartment::MultiThreaded => COINIT_MULTITHREADED,
+            ComApartment::ApartmentThreaded => COINIT_APARTMENTTHREADED,
+        }
+    }
+}
+
+/// `HostBackend` is the automation object owned by the host thread.
+pub trait HostBackend: 'static {
+    /// The element type created by the backend.
+    type Element: Clone;
+
+    /// Retrieves the runtime id of `element`, which is used as the key of the element proxy.
+    fn runtime_id(&self, element: &Self::Element) -> Result<Vec<i32>>;
+}
+
+impl HostBackend for UIAutomation {
+    type Element = UIElement;
+
+    fn runtime_id(&self, element: &UIElement) -> Result<Vec<i32>> {
+        element.get_runtime_id()
+    }
+}
+
+/// Holds the elements referenced by `ElementProxy` handles, keyed by runtime id.
+#[derive(Debug)]
+pub struct ElementRegistry<E> {
+    elements: HashMap<Vec<i32>, (E, usize)>
+}
+
+impl<E> ElementRegistry<E> {
+    /// Creates an empty registry.
+    pub fn new() -> Self {
+        Self {
+            elements: HashMap::new()
+        }
+    }
+
+    /// Registers `element` as `runtime_id`, and increases its reference count.
+    ///
+    /// When `runtime_id` has been registered already, the first registered element is kept and only its reference count
+    /// is increased, so that the existing proxies keep referring to the same element.
+    pub fn register(&mut self, runtime_id: Vec<i32>, element: E) {
+        let entry = self.elements.entry(runtime_id).or_insert((element, 0));
+        entry.1 += 1;
+    }
+
+    /// Increases the reference count of the element.
+    pub fn retain(&mut self, runtime_id: &[i32]) -> bool {
+        if let Some(entry) = self.elements.get_mut(runtime_id) {
+            entry.1 += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Decreases the reference count of the element, and removes it when it is no longer referenced.
+    pub fn release(&mut self, runtime_id: &[i32]) {
+        if let Some(entry) = self.elements.get_mut(runtime_id) {
+            entry.1 -= 1;
+            if entry.1 == 0 {
+                self.elements.remove(runtime_id);
+            }
+        }
+    }
+
+    /// Retrieves the registered element.
+    pub fn get(&self, runtime_id: &[i32]) -> Result<&E> {
+        match self.elements.get(runtime_id) {
+            Some((element, _)) => Ok(element),
+            None => Err(Error::new(ERR_NOTFOUND, "element is not registered")),
+        }
+    }
+
+    /// Retrieves the count of registered elements.
+    pub fn len(&self) -> usize {
+        self.elements.len()
+    }
+
+    /// Returns `true` if there is no registered element.
+    pub fn is_empty(&self) -> bool {
+        self.elements.is_empty()
+    }
+}
+
+impl<E> Default for ElementRegistry<E> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The state owned by the host thread. Requests are executed with the context exclusively.
+pub struct HostContext<B: HostBackend> {
+    backend: B,
+    registry: ElementRegistry<B::Element>
+}
+
+impl<B: HostBackend> HostContext<B> {
+    /// Retrieves the backend automation object.
+    pub fn backend(&self) -> &B {
+        &self.backend
+    }
+
+    /// Retrieves the registry of proxied elements.
+    pub fn registry(&self) -> &ElementRegistry<B::Element> {
+        &self.registry
+    }
+
+    /// Retrieves the element referenced by `runtime_id`.
+    pub fn element(&self, runtime_id: &[i32]) -> Result<&B::Element> {
+        self.registry.get(runtime_id)
+    }
+
+    /// Registers `element` and returns its runtime id.
+    pub fn register(&mut self, element: B::Element) -> Result<Vec<i32>> {
+        let runtime_id = self.backend.runtime_id(&element)?;
+        self.registry.register(runtime_id.clone(), element);
+        Ok(runtime_id)
+    }
+}
+
+type Job<B> = Box<dyn FnOnce(&mut HostContext<B>) + Send>;
+
+enum Message<B: HostBackend> {
+    Job(Job<B>),
+    Retain(Vec<i32>),
+    Release(Vec<i32>),
+    Shutdown
+}
+
+/// A `Send + Sync` handle which marshals requests to the host thread.
+pub struct HostHandle<B: HostBackend = UIAutomation> {
+    sender: Sender<Message<B>>
+}
+
+impl<B: HostBackend> HostHandle<B> {
+    /// Executes `request` on the host thread, and waits for its result.
+    pub fn execute<T, F>(&self, request: F) -> Result<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&mut HostContext<B>) -> Result<T> + Send + 'static
+    {
+        let (result_sender, result_receiver) = sync_channel(1);
+        let job: Job<B> = Box::new(move |ctx| {
+            let _ = result_sender.send(request(ctx));
+        });
+
+        if self.sender.send(Message::Job(job)).is_err() {
+            return Err(Error::new(ERR_INVALID_OBJECT, "automation host is stopped"));
+        }
+
+        match result_receiver.recv() {
+            Ok(result) => result,
+            Err(_) => Err(Error::new(ERR_INVALID_OBJECT, "automation host is stopped")),
+        }
+    }
+
+    /// Executes `request` on the host thread, and returns the created element as a proxy.
+    pub fn execute_element<F>(&self, request: F) -> Result<ElementProxy<B>>
+    where
+        F: FnOnce(&B) -> Result<B::Element> + Send + 'static
+    {
+        let runtime_id = self.execute(move |ctx| {
+            let element = request(ctx.backend())?;
+            ctx.register(element)
+        })?;
+
+        Ok(ElementProxy {
+            runtime_id,
+            host: self.clone()
+        })
+    }
+
+    fn post(&self, message: Message<B>) {
+        let _ = self.sender.send(message);
+    }
+}
+
+impl<B: HostBackend> Clone for HostHandle<B> {
+    fn clone(&self) -> Self {
+        Self {
+            sender: self.sender.clone()
+        }
+    }
+}
+
+impl<B: HostBackend> Debug for HostHandle<B> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("HostHandle").finish()
+    }
+}
+
+/// A `Send + Sync` proxy of an element which lives on the host thread.
+///
+/// The element is keyed by its runtime id, and is released from the host when all the proxies are dropped.
+pub struct ElementProxy<B: HostBackend = UIAutomation> {
+    runtime_id: Vec<i32>,
+    host: HostHandle<B>
+}
+
+impl<B: HostBackend> ElementProxy<B> {
+    /// Retrieves the runtime id of the proxied element.
+    pub fn get_runtime_id(&self) -> &[i32] {
+        &self.runtime_id
+    }
+
+    /// Retrieves the handle of the host thread.
+    pub fn host(&self) -> &HostHandle<B> {
+        &self.host
+    }
+
+    /// Executes `request` with the proxied element on the host thread.
+    pub fn execute<T, F>(&self, request: F) -> Result<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&B, &B::Element) -> Result<T> + Send + 'static
+    {
+        let runtime_id = self.runtime_id.clone();
+        self.host.execute(move |ctx| {
+            let element = ctx.element(&runtime_id)?;
+            request(ctx.backend(), element)
+        })
+    }
+
+    /// Executes `request` with the proxied element on the host thread, and returns the created element as a proxy.
+    pub fn execute_element<F>(&self, request: F) -> Result<ElementProxy<B>>
+    where
+        F: FnOnce(&B, &B::Element) -> Result<B::Element> + Send + 'static
+    {
+        let runtime_id = self.runtime_id.clone();
+        let runtime_id = self.host.execute(move |ctx| {
+            let element = request(ctx.backend(), ctx.element(&runtime_id)?)?;
+            ctx.register(element)
+        })?;
+
+        Ok(ElementProxy {
+            runtime_id,
+            host: self.host.clone()
+        })
+    }
+}
+
+impl ElementProxy<UIAutomation> {
+    /// Retrieves the name of the element.
+    pub fn get_name(&self) -> Result<String> {
+        self.execute(|_, e| e.get_name())
+    }
+
+    /// Retrieves the class name of the element.
+    pub fn get_classname(&self) -> Result<String> {
+        self.execute(|_, e| e.get_classname())
+    }
+
+    /// Retrieves the Microsoft UI Automation identifier of the element.
+    pub fn get_automation_id(&self) -> Result<String> {
+        self.execute(|_, e| e.get_automation_id())
+    }
+
+    /// Simulates mouse left click event on the element.
+    pub fn click(&self) -> Result<()> {
+        self.execute(|_, e| e.click())
+    }
+
+    /// Simulates typing `keys` on the element. See `UIElement::send_keys()`.
+    pub fn send_keys(&self, keys: &str, interval: u64) -> Result<()> {
+        let keys = keys.to_string();
+        self.execute(move |_, e| e.send_keys(&keys, interval))
+    }
+}
+
+impl<B: HostBackend> Clone for ElementProxy<B> {
+    fn clone(&self) -> Self {
+        self.host.post(Message::Retain(self.runtime_id.clone()));
+        Self {
+            runtime_id: self.runtime_id.clone(),
+            host: self.host.clone()
+        }
+    }
+}
+
+impl<B: HostBackend> Drop for ElementProxy<B> {
+    fn drop(&mut self) {
+        self.host.post(Message::Release(std::mem::take(&mut self.runtime_id)));
+    }
+}
+
+impl<B: HostBackend> Debug for ElementProxy<B> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ElementProxy").field("runtime_id", &self.runtime_id).finish()
+    }
+}
+
+/// Owns a dedicated thread which initializes the COM library and runs all the automation requests.
+///
+/// `UIElement` is tied to the COM apartment of the thread which created it.
+/// `AutomationHost` keeps the elements on its own thread, and exposes `Send + Sync` handles that marshal requests to it.
+///
+/// # Examples
+///
+/// ```
+/// use uiautomation::hosts::AutomationHost;
+///
+/// let host = AutomationHost::new().unwrap();
+/// let root = host.get_root_element().unwrap();
+/// let name = std::thread::spawn(move || root.get_name()).join().unwrap();
+/// assert!(name.is_ok());
+/// ```
+pub struct AutomationHost<B: HostBackend = UIAutomation> {
+    handle: HostHandle<B>,
+    thread: Option<JoinHandle<()>>
+}
+
+impl<B: HostBackend> AutomationHost<B> {
+    /// Creates a host whose backend is created by `factory` on the host thread. The COM library is not initialized by the host.
+    pub fn with_backend<F>(factory: F) -> Result<Self> where F: FnOnce() -> Result<B> + Send + 'static {
+        Self::start(factory, None)
+    }
+
+    fn start<F>(factory: F, apartment: Option<ComApartment>) -> Result<Self> where F: FnOnce() -> Result<B> + Send + 'static {
+        let (sender, receiver) = channel::<Message<B>>();
+        let (init_sender, init_receiver) = sync_channel::<Result<()>>(1);
+
+        let thread = spawn(move || {
+            if let Some(apartment) = apartment {
+                let result = unsafe {
+                    CoInitializeEx(None, apartment.into())
+                };
+                if result.is_err() {
+                    let _ = init_sender.send(Err(result.into()));
+                    return;
+                }
+            }
+
+            match factory() {
+                Ok(backend) => {
+                    let _ = init_sender.send(Ok(()));
+                    Self::serve(backend, receiver);
+                },
+                Err(e) => {
+                    let _ = init_sender.send(Err(e));
+                }
+            }
+
+            if apartment.is_some() {
+                unsafe { CoUninitialize() };
+            }
+        });
+
+        match init_receiver.recv() {
+            Ok(Ok(_)) => Ok(Self {
+                handle: HostHandle { sender },
+                thread: Some(thread)
+            }),
+            Ok(Err(e)) => {
+                let _ = thread.join();
+                Err(e)
+            },
+            Err(_) => Err(Error::new(ERR_INVALID_OBJECT, "automation host is stopped")),
+        }
+    }
+
+    fn serve(backend: B, receiver: std::sync::mpsc::Receiver<Message<B>>) {
+        let mut ctx = HostContext {
+            backend,
+            registry: ElementRegistry::new()
+        };
+
+        while let Ok(message) = receiver.recv() {
+            match message {
+                Message::Job(job) => job(&mut ctx),
+                Message::Retain(runtime_id) => {
+                    ctx.registry.retain(&runtime_id);
+                },
+                Message::Release(runtime_id) => ctx.registry.release(&runtime_id),
+                Message::Shutdown => break,
+            }
+        }
+    }
+
+    /// Retrieves a `Send + Sync` handle of the host.
+    pub fn handle(&self) -> HostHandle<B> {
+        self.handle.clone()
+    }
+
+    /// Executes `request` on the host thread, and waits for its result.
+    pub fn execute<T, F>(&self, request: F) -> Result<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&mut HostContext<B>) -> Result<T> + Send + 'static
+    {
+        self.handle.execute(request)
+    }
+
+    /// Executes `request` on the host thread, and returns the created element as a proxy.
+    pub fn execute_element<F>(&self, request: F) -> Result<ElementProxy<B>>
+    where
+        F: FnOnce(&B) -> Result<B::Element> + Send + 'static
+    {
+        self.handle.execute_element(request)
+    }
+}
+
+impl AutomationHost<UIAutomation> {
+    /// Creates a host whose thread is initialized as `ComApartment::MultiThreaded`.
+    pub fn new() -> Result<Self> {
+        Self::with_apartment(ComApartment::MultiThreaded)
+    }
+
+    /// Creates a host whose thread initializes the COM library as `apartment`.
+    pub fn with_apartment(apartment: ComApartment) -> Result<Self> {
+        Self::start(UIAutomation::new_direct, Some(apartment))
+    }
+
+    /// Retrieves the UI Automation element that represents the desktop.
+    pub fn get_root_element(&self) -> Result<ElementProxy> {
+        self.execute_element(|automation| automation.get_root_element())
+    }
+
+    /// Retrieves the UI Automation element that has the input focus.
+    pub fn get_focused_element(&self) -> Result<ElementProxy> {
+        self.execute_element(|automation| automation.get_focused_element())
+    }
+
+    /// Retrieves a UI Automation element for the specified window.
+    pub fn element_from_handle(&self, hwnd: Handle) -> Result<ElementProxy> {
+        self.execute_element(move |automation| automation.element_from_handle(hwnd))
+    }
+
+    /// Retrieves the UI Automation element at the specified point on the desktop.
+    pub fn element_from_point(&self, point: Point) -> Result<ElementProxy> {
+        self.execute_element(move |automation| automation.element_from_point(point))
+    }
+}
+
+impl<B: HostBackend> Drop for AutomationHost<B> {
+    fn drop(&mut self) {
+        self.handle.post(Message::Shutdown);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+impl<B: HostBackend> Debug for AutomationHost<B> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AutomationHost").finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+    use std::thread;
+    use std::thread::ThreadId;
+
+    use crate::errors::ERR_INVALID_OBJECT;
+    use crate::errors::ERR_NOTFOUND;
+    use crate::errors::Error;
+    use crate::Result;
+
+    use super::AutomationHost;
+    use super::ElementProxy;
+    use super::ElementRegistry;
+    use super::HostBackend;
+    use super::HostHandle;
+
+    /// A backend which is not `Send`, like the COM objects.
+    struct FakeBackend {
+        thread: ThreadId,
+        _not_send: Rc<()>
+    }
+
+    #[derive(Debug, Clone)]
+    struct FakeElement {
+        id: i32,
+        _not_send: Rc<()>
+    }
+
+    impl HostBackend for FakeBackend {
+        type Element = FakeElement;
+
+        fn runtime_id(&self, element: &FakeElement) -> Result<Vec<i32>> {
+            Ok(vec![42, element.id])
+        }
+    }
+
+    fn fake_host() -> AutomationHost<FakeBackend> {
+        AutomationHost::with_backend(|| Ok(FakeBackend { thread: thread::current().id(), _not_send: Rc::new(()) })).unwrap()
+    }
+
+    fn create(host: &AutomationHost<FakeBackend>, id: i32) -> ElementProxy<FakeBackend> {
+        host.execute_element(move |_| Ok(FakeElement { id, _not_send: Rc::new(()) })).unwrap()
+    }
+
+    fn registered(host: &AutomationHost<FakeBackend>) -> usize {
+        host.execute(|ctx| Ok(ctx.registry().len())).unwrap()
+    }
+
+    #[test]
+    fn test_send_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<HostHandle<FakeBackend>>();
+        assert_send_sync::<ElementProxy<FakeBackend>>();
+    }
+
+    #[test]
+    fn test_dedicated_thread() {
+        let host = fake_host();
+        let host_thread = host.execute(|ctx| Ok(ctx.backend().thread)).unwrap();
+        assert_ne!(host_thread, thread::current().id());
+
+        let handle = host.handle();
+        let workers: Vec<_> = (0..4).map(|_| {
+            let handle = handle.clone();
+            thread::spawn(move || handle.execute(|ctx| Ok(thread::current().id() == ctx.backend().thread)).unwrap())
+        }).collect();
+        for worker in workers {
+            assert!(worker.join().unwrap());
+        }
+    }
+
+    #[test]
+    fn test_proxy_across_threads() {
+        let host = fake_host();
+        let proxy = create(&host, 7);
+        assert_eq!(proxy.get_runtime_id(), &[42, 7]);
+
+        let id = thread::spawn(move || proxy.execute(|_, e| Ok(e.id))).join().unwrap();
+        assert_eq!(id, Ok(7));
+    }
+
+    #[test]
+    fn test_registry_refcount() {
+        let host = fake_host();
+        let first = create(&host, 1);
+        let same = create(&host, 1);
+        let other = first.clone();
+        let child = first.execute_element(|_, e| Ok(FakeElement { id: e.id + 1, _not_send: Rc::new(()) })).unwrap();
+        assert_eq!(registered(&host), 2);
+
+        drop(first);
+        drop(same);
+        assert_eq!(registered(&host), 2);
+
+        drop(other);
+        assert_eq!(registered(&host), 1);
+        drop(child);
+        assert_eq!(registered(&host), 0);
+    }
+
+    #[test]
+    fn test_released_element() {
+        let host = fake_host();
+        let proxy = create(&host, 1);
+        let id = proxy.get_runtime_id().to_vec();
+        drop(proxy);
+
+        let ret = host.execute(move |ctx| ctx.element(&id).map(|e| e.id));
+        assert_eq!(ret.unwrap_err().code(), ERR_NOTFOUND);
+    }
+
+    #[test]
+    fn test_factory_error() {
+        let ret = AutomationHost::<FakeBackend>::with_backend(|| Err(Error::new(ERR_NOTFOUND, "no backend")));
+        assert_eq!(ret.unwrap_err().code(), ERR_NOTFOUND);
+    }
+
+    #[test]
+    fn test_stopped_host() {
+        let host = fake_host();
+        let handle = host.handle();
+        drop(host);
+
+        let ret = handle.execute(|_| Ok(()));
+        assert_eq!(ret.unwrap_err().code(), ERR_INVALID_OBJECT);
+    }
+
+    #[test]
+    fn test_registry() {
+        let mut registry = ElementRegistry::new();
+        registry.register(vec![1], "a");
+        registry.register(vec![1], "b");
+        assert_eq!(registry.get(&[1]), Ok(&"a"));
+        assert!(registry.retain(&[1]));
+        assert!(!registry.retain(&[2]));
+
+        registry.release(&[1]);
+        registry.release(&[1]);
+        assert_eq!(registry.len(), 1);
+        registry.release(&[1]);
+        assert!(registry.is_empty());
+    }
+}
@@ -0,0 +1,596 @@
+use std::path::Path;
+
+use windows::Win32::Graphics::Gdi::BI_RGB;
+use windows::Win32::Graphics::Gdi::BITMAPINFO;
+use windows::Win32::Graphics::Gdi::BITMAPINFOHEADER;
+use windows::Win32::Graphics::Gdi::BitBlt;
+use windows::Win32::Graphics::Gdi::CAPTUREBLT;
+use windows::Win32::Graphics::Gdi::CreateCompatibleBitmap;
+use windows::Win32::Graphics::Gdi::CreateCompatibleDC;
+use windows::Win32::Graphics::Gdi::DIB_RGB_COLORS;
+use windows::Win32::Graphics::Gdi::DeleteDC;
+use windows::Win32::Graphics::Gdi::DeleteObject;
+use windows::Win32::Graphics::Gdi::GetDC;
+use windows::Win32::Graphics::Gdi::GetDIBits;
+use windows::Win32::Graphics::Gdi::ROP_CODE;
+use windows::Win32::Graphics::Gdi::ReleaseDC;
+use windows::Win32::Graphics::Gdi::SRCCOPY;
+use windows::Win32::Graphics::Gdi::SelectObject;
+use windows::Win32::UI::WindowsAndMessaging::GetSystemMetrics;
+use windows::Win32::UI::WindowsAndMessaging::SM_CXVIRTUALSCREEN;
+use windows::Win32::UI::WindowsAndMessaging::SM_CYVIRTUALSCREEN;
+use windows::Win32::UI::WindowsAndMessaging::SM_XVIRTUALSCREEN;
+use windows::Win32::UI::WindowsAndMessaging::SM_YVIRTUALSCREEN;
+
+use super::errors::ERR_FORMAT;
+use super::errors::ERR_NOTFOUND;
+use super::errors::Error;
+use super::errors::Result;
+use super::inputs::Mouse;
+use super::types::Point;
+use super::types::Rect;
+
+/// The template size at the coarse level of the search, which is downscaled from large templates.
+const COARSE_SIZE: u32 = 8;
+/// The max downscale factor of the coarse search.
+const MAX_SCALE: u32 = 4;
+/// The tolerance of the coarse search, which keeps more candidates to be refined.
+const COARSE_TOLERANCE: f64 = 0.25;
+
+/// An image in RGBA format, with 4 bytes per pixel.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Image {
+    width: u32,
+    height: u32,
+    pixels: Vec<u8>
+}
+
+impl Image {
+    /// Creates an image from the RGBA `pixels`, row by row from the top.
+    pub fn new(width: u32, height: u32, pixels: Vec<u8>) -> Result<Self> {
+        if pixels.len() != width as usize * height as usize * 4 {
+            return Err(Error::new(ERR_FORMAT, "the pixels do not match the size of image"));
+        }
+
+        Ok(Self {
+            width,
+            height,
+            pixels
+        })
+    }
+
+    /// Creates an image filled with `color`.
+    pub fn filled(width: u32, height: u32, color: [u8; 4]) -> Self {
+        Self {
+            width,
+            height,
+            pixels: color.repeat(width as usize * height as usize)
+        }
+    }
+
+    /// Retrieves the width.
+    pub fn get_width(&self) -> u32 {
+        self.width
+    }
+
+    /// Retrieves the height.
+    pub fn get_height(&self) -> u32 {
+        self.height
+    }
+
+    /// Retrieves the RGBA pixels.
+    pub fn get_pixels(&self) -> &[u8] {
+        &self.pixels
+    }
+
+    /// Retrieves the RGBA color of the pixel at (`x`, `y`).
+    pub fn get_pixel(&self, x: u32, y: u32) -> [u8; 4] {
+        let offset = self.offset(x, y);
+        [self.pixels[offset], self.pixels[offset + 1], self.pixels[offset + 2], self.pixels[offset + 3]]
+    }
+
+    /// Sets the RGBA color of the pixel at (`x`, `y`).
+    pub fn set_pixel(&mut self, x: u32, y: u32, color: [u8; 4]) {
+        let offset = self.offset(x, y);
+        self.pixels[offset..offset + 4].copy_from_slice(&color);
+    }
+
+    fn offset(&self, x: u32, y: u32) -> usize {
+        assert!(x < self.width && y < self.height, "the pixel ({}, {}) is out of the image", x, y);
+        (y as usize * self.width as usize + x as usize) * 4
+    }
+
+    /// Copies the area at (`x`, `y`) of `width` and `height`, clipped by the image.
+    pub fn crop(&self, x: u32, y: u32, width: u32, height: u32) -> Self {
+        let x = x.min(self.width);
+        let y = y.min(self.height);
+        let width = width.min(self.width - x);
+        let height = height.min(self.height - y);
+
+        let mut pixels: Vec<u8> = Vec::with_capacity(width as usize * height as usize * 4);
+        for row in y..y + height {
+            let start = self.offset_unchecked(x, row);
+            pixels.extend_from_slice(&self.pixels[start..start + width as usize * 4]);
+        }
+
+        Self {
+            width,
+            height,
+            pixels
+        }
+    }
+
+    fn offset_unchecked(&self, x: u32, y: u32) -> usize {
+        (y as usize * self.width as usize + x as usize) * 4
+    }
+
+    /// Decodes an image from PNG data.
+    pub fn from_png(data: &[u8]) -> Result<Self> {
+        let format_error = |e: png::DecodingError| Error::new(ERR_FORMAT, &format!("invalid png: {}", e));
+
+        let mut decoder = png::Decoder::new(data);
+        decoder.set_transformations(png::Transformations::normalize_to_color8());
+        let mut reader = decoder.read_info().map_err(format_error)?;
+        let mut buffer = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut buffer).map_err(format_error)?;
+        buffer.truncate(info.buffer_size());
+
+        let pixels = match info.color_type {
+            png::ColorType::Rgba => buffer,
+            png::ColorType::Rgb => buffer.chunks_exact(3).flat_map(|p| [p[0], p[1], p[2], 255]).collect(),
+            png::ColorType::GrayscaleAlpha => buffer.chunks_exact(2).flat_map(|p| [p[0], p[0], p[0], p[1]]).collect(),
+            png::ColorType::Grayscale => buffer.iter().flat_map(|g| [*g, *g, *g, 255]).collect(),
+            png::ColorType::Indexed => return Err(Error::new(ERR_FORMAT, "unexpanded indexed png")),
+        };
+
+        Self::new(info.width, info.height, pixels)
+    }
+
+    /// Loads an image from a PNG file.
+    pub fn load_png<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let data = std::fs::read(path).map_err(|e| Error::from(e.to_string()))?;
+        Self::from_png(&data)
+    }
+
+    /// Encodes the image as PNG data.
+    pub fn to_png(&self) -> Result<Vec<u8>> {
+        let format_error = |e: png::EncodingError| Error::new(ERR_FORMAT, &format!("failed to encode png: {}", e));
+
+        let mut data: Vec<u8> = Vec::new();
+        {
+            let mut encoder = png::Encoder::new(&mut data, self.width, self.height);
+            encoder.set_color(png::ColorType::Rgba);
+            encoder.set_depth(png::BitDepth::Eight);
+            let mut writer = encoder.write_header().map_err(format_error)?;
+            writer.write_image_data(&self.pixels).map_err(format_error)?;
+        }
+        Ok(data)
+    }
+
+    /// Saves the image as a PNG file.
+    pub fn save_png<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let data = self.to_png()?;
+        std::fs::write(path, data).map_err(|e| Error::from(e.to_string()))
+    }
+
+    /// Captures the screen in `region`, or the whole virtual screen if `region` is `None`.
+    pub fn capture_screen(region: Option<Rect>) -> Result<Self> {
+        let region = region.unwrap_or_else(get_virtual_screen);
+        let width = region.get_width();
+        let height = region.get_height();
+        if width <= 0 || height <= 0 {
+            return Err(Error::new(ERR_FORMAT, "the region to capture is empty"));
+        }
+
+        let mut pixels: Vec<u8> = vec![0; width as usize * height as usize * 4];
+        unsafe {
+            let screen = GetDC(None);
+            let memory = CreateCompatibleDC(screen);
+            let bitmap = CreateCompatibleBitmap(screen, width, height);
+            let old = SelectObject(memory, bitmap);
+
+            let copied = BitBlt(memory, 0, 0, width, height, screen, region.get_left(), region.get_top(), ROP_CODE(SRCCOPY.0 | CAPTUREBLT.0));
+
+            let mut info = BITMAPINFO {
+                bmiHeader: BITMAPINFOHEADER {
+                    biSize: std::mem::size_of::<BITMAPINFOHEADER>() as u32,
+                    biWidth: width,
+                    // a negative height means a top-down bitmap.
+                    biHeight: -height,
+                    biPlanes: 1,
+                    biBitCount: 32,
+                    biCompression: BI_RGB.0,
+                    ..Default::default()
+                },
+                ..Default::default()
+            };
+            let lines = if copied.is_ok() {
+                GetDIBits(memory, bitmap, 0, height as u32, Some(pixels.as_mut_ptr() as *mut _), &mut info, DIB_RGB_COLORS)
+            } else {
+                0
+            };
+
+            SelectObject(memory, old);
+            let _ = DeleteObject(bitmap);
+            let _ = DeleteDC(memory);
+            ReleaseDC(None, screen);
+
+            copied?;
+            if lines != height {
+                return Err(Error::last_os_error());
+            }
+        }
+
+        // BGRA to RGBA, and the alpha of screen pixels is meaningless.
+        for pixel in pixels.chunks_exact_mut(4) {
+            pixel.swap(0, 2);
+            pixel[3] = 255;
+        }
+
+        Self::new(width as u32, height as u32, pixels)
+    }
+
+    /// Converts the image to integer gray levels, ignoring the alpha channel.
+    fn to_gray(&self) -> Vec<u8> {
+        self.pixels.chunks_exact(4)
+            .map(|p| ((299 * p[0] as u32 + 587 * p[1] as u32 + 114 * p[2] as u32 + 500) / 1000) as u8)
+            .collect()
+    }
+}
+
+/// Retrieves the bounding rectangle of all the monitors.
+fn get_virtual_screen() -> Rect {
+    unsafe {
+        let left = GetSystemMetrics(SM_XVIRTUALSCREEN);
+        let top = GetSystemMetrics(SM_YVIRTUALSCREEN);
+        let width = GetSystemMetrics(SM_CXVIRTUALSCREEN);
+        let height = GetSystemMetrics(SM_CYVIRTUALSCREEN);
+        Rect::new(left, top, left + width - 1, top + height - 1)
+    }
+}
+
+/// A gray image with the integral tables of its values and squared values, to sum any window in constant time.
+///
+/// The gray levels are integers, so the tables are exact and take 17 bytes per pixel in total.
+struct GrayImage {
+    width: usize,
+    height: usize,
+    values: Vec<u8>,
+    sums: Vec<u64>,
+    squares: Vec<u64>
+}
+
+impl GrayImage {
+    fn new(width: usize, height: usize, values: Vec<u8>) -> Self {
+        let stride = width + 1;
+        let mut sums = vec![0; stride * (height + 1)];
+        let mut squares = vec![0; stride * (height + 1)];
+        for y in 0..height {
+            let mut row_sum = 0;
+            let mut row_square = 0;
+            for x in 0..width {
+                let v = values[y * width + x] as u64;
+                row_sum += v;
+                row_square += v * v;
+                sums[(y + 1) * stride + x + 1] = sums[y * stride + x + 1] + row_sum;
+                squares[(y + 1) * stride + x + 1] = squares[y * stride + x + 1] + row_square;
+            }
+        }
+
+        Self {
+            width,
+            height,
+            values,
+            sums,
+            squares
+        }
+    }
+
+    fn from_image(image: &Image) -> Self {
+        Self::new(image.width as usize, image.height as usize, image.to_gray())
+    }
+
+    /// Downscales the image by averaging `scale` x `scale` blocks, rounded to integer gray levels.
+    fn downscale(&self, scale: usize) -> Self {
+        let width = self.width / scale;
+        let height = self.height / scale;
+        let area = (scale * scale) as u64;
+        let values = (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .map(|(x, y)| ((self.window_sums(x * scale, y * scale, scale, scale).0 + area / 2) / area) as u8)
+            .collect();
+        Self::new(width, height, values)
+    }
+
+    /// Sums the values and the squared values in the window.
+    fn window_sums(&self, x: usize, y: usize, width: usize, height: usize) -> (u64, u64) {
+        let stride = self.width + 1;
+        // adds before subtracting, because the tables are unsigned.
+        let sum = |table: &[u64]| {
+            table[(y + height) * stride + x + width] + table[y * stride + x] - table[y * stride + x + width] - table[(y + height) * stride + x]
+        };
+        (sum(&self.sums), sum(&self.squares))
+    }
+
+    /// Calculates the normalized cross-correlation between `template` and the window at (`x`, `y`), in `[-1.0, 1.0]`.
+    fn correlate(&self, template: &Template, x: usize, y: usize) -> f64 {
+        let n = (template.width * template.height) as f64;
+        let (sum, square) = self.window_sums(x, y, template.width, template.height);
+        let (sum, square) = (sum as f64, square as f64);
+        let variance = (square - sum * sum / n).max(0.0);
+
+        if template.variance <= f64::EPSILON || variance <= f64::EPSILON {
+            // flat areas match when both are flat with the same brightness.
+            let same = template.variance <= f64::EPSILON && variance <= f64::EPSILON && (sum / n - template.mean).abs() < 1.0;
+            return if same { 1.0 } else { 0.0 };
+        }
+
+        let mut product = 0.0;
+        for ty in 0..template.height {
+            let row = (y + ty) * self.width + x;
+            let values = &self.values[row..row + template.width];
+            let deviations = &template.deviations[ty * template.width..(ty + 1) * template.width];
+            product += values.iter().zip(deviations).map(|(v, d)| *v as f64 * d).sum::<f64>();
+        }
+
+        product / (variance * template.variance).sqrt()
+    }
+}
+
+/// A gray template with the deviations from its mean.
+struct Template {
+    width: usize,
+    height: usize,
+    mean: f64,
+    deviations: Vec<f64>,
+    variance: f64
+}
+
+impl Template {
+    fn new(image: &GrayImage) -> Self {
+        let n = image.values.len() as f64;
+        let mean = image.values.iter().map(|v| *v as u64).sum::<u64>() as f64 / n;
+        let deviations: Vec<f64> = image.values.iter().map(|v| *v as f64 - mean).collect();
+        let variance = deviations.iter().map(|d| d * d).sum();
+
+        Self {
+            width: image.width,
+            height: image.height,
+            mean,
+            deviations,
+            variance
+        }
+    }
+}
+
+/// A location of the template found in an image.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ImageMatch {
+    /// The matched area in the image.
+    pub rect: Rect,
+    /// The normalized cross-correlation score in `[-1.0, 1.0]`.
+    pub score: f64
+}
+
+/// Finds the locations of `template` in `image` with the normalized cross-correlation score not less than `threshold`,
+/// such as `0.9`. The matches are sorted by score from high to low, and the overlapped matches are removed.
+///
+/// The images are compared in gray levels, ignoring the alpha channel. Large templates are searched on downscaled images
+/// first, then refined at full size.
+pub fn find_image(image: &Image, template: &Image, threshold: f64) -> Vec<ImageMatch> {
+    if template.width == 0 || template.height == 0 || template.width > image.width || template.height > image.height {
+        return Vec::new();
+    }
+
+    let gray = GrayImage::from_image(image);
+    let pattern = GrayImage::from_image(template);
+    let full = Template::new(&pattern);
+
+    let scale = (template.width.min(template.height) / COARSE_SIZE).clamp(1, MAX_SCALE) as usize;
+    let max_x = gray.width - full.width;
+    let max_y = gray.height - full.height;
+
+    let mut candidates: Vec<(usize, usize, f64)> = Vec::new();
+    if scale == 1 {
+        for y in 0..=max_y {
+            for x in 0..=max_x {
+                let score = gray.correlate(&full, x, y);
+                if score >= threshold {
+                    candidates.push((x, y, score));
+                }
+            }
+        }
+    } else {
+        let coarse = gray.downscale(scale);
+        let coarse_template = Template::new(&pattern.downscale(scale));
+        let mut refined: Vec<bool> = vec![false; (max_x + 1) * (max_y + 1)];
+        for cy in 0..=coarse.height - coarse_template.height {
+            for cx in 0..=coarse.width - coarse_template.width {
+                if coarse.correlate(&coarse_template, cx, cy) < threshold - COARSE_TOLERANCE {
+                    continue;
+                }
+
+                // refines around the coarse location at full size.
+                for y in (cy * scale).saturating_sub(scale)..=((cy + 1) * scale).min(max_y) {
+                    for x in (cx * scale).saturating_sub(scale)..=((cx + 1) * scale).min(max_x) {
+                        let index = y * (max_x + 1) + x;
+                        if refined[index] {
+                            continue;
+                        }
+                        refined[index] = true;
+
+                        let score = gray.correlate(&full, x, y);
+                        if score >= threshold {
+                            candidates.push((x, y, score));
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    suppress_overlaps(candidates, full.width, full.height)
+}
+
+/// Keeps the best scored candidates, removing the ones which overlap them.
+fn suppress_overlaps(mut candidates: Vec<(usize, usize, f64)>, width: usize, height: usize) -> Vec<ImageMatch> {
+    candidates.sort_by(|a, b| b.2.total_cmp(&a.2).then(a.1.cmp(&b.1)).then(a.0.cmp(&b.0)));
+
+    let mut matches: Vec<ImageMatch> = Vec::new();
+    for (x, y, score) in candidates {
+        let overlapped = matches.iter().any(|m| {
+            (m.rect.get_left() as usize).abs_diff(x) < width && (m.rect.get_top() as usize).abs_diff(y) < height
+        });
+        if !overlapped {
+            let rect = Rect::new(x as i32, y as i32, (x + width) as i32 - 1, (y + height) as i32 - 1);
+            matches.push(ImageMatch { rect, score });
+        }
+    }
+    matches
+}
+
+/// Locates `template` on the screen in `region`, or the whole virtual screen if `region` is `None`.
+///
+/// Returns the matched areas in screen coordinates, from the best to the worst.
+///
+/// # Examples
+/// ```no_run
+/// use uiautomation::images::Image;
+/// use uiautomation::images::locate_image;
+///
+/// let template = Image::load_png("button.png").unwrap();
+/// let rects = locate_image(&template, None, 0.9).unwrap();
+/// ```
+pub fn locate_image(template: &Image, region: Option<Rect>, threshold: f64) -> Result<Vec<Rect>> {
+    let region = region.unwrap_or_else(get_virtual_screen);
+    let screen = Image::capture_screen(Some(region))?;
+
+    let rects = find_image(&screen, template, threshold).into_iter().map(|m| to_screen_rect(&m.rect, &region)).collect();
+    Ok(rects)
+}
+
+/// Maps `rect` in the image captured from `region` to screen coordinates.
+fn to_screen_rect(rect: &Rect, region: &Rect) -> Rect {
+    Rect::new(rect.get_left() + region.get_left(), rect.get_top() + region.get_top(), rect.get_right() + region.get_left(), rect.get_bottom() + region.get_top())
+}
+
+/// Clicks the center of the best match of `template` on the screen, and returns the matched area.
+pub fn click_image(template: &Image, region: Option<Rect>, threshold: f64) -> Result<Rect> {
+    let rects = locate_image(template, region, threshold)?;
+    let Some(rect) = rects.first() else {
+        return Err(Error::new(ERR_NOTFOUND, "can not find the image on screen"));
+    };
+
+    let center = Point::new((rect.get_left() + rect.get_right()) / 2, (rect.get_top() + rect.get_bottom()) / 2);
+    Mouse::new().click(center)?;
+    Ok(*rect)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::types::Rect;
+
+    use super::Image;
+    use super::find_image;
+    use super::to_screen_rect;
+
+    /// Creates a synthetic image with a gradient background.
+    fn background(width: u32, height: u32) -> Image {
+        let mut image = Image::filled(width, height, [0, 0, 0, 255]);
+        for y in 0..height {
+            for x in 0..width {
+                let v = ((x * 7 + y * 3) % 64) as u8 + 32;
+                image.set_pixel(x, y, [v, v, v, 255]);
+            }
+        }
+        image
+    }
+
+    /// Creates a synthetic icon with a cross and a border.
+    fn icon(size: u32, color: [u8; 4]) -> Image {
+        let mut image = Image::filled(size, size, [255, 255, 255, 255]);
+        for i in 0..size {
+            image.set_pixel(i, size / 2, color);
+            image.set_pixel(size / 2, i, color);
+            image.set_pixel(i, 0, [0, 0, 0, 255]);
+            image.set_pixel(0, i, [0, 0, 0, 255]);
+        }
+        image
+    }
+
+    fn paste(image: &mut Image, source: &Image, left: u32, top: u32) {
+        for y in 0..source.get_height() {
+            for x in 0..source.get_width() {
+                image.set_pixel(left + x, top + y, source.get_pixel(x, y));
+            }
+        }
+    }
+
+    #[test]
+    fn test_png() {
+        let image = icon(9, [200, 10, 10, 255]);
+        let data = image.to_png().unwrap();
+        assert_eq!(&data[1..4], b"PNG");
+        assert_eq!(Image::from_png(&data).unwrap(), image);
+        assert!(Image::from_png(b"not a png").is_err());
+        assert!(Image::new(2, 2, vec![0; 15]).is_err());
+    }
+
+    #[test]
+    fn test_crop() {
+        let image = icon(9, [200, 10, 10, 255]);
+        let part = image.crop(4, 0, 10, 2);
+        assert_eq!((part.get_width(), part.get_height()), (5, 2));
+        assert_eq!(part.get_pixel(0, 1), [200, 10, 10, 255]);
+    }
+
+    #[test]
+    fn test_find_small() {
+        let mut image = background(60, 40);
+        let template = icon(7, [200, 10, 10, 255]);
+        paste(&mut image, &template, 13, 21);
+
+        let data = image.to_png().unwrap();
+        let image = Image::from_png(&data).unwrap();
+        let matches = find_image(&image, &template, 0.9);
+        assert_eq!(matches.len(), 1);
+        assert_eq!((matches[0].rect.get_left(), matches[0].rect.get_top()), (13, 21));
+        assert_eq!((matches[0].rect.get_right(), matches[0].rect.get_bottom()), (19, 27));
+        assert!(matches[0].score > 0.99);
+    }
+
+    #[test]
+    fn test_find_large() {
+        let mut image = background(200, 120);
+        let template = icon(33, [10, 10, 200, 255]);
+        paste(&mut image, &template, 101, 57);
+        paste(&mut image, &template, 7, 3);
+
+        let matches = find_image(&image, &template, 0.9);
+        let mut positions: Vec<(i32, i32)> = matches.iter().map(|m| (m.rect.get_left(), m.rect.get_top())).collect();
+        positions.sort();
+        assert_eq!(positions, vec![(7, 3), (101, 57)]);
+    }
+
+    #[test]
+    fn test_not_found() {
+        let image = background(60, 40);
+        let template = icon(7, [200, 10, 10, 255]);
+        assert!(find_image(&image, &template, 0.9).is_empty());
+        assert!(find_image(&template, &image, 0.1).is_empty());
+
+        // flat areas match only the same brightness.
+        let flat = Image::filled(30, 30, [100, 100, 100, 255]);
+        assert_eq!(find_image(&flat, &Image::filled(5, 5, [100, 100, 100, 255]), 0.9).len(), 36);
+        assert!(find_image(&flat, &Image::filled(5, 5, [10, 10, 10, 255]), 0.9).is_empty());
+    }
+
+    #[test]
+    fn test_screen_rect() {
+        let rect = Rect::new(13, 21, 19, 27);
+        assert_eq!(to_screen_rect(&rect, &Rect::new(0, 0, 1919, 1079)), rect);
+        assert_eq!(to_screen_rect(&rect, &Rect::new(100, 50, 400, 300)), Rect::new(113, 71, 119, 77));
+        // the monitors on the left or top of the primary one have negative coordinates.
+        assert_eq!(to_screen_rect(&rect, &Rect::new(-1920, -200, -1, 879)), Rect::new(-1907, -179, -1901, -173));
+    }
+}
@@ -2,7 +2,6 @@ use std::cmp::max;
 use std::cmp::min;
 use std::mem;
 use std::str::Chars;
-use std::thread::sleep;
 use std::time::Duration;
 
 use phf::phf_map;
@@ -18,6 +17,11 @@ use super::errors::ERR_FORMAT;
 use super::Error;
 use super::Result;
 use super::types::Point;
+use super::timers::CancellationToken;
+use super::timers::run_steps;
+use super::timers::sleep;
+#[cfg(feature = "async")]
+use super::timers::AsyncTimer;
 
 const VIRTUAL_KEYS: phf::Map<&'static str, VIRTUAL_KEY> = phf_map! {
     "CONTROL" => VK_CONTROL, "CTRL" => VK_CONTROL, "LCONTROL" => VK_LCONTROL, "LCTRL" => VK_LCONTROL, "RCONTROL" => VK_RCONTROL, "RCTRL" => VK_RCONTROL,
@@ -287,6 +291,30 @@ impl Input {
     }
 }
 
+/// Quotes the special characters `{` `}` `(` `)` in `text`, so that it is typed as it is by `Keyboard::send_keys()`.
+/// 
+/// # Examples
+/// 
+/// ```
+/// use uiautomation::inputs::escape_keys;
+/// 
+/// assert_eq!(escape_keys("f(x) = {x}"), "f{(}x{)} = {{}x{}}");
+/// ```
+pub fn escape_keys(text: &str) -> String {
+    let mut keys = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '{' | '}' | '(' | ')' => {
+                keys.push('{');
+                keys.push(ch);
+                keys.push('}');
+            },
+            _ => keys.push(ch),
+        }
+    }
+    keys
+}
+
 fn parse_input(expression: &str) -> Result<Vec<Input>> {
     let mut inputs: Vec<Input> = Vec::new();
 
@@ -384,7 +412,8 @@ fn read_group_items(expr: &mut Chars<'_>) -> Result<Vec<InputItem>> {
 #[derive(Debug, Default)]
 pub struct Keyboard {
     interval: u64,
-    holdkeys: Vec<VIRTUAL_KEY>
+    holdkeys: Vec<VIRTUAL_KEY>,
+    cancel: Option<CancellationToken>
 }
 
 impl Keyboard {
@@ -392,7 +421,8 @@ impl Keyboard {
     pub fn new() -> Self {
         Self {
             interval: 0,
-            holdkeys: Vec::new()
+            holdkeys: Vec::new(),
+            cancel: None
         }
     }
 
@@ -404,6 +434,12 @@ impl Keyboard {
         self
     }
 
+    /// Sets the token to cancel typing. The keys are checked between the steps, and an `ERR_CANCELLED` error occurs when the `token` is cancelled.
+    pub fn cancellation(mut self, token: CancellationToken) -> Self {
+        self.cancel = Some(token);
+        self
+    }
+
     /// Simulates typing `keys` on keyboard.
     /// 
     /// `{}` is used for some special keys. For example: `{ctrl}{alt}{delete}`, `{shift}{home}`.
@@ -412,14 +448,34 @@ impl Keyboard {
     /// 
     /// `{` `}` `(` `)` can be quoted by `{}`. For example: `{{}Hi,{(}rust!{)}{}}` types `{Hi,(rust)}`.
     pub fn send_keys(&self, keys: &str) -> Result<()> {
-        let inputs = parse_input(keys)?;
-        for ref input in inputs {
-            // self.send_keyboard(input)?;
+        let interval = Duration::from_millis(self.interval);
+        let batches = self.create_batches(keys)?.into_iter().map(|batch| (batch, interval));
+        send_batches(batches, self.cancel.as_ref(), send_input)
+    }
+
+    /// Simulates typing `keys` on keyboard, waiting between keys by `timer` without blocking the thread.
+    /// 
+    /// See `send_keys()` for the syntax of `keys`.
+    #[cfg(feature = "async")]
+    pub async fn send_keys_async<T: AsyncTimer>(&self, keys: &str, timer: &T) -> Result<()> {
+        let interval = Duration::from_millis(self.interval);
+        let batches = self.create_batches(keys)?.into_iter().map(|batch| (batch, interval));
+        send_batches_async(batches, self.cancel.as_ref(), timer, send_input).await
+    }
+
+    /// Splits `keys` into the input batches which are sent at once. Every key is sent separately when `interval` is set.
+    fn create_batches(&self, keys: &str) -> Result<Vec<Vec<INPUT>>> {
+        let mut batches: Vec<Vec<INPUT>> = Vec::new();
+        for ref input in parse_input(keys)? {
             let input_keys = input.create_inputs()?;
-            self.send_keyboard(&input_keys)?;
+            if self.interval == 0 {
+                batches.push(input_keys);
+            } else {
+                batches.extend(input_keys.into_iter().map(|key| vec![key]));
+            }
         }
 
-        Ok(())
+        Ok(batches)
     }
 
     /// Simulates starting to hold `keys` on keyboard. Only holdkeys are allowed.
@@ -446,7 +502,7 @@ impl Keyboard {
             holdkey_inputs.push(Input::create_virtual_key(*holdkey, KEYEVENTF_KEYDOWN));
         }
         // send_input(&holdkey_inputs.as_slice())?;
-        self.send_keyboard(&holdkey_inputs)?;
+        self.send_keyboard(&holdkey_inputs, self.cancel.as_ref())?;
 
         self.holdkeys.extend(holdkeys);
 
@@ -465,7 +521,8 @@ impl Keyboard {
             self.holdkeys.clear();
 
             // send_input(&holdkey_inputs.as_slice())
-            self.send_keyboard(&holdkey_inputs)
+            // the releasing is not cancellable, or the keys are left pressed.
+            self.send_keyboard(&holdkey_inputs, None)
         }
     }
 
@@ -485,26 +542,13 @@ impl Keyboard {
     //     }
     // }
 
-    fn send_keyboard(&self, input_keys: &[INPUT]) -> Result<()> {
+    fn send_keyboard(&self, input_keys: &[INPUT], cancel: Option<&CancellationToken>) -> Result<()> {
         // let input_keys = input.create_inputs()?;
         if self.interval == 0 {
             send_input(input_keys)
         } else {
-            for input_key in input_keys {
-                let input_key_slice: [INPUT; 1] = [input_key.clone()];
-                send_input(&input_key_slice)?;
-
-                self.wait();
-            }
-
-            Ok(())
-        }
-    }
-
-
-    fn wait(&self) {
-        if self.interval > 0 {
-            sleep(Duration::from_millis(self.interval));
+            let interval = Duration::from_millis(self.interval);
+            send_batches(input_keys.iter().map(|input_key| (vec![*input_key], interval)), cancel, send_input)
         }
     }
 }
@@ -530,7 +574,8 @@ pub struct Mouse {
     interval: u64,
     move_time: u64,
     auto_move: bool,
-    holdkeys: Vec<VIRTUAL_KEY>
+    holdkeys: Vec<VIRTUAL_KEY>,
+    cancel: Option<CancellationToken>
 }
 
 impl Default for Mouse {
@@ -539,7 +584,8 @@ impl Default for Mouse {
             interval: 100, 
             move_time: 500,
             auto_move: true,
-            holdkeys: Vec::new()
+            holdkeys: Vec::new(),
+            cancel: None
         }
     }
 }
@@ -570,6 +616,12 @@ impl Mouse {
         self
     }
 
+    /// Sets the token to cancel mouse moves and clicks. An `ERR_CANCELLED` error occurs when the `token` is cancelled.
+    pub fn cancellation(mut self, token: CancellationToken) -> Self {
+        self.cancel = Some(token);
+        self
+    }
+
     /// Sets the holdkeys when mouse clicks.
     /// 
     /// The holdkeys is quoted by `{}`. For example: `{Shift}`, `{Ctrl}{Alt}`.
@@ -631,36 +683,55 @@ impl Mouse {
     /// mouse.move_to(Point::new(1000,800)).unwrap();
     /// ```
     pub fn move_to(&self, target: Point) -> Result<()> {
-        let (width, height) = get_screen_size()?;
-        let x = min(max(0, target.get_x()), width);
-        let y = min(max(0, target.get_y()), height);
-        let target = Point::new(x, y);
+        let target = Self::clamp_to_screen(target)?;
         
         if self.move_time > 0 {
             let source = Self::get_cursor_pos()?;
-            let delta_x = target.get_x() - source.get_x();
-            let delta_y = target.get_y() - source.get_y();
-
-            let delta = max(delta_x.abs(), delta_y.abs());
-            let steps = delta / 20;
-            if steps > 1 {
-                let step_x = delta_x / steps;
-                let step_y = delta_y / steps;
-                let interval = Duration::from_millis(self.move_time / steps as u64);
-                for i in 1..steps {
-                    let pos = Point::new(
-                        source.get_x() + step_x * i, 
-                        source.get_y() + step_y * i
-                    );
-                    Self::set_cursor_pos(pos)?;
-                    sleep(interval);
+            let (steps, interval) = move_steps(source, target, self.move_time);
+            run_steps(steps, interval, self.cancel.as_ref(), Self::set_cursor_pos)?;
+        }
+
+        Self::set_cursor_pos(target)
+    }
+
+    /// Moves the cursor from current position to the `target` position, waiting between steps by `timer` without blocking the thread.
+    #[cfg(feature = "async")]
+    pub async fn move_to_async<T: AsyncTimer>(&self, target: Point, timer: &T) -> Result<()> {
+        let target = Self::clamp_to_screen(target)?;
+        
+        if self.move_time > 0 {
+            let source = Self::get_cursor_pos()?;
+            let (steps, interval) = move_steps(source, target, self.move_time);
+            for pos in steps {
+                if let Some(ref token) = self.cancel {
+                    token.check()?;
                 }
+                Self::set_cursor_pos(pos)?;
+                timer.delay(interval).await;
             }
         }
 
         Self::set_cursor_pos(target)
     }
 
+    /// Simulates a mouse click event, waiting by `timer` without blocking the thread.
+    #[cfg(feature = "async")]
+    pub async fn click_async<T: AsyncTimer>(&self, pos: Point, timer: &T) -> Result<()> {
+        if self.auto_move {
+            self.move_to_async(pos, timer).await?;
+        }
+
+        let steps = self.click_steps(pos, MOUSEEVENTF_LEFTDOWN, MOUSEEVENTF_LEFTUP, 1);
+        send_batches_async(steps, self.cancel.as_ref(), timer, send_input).await
+    }
+
+    fn clamp_to_screen(target: Point) -> Result<Point> {
+        let (width, height) = get_screen_size()?;
+        let x = min(max(0, target.get_x()), width);
+        let y = min(max(0, target.get_y()), height);
+        Ok(Point::new(x, y))
+    }
+
     /// Simulates a mouse click event.
     /// 
     /// # Examples
@@ -676,12 +747,8 @@ impl Mouse {
             self.move_to(pos)?;
         }
 
-        self.before_click()?;
-        self.mouse_event(pos.get_x(), pos.get_y(), MOUSEEVENTF_LEFTDOWN)?;
-        self.mouse_event(pos.get_x(), pos.get_y(), MOUSEEVENTF_LEFTUP)?;
-        self.after_click()?;
-
-        Ok(())
+        let steps = self.click_steps(pos, MOUSEEVENTF_LEFTDOWN, MOUSEEVENTF_LEFTUP, 1);
+        send_batches(steps, self.cancel.as_ref(), send_input)
     }
 
     /// Simulates a mouse double click event.
@@ -699,19 +766,8 @@ impl Mouse {
             self.move_to(pos)?;
         }
 
-        self.before_click()?;
-
-        self.mouse_event(pos.get_x(), pos.get_y(), MOUSEEVENTF_LEFTDOWN)?;
-        self.mouse_event(pos.get_x(), pos.get_y(), MOUSEEVENTF_LEFTUP)?;
-
-        sleep(Duration::from_millis(max(200, self.interval)));
-
-        self.mouse_event(pos.get_x(), pos.get_y(), MOUSEEVENTF_LEFTDOWN)?;
-        self.mouse_event(pos.get_x(), pos.get_y(), MOUSEEVENTF_LEFTUP)?;
-
-        self.after_click()?;
-
-        Ok(())
+        let steps = self.click_steps(pos, MOUSEEVENTF_LEFTDOWN, MOUSEEVENTF_LEFTUP, 2);
+        send_batches(steps, self.cancel.as_ref(), send_input)
     }
 
     /// Simulates a right mouse click event.
@@ -729,36 +785,34 @@ impl Mouse {
             self.move_to(pos)?;
         }
 
-        self.before_click()?;
-        self.mouse_event(pos.get_x(), pos.get_y(), MOUSEEVENTF_RIGHTDOWN)?;
-        self.mouse_event(pos.get_x(), pos.get_y(), MOUSEEVENTF_RIGHTUP)?;
-        self.after_click()?;
-
-        Ok(())
+        let steps = self.click_steps(pos, MOUSEEVENTF_RIGHTDOWN, MOUSEEVENTF_RIGHTUP, 1);
+        send_batches(steps, self.cancel.as_ref(), send_input)
     }
 
-    fn before_click(&self) -> Result<()> {
+    /// Creates the input steps to click `count` times at `pos` with the holdkeys, and the wait time after each step.
+    fn click_steps(&self, pos: Point, down: MOUSE_EVENT_FLAGS, up: MOUSE_EVENT_FLAGS, count: usize) -> Vec<(Vec<INPUT>, Duration)> {
+        let interval = Duration::from_millis(self.interval);
+        let mut steps: Vec<(Vec<INPUT>, Duration)> = Vec::new();
         for holdkey in &self.holdkeys {
-            let key_input = [Input::create_virtual_key(*holdkey, KEYEVENTF_KEYDOWN)];
-            send_input(&key_input)?;
-            self.wait();
+            steps.push((vec![Input::create_virtual_key(*holdkey, KEYEVENTF_KEYDOWN)], interval));
+        }
+        for i in 0..count {
+            if i > 0 {
+                if let Some(last) = steps.last_mut() {
+                    last.1 += Duration::from_millis(max(200, self.interval));
+                }
+            }
+            steps.push((vec![Self::create_mouse_input(pos.get_x(), pos.get_y(), down)], interval));
+            steps.push((vec![Self::create_mouse_input(pos.get_x(), pos.get_y(), up)], interval));
         }
-
-        Ok(())
-    }
-
-    fn after_click(&self) -> Result<()> {
         for holdkey in &self.holdkeys {
-            let key_input = [Input::create_virtual_key(*holdkey, KEYEVENTF_KEYUP)];
-            send_input(&key_input)?;
-            self.wait();
+            steps.push((vec![Input::create_virtual_key(*holdkey, KEYEVENTF_KEYUP)], interval));
         }
-
-        Ok(())
+        steps
     }
 
-    fn mouse_event(&self, x: i32, y: i32, flags: MOUSE_EVENT_FLAGS) -> Result<()> {
-        let input = [INPUT {
+    fn create_mouse_input(x: i32, y: i32, flags: MOUSE_EVENT_FLAGS) -> INPUT {
+        INPUT {
             r#type: INPUT_MOUSE,
             Anonymous: INPUT_0 { 
                 mi: MOUSEINPUT { 
@@ -770,17 +824,29 @@ impl Mouse {
                     dwExtraInfo: 0 
                 }
             }
-        }];
-        send_input(&input)?;
-        self.wait();
-
-        Ok(())
+        }
     }
+}
 
-    fn wait(&self) {
-        if self.interval > 0 {
-            sleep(Duration::from_millis(self.interval));
-        }
+/// Calculates the intermediate cursor positions moving from `source` to `target` in `move_time` milliseconds, 
+/// and the interval between them.
+fn move_steps(source: Point, target: Point, move_time: u64) -> (Vec<Point>, Duration) {
+    let delta_x = target.get_x() - source.get_x();
+    let delta_y = target.get_y() - source.get_y();
+
+    let delta = max(delta_x.abs(), delta_y.abs());
+    let steps = delta / 20;
+    if steps > 1 {
+        let step_x = delta_x / steps;
+        let step_y = delta_y / steps;
+        let interval = Duration::from_millis(move_time / steps as u64);
+        let points = (1..steps).map(|i| Point::new(
+            source.get_x() + step_x * i, 
+            source.get_y() + step_y * i
+        )).collect();
+        (points, interval)
+    } else {
+        (Vec::new(), Duration::ZERO)
     }
 }
 
@@ -811,15 +877,152 @@ fn send_input(inputs: &[INPUT]) -> Result<()> {
     }
 }
 
+/// The release inputs of the keys and buttons which have been pressed but not released yet.
+#[derive(Default)]
+struct PressedInputs {
+    releases: Vec<INPUT>
+}
+
+impl PressedInputs {
+    /// Records the presses and releases in `inputs`, which have been sent.
+    fn track(&mut self, inputs: &[INPUT]) {
+        for input in inputs {
+            let Some((release, pressed)) = get_release(input) else {
+                continue;
+            };
+
+            if pressed {
+                self.releases.push(release);
+            } else if let Some(index) = self.releases.iter().rposition(|r| is_same_release(r, &release)) {
+                self.releases.remove(index);
+            }
+        }
+    }
+
+    /// Releases all the pressed keys and buttons by `send`, in the reverse order of pressing.
+    fn release<F: FnMut(&[INPUT]) -> Result<()>>(self, mut send: F) {
+        if !self.releases.is_empty() {
+            let releases: Vec<INPUT> = self.releases.into_iter().rev().collect();
+            let _ = send(&releases);
+        }
+    }
+}
+
+/// Retrieves the input which releases the key or button of `input`, and whether `input` presses it.
+fn get_release(input: &INPUT) -> Option<(INPUT, bool)> {
+    let mut release = *input;
+    unsafe {
+        if input.r#type == INPUT_KEYBOARD {
+            let pressed = !input.Anonymous.ki.dwFlags.contains(KEYEVENTF_KEYUP);
+            release.Anonymous.ki.dwFlags |= KEYEVENTF_KEYUP;
+            Some((release, pressed))
+        } else if input.r#type == INPUT_MOUSE {
+            let (up, pressed) = match input.Anonymous.mi.dwFlags {
+                MOUSEEVENTF_LEFTDOWN => (MOUSEEVENTF_LEFTUP, true),
+                MOUSEEVENTF_RIGHTDOWN => (MOUSEEVENTF_RIGHTUP, true),
+                MOUSEEVENTF_MIDDLEDOWN => (MOUSEEVENTF_MIDDLEUP, true),
+                flags @ (MOUSEEVENTF_LEFTUP | MOUSEEVENTF_RIGHTUP | MOUSEEVENTF_MIDDLEUP) => (flags, false),
+                _ => return None,
+            };
+            release.Anonymous.mi.dwFlags = up;
+            Some((release, pressed))
+        } else {
+            None
+        }
+    }
+}
+
+fn is_same_release(a: &INPUT, b: &INPUT) -> bool {
+    unsafe {
+        a.r#type == b.r#type && if a.r#type == INPUT_KEYBOARD {
+            a.Anonymous.ki.wVk == b.Anonymous.ki.wVk && a.Anonymous.ki.wScan == b.Anonymous.ki.wScan
+        } else {
+            a.Anonymous.mi.dwFlags == b.Anonymous.mi.dwFlags
+        }
+    }
+}
+
+/// Sends the input batches by `send`, waiting the duration after each batch. The token is checked between the batches.
+///
+/// When the batches stop early by cancellation or an error, the keys and buttons which have been pressed are released,
+/// so that none of them is left pressed.
+fn send_batches<I, F>(batches: I, cancel: Option<&CancellationToken>, mut send: F) -> Result<()>
+where
+    I: IntoIterator<Item = (Vec<INPUT>, Duration)>,
+    F: FnMut(&[INPUT]) -> Result<()>
+{
+    let mut pressed = PressedInputs::default();
+    let ret = run_steps(batches, Duration::ZERO, cancel, |(batch, wait)| {
+        send(&batch)?;
+        pressed.track(&batch);
+        if wait.is_zero() {
+            Ok(())
+        } else {
+            sleep(wait, cancel)
+        }
+    });
+
+    if ret.is_err() {
+        pressed.release(send);
+    }
+    ret
+}
+
+/// Releases the pressed keys and buttons when dropped, so that a failed or dropped future leaves none of them pressed.
+#[cfg(feature = "async")]
+struct ReleaseGuard<F: FnMut(&[INPUT]) -> Result<()>> {
+    pressed: PressedInputs,
+    send: F
+}
+
+#[cfg(feature = "async")]
+impl<F: FnMut(&[INPUT]) -> Result<()>> Drop for ReleaseGuard<F> {
+    fn drop(&mut self) {
+        mem::take(&mut self.pressed).release(&mut self.send);
+    }
+}
+
+/// Sends the input batches by `send`, waiting the duration after each batch by `timer`. The token is checked before each batch.
+///
+/// The keys and buttons which have been pressed are released when the batches stop early by cancellation or an error,
+/// or when the future is dropped before completion.
+#[cfg(feature = "async")]
+async fn send_batches_async<I, F, T>(batches: I, cancel: Option<&CancellationToken>, timer: &T, send: F) -> Result<()>
+where
+    I: IntoIterator<Item = (Vec<INPUT>, Duration)>,
+    F: FnMut(&[INPUT]) -> Result<()>,
+    T: AsyncTimer
+{
+    let mut guard = ReleaseGuard { pressed: PressedInputs::default(), send };
+    for (batch, wait) in batches {
+        if let Some(token) = cancel {
+            token.check()?;
+        }
+        (guard.send)(&batch)?;
+        guard.pressed.track(&batch);
+        if !wait.is_zero() {
+            timer.delay(wait).await;
+        }
+    }
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use windows::Win32::UI::Input::KeyboardAndMouse::*;
 
     use crate::inputs::Keyboard;
     use crate::inputs::parse_input;
+    use crate::inputs::escape_keys;
     use crate::inputs::Input;
     use crate::inputs::InputItem;
     use crate::inputs::VIRTUAL_KEYS;
+    use crate::inputs::Mouse;
+    use crate::inputs::move_steps;
+    use crate::inputs::send_batches;
+    use crate::timers::CancellationToken;
+    use crate::types::Point;
 
     #[test]
     fn test_virtual_keys() {
@@ -879,6 +1082,19 @@ mod tests {
         )
     }
 
+    #[test]
+    fn test_escape_keys() {
+        let text = "{enter}(ab) 你好";
+        let chars: Vec<InputItem> = text.chars().map(InputItem::Character).collect();
+        assert_eq!(
+            parse_input(&escape_keys(text)).unwrap(),
+            vec![Input {
+                holdkeys: Vec::new(),
+                items: chars
+            }]
+        );
+    }
+
     #[test]
     fn test_parse_input_5() {
         assert!(parse_input("Hello,Rust UIAutomation!{enter}").is_ok());
@@ -905,4 +1121,151 @@ mod tests {
             assert!(keys.is_ok());
         }
     }
+
+    #[test]
+    fn test_create_batches() {
+        let batches = Keyboard::new().create_batches("{enter}{tab}").unwrap();
+        assert_eq!(batches.iter().map(|b| b.len()).sum::<usize>(), 4);
+
+        let batches = Keyboard::new().interval(10).create_batches("{enter}{tab}").unwrap();
+        assert_eq!(batches.len(), 4);
+        assert!(batches.iter().all(|b| b.len() == 1));
+    }
+
+    #[test]
+    fn test_move_steps() {
+        let (steps, interval) = move_steps(Point::new(0, 0), Point::new(100, 50), 500);
+        assert_eq!(steps, vec![Point::new(20, 10), Point::new(40, 20), Point::new(60, 30), Point::new(80, 40)]);
+        assert_eq!(interval.as_millis(), 100);
+
+        let (steps, _) = move_steps(Point::new(0, 0), Point::new(30, 10), 500);
+        assert!(steps.is_empty());
+    }
+
+    /// Describes a sent input as `(key or button, pressed)`.
+    fn describe(input: &INPUT) -> (String, bool) {
+        unsafe {
+            if input.r#type == INPUT_KEYBOARD {
+                let ki = input.Anonymous.ki;
+                (format!("key {}", ki.wVk.0), !ki.dwFlags.contains(KEYEVENTF_KEYUP))
+            } else {
+                let flags = input.Anonymous.mi.dwFlags;
+                let button = if flags == MOUSEEVENTF_LEFTDOWN || flags == MOUSEEVENTF_LEFTUP { "left" } else { "right" };
+                (String::from(button), flags == MOUSEEVENTF_LEFTDOWN || flags == MOUSEEVENTF_RIGHTDOWN)
+            }
+        }
+    }
+
+    /// Sends `batches` until `presses` keys or buttons are pressed, then cancels and returns the sent inputs.
+    fn send_cancelled(batches: Vec<Vec<INPUT>>, presses: usize) -> Vec<(String, bool)> {
+        let token = CancellationToken::new();
+        let mut sent: Vec<(String, bool)> = Vec::new();
+        let batches = batches.into_iter().map(|batch| (batch, std::time::Duration::ZERO));
+        let ret = send_batches(batches, Some(&token), |inputs| {
+            sent.extend(inputs.iter().map(describe));
+            if sent.iter().filter(|(_, pressed)| *pressed).count() >= presses {
+                token.cancel();
+            }
+            Ok(())
+        });
+        assert_eq!(ret.unwrap_err().code(), crate::errors::ERR_CANCELLED);
+        sent
+    }
+
+    fn assert_released(sent: &[(String, bool)]) {
+        let mut pressed: Vec<&str> = Vec::new();
+        for (key, down) in sent {
+            if *down {
+                pressed.push(key);
+            } else {
+                let index = pressed.iter().rposition(|k| k == key).expect("released without pressing");
+                pressed.remove(index);
+            }
+        }
+        assert!(pressed.is_empty(), "left pressed: {:?}", pressed);
+    }
+
+    #[test]
+    fn test_cancelled_click_releases() {
+        let mouse = Mouse::new().holdkeys("{ctrl}{shift}");
+        let steps = mouse.click_steps(Point::new(10, 20), MOUSEEVENTF_LEFTDOWN, MOUSEEVENTF_LEFTUP, 2);
+        let sent = send_cancelled(steps.into_iter().map(|(batch, _)| batch).collect(), 3);
+
+        let ctrl = format!("key {}", VK_CONTROL.0);
+        let shift = format!("key {}", VK_SHIFT.0);
+        let left = String::from("left");
+        assert_eq!(sent, vec![
+            (ctrl.clone(), true),
+            (shift.clone(), true),
+            (left.clone(), true),
+            (left, false),
+            (shift, false),
+            (ctrl, false)
+        ]);
+    }
+
+    #[test]
+    fn test_cancelled_keys_release() {
+        let batches = Keyboard::new().interval(10).create_batches("{ctrl}({enter}{tab})").unwrap();
+        for presses in 1..=3 {
+            let sent = send_cancelled(batches.clone(), presses);
+            assert_eq!(sent.iter().filter(|(_, pressed)| *pressed).count(), presses);
+            assert_released(&sent);
+        }
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn test_async_click_releases() {
+        use std::cell::RefCell;
+        use std::future::Future;
+        use std::pin::pin;
+        use std::task::Context;
+        use std::task::Waker;
+
+        use crate::errors::ERR_CANCELLED;
+        use crate::inputs::send_batches_async;
+        use crate::timers::tests::MockTimer;
+        use crate::timers::tests::block_on;
+
+        let mouse = Mouse::new().holdkeys("{ctrl}{shift}").interval(10);
+        let steps = || mouse.click_steps(Point::new(10, 20), MOUSEEVENTF_LEFTDOWN, MOUSEEVENTF_LEFTUP, 1);
+
+        // cancelled after the left button is pressed.
+        let token = CancellationToken::new();
+        let sent: RefCell<Vec<(String, bool)>> = RefCell::new(Vec::new());
+        let timer = MockTimer::default();
+        let ret = block_on(send_batches_async(steps(), Some(&token), &timer, |inputs| {
+            sent.borrow_mut().extend(inputs.iter().map(describe));
+            if sent.borrow().len() == 3 {
+                token.cancel();
+            }
+            Ok(())
+        }));
+        assert_eq!(ret.unwrap_err().code(), ERR_CANCELLED);
+        assert_eq!(sent.borrow().len(), 6);
+        assert_released(&sent.borrow());
+
+        // dropped while waiting after the first holdkey.
+        struct Pending;
+        impl crate::timers::AsyncTimer for Pending {
+            type Delay = std::future::Pending<()>;
+            fn delay(&self, _: std::time::Duration) -> Self::Delay {
+                std::future::pending()
+            }
+            fn now(&self) -> u64 {
+                0
+            }
+        }
+        sent.borrow_mut().clear();
+        {
+            let mut future = pin!(send_batches_async(steps(), None, &Pending, |inputs| {
+                sent.borrow_mut().extend(inputs.iter().map(describe));
+                Ok(())
+            }));
+            assert!(future.as_mut().poll(&mut Context::from_waker(Waker::noop())).is_pending());
+        }
+        assert_eq!(sent.borrow().len(), 2);
+        assert_released(&sent.borrow());
+    }
 }
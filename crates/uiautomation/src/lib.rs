@@ -9,6 +9,22 @@ pub mod actions;
 pub mod inputs;
 pub mod processes;
 pub mod dialogs;
+pub mod locators;
+pub mod hosts;
+pub mod timers;
+pub mod windows;
+pub mod clipboard;
+pub mod texts;
+pub mod documents;
+pub mod tables;
+pub mod containers;
+pub mod trees;
+pub mod names;
+pub mod snapshots;
+pub mod selectors;
+pub mod fingerprints;
+pub mod images;
+pub mod highlights;
 
 pub use self::errors::Error;
 pub use self::errors::Result;
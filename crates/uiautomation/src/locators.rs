@@ -0,0 +1,406 @@
+use std::cell::RefCell;
+use std::fmt::Debug;
+use std::thread::sleep;
+use std::time::Duration;
+
+use windows::Win32::Foundation::CO_E_OBJNOTCONNECTED;
+use windows::Win32::Foundation::RPC_E_DISCONNECTED;
+use windows::Win32::UI::Accessibility::UIA_E_ELEMENTNOTAVAILABLE;
+
+use super::core::UIElement;
+use super::core::UIMatcher;
+use super::errors::ERR_NOTFOUND;
+use super::errors::Error;
+use super::errors::Result;
+
+/// Defines when and how a `UILocator` re-resolves its element.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetryPolicy {
+    retries: u32,
+    interval: u64,
+    codes: Vec<i32>
+}
+
+impl RetryPolicy {
+    /// Creates a policy which retries `retries` times after a stale element error.
+    pub fn new(retries: u32) -> Self {
+        Self {
+            retries,
+            ..Default::default()
+        }
+    }
+
+    /// Creates a policy which never retries.
+    pub fn never() -> Self {
+        Self::new(0)
+    }
+
+    /// Sets the count of retries. The default value is `1`.
+    pub fn retries(mut self, retries: u32) -> Self {
+        self.retries = retries;
+        self
+    }
+
+    /// Sets the time in milliseconds to wait before re-resolving the element. The default value is `0`.
+    pub fn interval(mut self, interval: u64) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Appends an error code which is treated as a stale element error.
+    pub fn stale_code(mut self, code: i32) -> Self {
+        if !self.codes.contains(&code) {
+            self.codes.push(code);
+        }
+        self
+    }
+
+    /// Retrieves the count of retries.
+    pub fn get_retries(&self) -> u32 {
+        self.retries
+    }
+
+    /// Retrieves the waiting time in milliseconds before re-resolving the element.
+    pub fn get_interval(&self) -> u64 {
+        self.interval
+    }
+
+    /// Returns `true` when `error` means the element is no longer available.
+    pub fn is_stale(&self, error: &Error) -> bool {
+        self.codes.contains(&error.code())
+    }
+}
+
+impl Default for RetryPolicy {
+    /// `UIA_E_ELEMENTNOTAVAILABLE`, `RPC_E_DISCONNECTED` and `CO_E_OBJNOTCONNECTED` are treated as stale element errors by default.
+    fn default() -> Self {
+        Self {
+            retries: 1,
+            interval: 0,
+            codes: vec![UIA_E_ELEMENTNOTAVAILABLE as i32, RPC_E_DISCONNECTED.0, CO_E_OBJNOTCONNECTED.0]
+        }
+    }
+}
+
+/// `LocatorSource` enumerates the candidates of a `UILocator`.
+/// 
+/// `UIMatcher` is the default source. The same matcher filters are evaluated every time the element is resolved.
+pub trait LocatorSource {
+    /// The element type to be located.
+    type Element: Clone;
+
+    /// Retrieves all the elements which match the source conditions.
+    fn candidates(&self) -> Result<Vec<Self::Element>>;
+
+    /// Retrieves the runtime id of `element`.
+    fn runtime_id(&self, element: &Self::Element) -> Result<Vec<i32>>;
+}
+
+impl LocatorSource for UIMatcher {
+    type Element = UIElement;
+
+    fn candidates(&self) -> Result<Vec<UIElement>> {
+        self.find_all()
+    }
+
+    fn runtime_id(&self, element: &UIElement) -> Result<Vec<i32>> {
+        element.get_runtime_id()
+    }
+}
+
+/// A re-resolvable handle of an element.
+/// 
+/// `UILocator` remembers how the element was found (matcher filters, root, index and runtime id).
+/// When an operation fails with a stale element error, the element is resolved again and the operation is retried as the `RetryPolicy` defines.
+/// 
+/// # Examples
+/// 
+/// ```
+/// use uiautomation::UIAutomation;
+/// 
+/// let automation = UIAutomation::new().unwrap();
+/// let locator = automation.create_matcher().depth(2).classname("Notepad").timeout(0).locator();
+/// if let Ok(name) = locator.run(|notepad| notepad.get_name()) {
+///     println!("{}", name);
+/// }
+/// ```
+pub struct UILocator<S: LocatorSource = UIMatcher> {
+    source: S,
+    index: usize,
+    policy: RetryPolicy,
+    element: RefCell<Option<S::Element>>,
+    runtime_id: RefCell<Option<Vec<i32>>>
+}
+
+impl<S: LocatorSource> UILocator<S> {
+    /// Creates a locator which finds the element from `source`.
+    pub fn new(source: S) -> Self {
+        Self {
+            source,
+            index: 0,
+            policy: RetryPolicy::default(),
+            element: RefCell::new(None),
+            runtime_id: RefCell::new(None)
+        }
+    }
+
+    /// Sets the index of the element in the candidates. The default index is `0`.
+    pub fn index(mut self, index: usize) -> Self {
+        self.index = index;
+        self.element.replace(None);
+        self.runtime_id.replace(None);
+        self
+    }
+
+    /// Sets the retry policy.
+    pub fn policy(mut self, policy: RetryPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Retrieves the source of the locator.
+    pub fn get_source(&self) -> &S {
+        &self.source
+    }
+
+    /// Retrieves the runtime id of the last resolved element.
+    pub fn get_runtime_id(&self) -> Option<Vec<i32>> {
+        self.runtime_id.borrow().clone()
+    }
+
+    /// Forgets the resolved element. The element will be resolved again on next access.
+    /// 
+    /// The runtime id is kept, so the same element is preferred if it still exists.
+    pub fn invalidate(&self) {
+        self.element.replace(None);
+    }
+
+    /// Retrieves the located element. The element is resolved only when it has not been resolved yet.
+    pub fn element(&self) -> Result<S::Element> {
+        if let Some(ref element) = *self.element.borrow() {
+            return Ok(element.clone());
+        }
+
+        self.resolve()
+    }
+
+    /// Resolves the element from the source again.
+    /// 
+    /// The candidate with the remembered runtime id is preferred, otherwise the candidate at `index` is used.
+    pub fn resolve(&self) -> Result<S::Element> {
+        let last_id = self.get_runtime_id();
+        let candidates = self.source.candidates()?;
+
+        let mut ids = Vec::with_capacity(candidates.len());
+        for candidate in &candidates {
+            ids.push(self.source.runtime_id(candidate).ok());
+        }
+
+        let pos = last_id.and_then(|id| ids.iter().position(|i| i.as_ref() == Some(&id)));
+        let pos = match pos {
+            Some(pos) => pos,
+            None if self.index < candidates.len() => self.index,
+            None => return Err(Error::new(ERR_NOTFOUND, "can not find element")),
+        };
+
+        let element = candidates[pos].clone();
+        self.element.replace(Some(element.clone()));
+        self.runtime_id.replace(ids[pos].take());
+
+        Ok(element)
+    }
+
+    /// Runs `operation` on the located element.
+    /// 
+    /// When `operation` fails with a stale element error, the element is resolved again and `operation` is retried.
+    pub fn run<T, F>(&self, operation: F) -> Result<T> where F: Fn(&S::Element) -> Result<T> {
+        let mut retries = 0;
+        let mut element = self.element()?;
+        loop {
+            match operation(&element) {
+                Err(e) if retries < self.policy.retries && self.policy.is_stale(&e) => {
+                    retries += 1;
+                    if self.policy.interval > 0 {
+                        sleep(Duration::from_millis(self.policy.interval));
+                    }
+
+                    element = self.resolve()?;
+                },
+                ret => return ret
+            }
+        }
+    }
+}
+
+impl UILocator<UIMatcher> {
+    /// Simulates mouse left click event on the located element.
+    pub fn click(&self) -> Result<()> {
+        self.run(|e| e.click())
+    }
+
+    /// Simulates typing `keys` on the located element. See `UIElement::send_keys()`.
+    pub fn send_keys(&self, keys: &str, interval: u64) -> Result<()> {
+        self.run(|e| e.send_keys(keys, interval))
+    }
+
+    /// Retrieves the name of the located element.
+    pub fn get_name(&self) -> Result<String> {
+        self.run(|e| e.get_name())
+    }
+}
+
+impl<S: LocatorSource + Debug> Debug for UILocator<S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("UILocator")
+            .field("source", &self.source)
+            .field("index", &self.index)
+            .field("policy", &self.policy)
+            .field("runtime_id", &self.get_runtime_id())
+        .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+    use std::cell::RefCell;
+
+    use windows::Win32::UI::Accessibility::UIA_E_ELEMENTNOTAVAILABLE;
+
+    use crate::errors::ERR_NOTFOUND;
+    use crate::errors::Error;
+    use crate::Result;
+
+    use super::LocatorSource;
+    use super::RetryPolicy;
+    use super::UILocator;
+
+    /// A node of `FakeTree`, which is valid only in the generation it was created.
+    #[derive(Debug, Clone, PartialEq)]
+    struct FakeNode {
+        generation: u32,
+        name: String
+    }
+
+    /// A tree whose nodes are replaced when the ui is rebuilt.
+    struct FakeTree {
+        generation: Cell<u32>,
+        names: RefCell<Vec<&'static str>>,
+        keep_ids: bool,
+        resolves: Cell<u32>
+    }
+
+    impl FakeTree {
+        fn new(names: Vec<&'static str>) -> Self {
+            Self {
+                generation: Cell::new(0),
+                names: RefCell::new(names),
+                keep_ids: false,
+                resolves: Cell::new(0)
+            }
+        }
+
+        fn rebuild(&self) {
+            self.generation.set(self.generation.get() + 1);
+        }
+
+        fn get_name(&self, node: &FakeNode) -> Result<String> {
+            if node.generation == self.generation.get() {
+                Ok(node.name.clone())
+            } else {
+                Err(Error::new(UIA_E_ELEMENTNOTAVAILABLE as i32, "element not available"))
+            }
+        }
+    }
+
+    impl LocatorSource for &FakeTree {
+        type Element = FakeNode;
+
+        fn candidates(&self) -> Result<Vec<FakeNode>> {
+            self.resolves.set(self.resolves.get() + 1);
+            Ok(self.names.borrow().iter().map(|n| FakeNode { generation: self.generation.get(), name: n.to_string() }).collect())
+        }
+
+        fn runtime_id(&self, element: &FakeNode) -> Result<Vec<i32>> {
+            let generation = if self.keep_ids { 0 } else { element.generation as i32 };
+            Ok(vec![generation, element.name.len() as i32, element.name.as_bytes()[0] as i32])
+        }
+    }
+
+    #[test]
+    fn test_resolve_once() {
+        let tree = FakeTree::new(vec!["a", "b"]);
+        let locator = UILocator::new(&tree).index(1);
+        assert_eq!(locator.run(|n| tree.get_name(n)), Ok("b".into()));
+        assert_eq!(locator.run(|n| tree.get_name(n)), Ok("b".into()));
+        assert_eq!(tree.resolves.get(), 1);
+    }
+
+    #[test]
+    fn test_recover_stale() {
+        let tree = FakeTree::new(vec!["a", "b"]);
+        let locator = UILocator::new(&tree).index(1);
+        locator.element().unwrap();
+
+        tree.rebuild();
+        assert_eq!(locator.run(|n| tree.get_name(n)), Ok("b".into()));
+        assert_eq!(tree.resolves.get(), 2);
+        assert_eq!(locator.get_runtime_id(), Some(vec![1, 1, b'b' as i32]));
+    }
+
+    #[test]
+    fn test_never_retry() {
+        let tree = FakeTree::new(vec!["a"]);
+        let locator = UILocator::new(&tree).policy(RetryPolicy::never());
+        locator.element().unwrap();
+
+        tree.rebuild();
+        let ret = locator.run(|n| tree.get_name(n));
+        assert_eq!(ret.unwrap_err().code(), UIA_E_ELEMENTNOTAVAILABLE as i32);
+    }
+
+    #[test]
+    fn test_retries_exhausted() {
+        let tree = FakeTree::new(vec!["a"]);
+        let locator = UILocator::new(&tree).policy(RetryPolicy::new(2));
+        let calls = Cell::new(0);
+        let ret = locator.run(|n| {
+            calls.set(calls.get() + 1);
+            tree.rebuild();
+            tree.get_name(n)
+        });
+        assert!(ret.is_err());
+        assert_eq!(calls.get(), 3);
+    }
+
+    #[test]
+    fn test_other_errors() {
+        let tree = FakeTree::new(vec!["a"]);
+        let locator = UILocator::new(&tree);
+        let ret: Result<()> = locator.run(|_| Err(Error::new(ERR_NOTFOUND, "no")));
+        assert_eq!(ret.unwrap_err().code(), ERR_NOTFOUND);
+        assert_eq!(tree.resolves.get(), 1);
+    }
+
+    #[test]
+    fn test_prefer_runtime_id() {
+        let mut tree = FakeTree::new(vec!["a", "bb"]);
+        tree.keep_ids = true;
+        let locator = UILocator::new(&tree).index(1);
+        locator.element().unwrap();
+
+        tree.names.replace(vec!["c", "a", "bb"]);
+        locator.invalidate();
+        assert_eq!(locator.element().unwrap().name, "bb");
+
+        tree.rebuild();
+        assert_eq!(locator.run(|n| tree.get_name(n)), Ok("bb".into()));
+    }
+
+    #[test]
+    fn test_index_out_of_range() {
+        let tree = FakeTree::new(vec!["a"]);
+        let locator = UILocator::new(&tree).index(3);
+        assert_eq!(locator.element().unwrap_err().code(), ERR_NOTFOUND);
+    }
+}
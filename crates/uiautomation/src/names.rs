@@ -0,0 +1,239 @@
+use std::collections::HashMap;
+use std::collections::HashSet;
+
+use windows::Win32::Globalization::GetUserDefaultUILanguage;
+
+/// The mask of the primary language in a locale identifier.
+const PRIMARY_LANGUAGE_MASK: u32 = 0x3FF;
+
+/// The locale identifier of the neutral language, which is used when no other locale matches.
+pub const LANG_NEUTRAL: u32 = 0;
+
+/// Retrieves the locale identifier of the user's UI language.
+pub fn get_ui_language() -> u32 {
+    unsafe {
+        GetUserDefaultUILanguage() as u32
+    }
+}
+
+/// The names of a logical UI item in several locales.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LocalizedName {
+    names: Vec<(u32, String)>
+}
+
+impl LocalizedName {
+    /// Creates an empty localized name.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the `name` for the locale `lcid`, such as `0x0409` for `en-US`, or `0x0804` for `zh-CN`.
+    ///
+    /// Use `LANG_NEUTRAL` for the name which is used when no other locale matches.
+    pub fn name<S: Into<String>>(mut self, lcid: u32, name: S) -> Self {
+        self.insert(lcid, name);
+        self
+    }
+
+    /// Adds or replaces the `name` for the locale `lcid`.
+    pub fn insert<S: Into<String>>(&mut self, lcid: u32, name: S) {
+        let name = name.into();
+        if let Some(item) = self.names.iter_mut().find(|(id, _)| *id == lcid) {
+            item.1 = name;
+        } else {
+            self.names.push((lcid, name));
+        }
+    }
+
+    /// Retrieves the name for the locale `lcid`.
+    ///
+    /// The name of the same locale is preferred, then the name of the same primary language, such as `zh-TW` for `zh-CN`,
+    /// then the neutral name.
+    pub fn get_name(&self, lcid: u32) -> Option<&str> {
+        let primary = lcid & PRIMARY_LANGUAGE_MASK;
+        self.find(|id| id == lcid)
+            .or_else(|| self.find(|id| id != LANG_NEUTRAL && id & PRIMARY_LANGUAGE_MASK == primary))
+            .or_else(|| self.find(|id| id == LANG_NEUTRAL))
+    }
+
+    /// Retrieves the names in all locales.
+    pub fn get_names(&self) -> Vec<&str> {
+        self.names.iter().map(|(_, name)| name.as_str()).collect()
+    }
+
+    /// Returns `true` if no name is added.
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    fn find<F: Fn(u32) -> bool>(&self, predicate: F) -> Option<&str> {
+        self.names.iter().find(|(id, _)| predicate(*id)).map(|(_, name)| name.as_str())
+    }
+}
+
+/// Maps logical keys to the localized names of UI items, so that one matcher works across UI languages.
+///
+/// # Examples
+/// ```
+/// use uiautomation::names::LANG_NEUTRAL;
+/// use uiautomation::names::NameDictionary;
+///
+/// let dictionary = NameDictionary::new()
+///     .name("settings", LANG_NEUTRAL, "Settings")
+///     .name("settings", 0x0804, "设置");
+/// assert_eq!(dictionary.get_name("settings", 0x0804), Some("设置"));
+/// assert_eq!(dictionary.get_name("settings", 0x0407), Some("Settings"));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct NameDictionary {
+    entries: HashMap<String, LocalizedName>
+}
+
+impl NameDictionary {
+    /// Creates an empty dictionary.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the `name` of `key` for the locale `lcid`.
+    pub fn name<K: Into<String>, S: Into<String>>(mut self, key: K, lcid: u32, name: S) -> Self {
+        self.insert(key, lcid, name);
+        self
+    }
+
+    /// Adds or replaces the `name` of `key` for the locale `lcid`.
+    pub fn insert<K: Into<String>, S: Into<String>>(&mut self, key: K, lcid: u32, name: S) {
+        self.entries.entry(key.into()).or_default().insert(lcid, name);
+    }
+
+    /// Retrieves the localized names of `key`.
+    pub fn get(&self, key: &str) -> Option<&LocalizedName> {
+        self.entries.get(key)
+    }
+
+    /// Retrieves the name of `key` for the locale `lcid`.
+    pub fn get_name(&self, key: &str, lcid: u32) -> Option<&str> {
+        self.entries.get(key).and_then(|names| names.get_name(lcid))
+    }
+
+    /// Retrieves the name of `key` for the user's UI language.
+    pub fn get_ui_name(&self, key: &str) -> Option<&str> {
+        self.get_name(key, get_ui_language())
+    }
+}
+
+/// Normalizes the text for fuzzy comparing: lowercase, and the whitespaces collapsed.
+fn normalize(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<&str>>().join(" ").to_lowercase()
+}
+
+/// Calculates the Levenshtein distance between two texts, counted by chars.
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    let mut cur: Vec<usize> = vec![0; b.len() + 1];
+    for (i, ca) in a.iter().enumerate() {
+        cur[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let cost = if ca == cb { 0 } else { 1 };
+            cur[j + 1] = (prev[j] + cost).min(prev[j + 1] + 1).min(cur[j] + 1);
+        }
+        std::mem::swap(&mut prev, &mut cur);
+    }
+
+    prev[b.len()]
+}
+
+/// Calculates the similarity between two texts in `[0.0, 1.0]`, ignoring case and extra whitespaces.
+///
+/// The score is the higher one of the edit distance similarity and the token similarity, which ignores the order of words.
+pub fn similarity(a: &str, b: &str) -> f64 {
+    let a = normalize(a);
+    let b = normalize(b);
+    if a == b {
+        return 1.0;
+    }
+
+    let len = a.chars().count().max(b.chars().count());
+    let edit_score = 1.0 - edit_distance(&a, &b) as f64 / len as f64;
+
+    edit_score.max(token_similarity(&a, &b))
+}
+
+/// Calculates the Dice coefficient of the words in two texts.
+fn token_similarity(a: &str, b: &str) -> f64 {
+    let tokenize = |text: &str| -> HashSet<String> {
+        text.split(|c: char| !c.is_alphanumeric())
+            .filter(|t| !t.is_empty())
+            .map(String::from)
+            .collect()
+    };
+
+    let a = tokenize(a);
+    let b = tokenize(b);
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+
+    let common = a.intersection(&b).count();
+    2.0 * common as f64 / (a.len() + b.len()) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::LANG_NEUTRAL;
+    use super::LocalizedName;
+    use super::NameDictionary;
+    use super::edit_distance;
+    use super::similarity;
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("", "abc"), 3);
+        assert_eq!(edit_distance("设置", "设置"), 0);
+        assert_eq!(edit_distance("检查更新", "检查是否更新"), 2);
+    }
+
+    #[test]
+    fn test_similarity() {
+        assert_eq!(similarity("Check for updates", "check  for UPDATES"), 1.0);
+        assert_eq!(similarity("Save As", "As Save"), 1.0);
+        assert!(similarity("Check for updates", "Check for update") > 0.9);
+        assert!(similarity("Download and install", "Check for updates") < 0.5);
+        assert_eq!(similarity("", "OK"), 0.0);
+    }
+
+    #[test]
+    fn test_localized_name() {
+        let name = LocalizedName::new()
+            .name(LANG_NEUTRAL, "Settings")
+            .name(0x0804, "设置")
+            .name(0x0404, "設定");
+        assert_eq!(name.get_name(0x0804), Some("设置"));
+        assert_eq!(name.get_name(0x0404), Some("設定"));
+        assert_eq!(name.get_name(0x1004), Some("设置"));
+        assert_eq!(name.get_name(0x0409), Some("Settings"));
+        assert_eq!(name.get_names(), vec!["Settings", "设置", "設定"]);
+
+        let name = LocalizedName::new().name(0x0409, "Start").name(0x0409, "Start menu");
+        assert_eq!(name.get_name(0x0409), Some("Start menu"));
+        assert_eq!(name.get_name(0x0804), None);
+    }
+
+    #[test]
+    fn test_dictionary() {
+        let dictionary = NameDictionary::new()
+            .name("start", 0x0409, "Start")
+            .name("start", 0x0804, "开始")
+            .name("settings", LANG_NEUTRAL, "Settings");
+        assert_eq!(dictionary.get_name("start", 0x0804), Some("开始"));
+        assert_eq!(dictionary.get_name("start", 0x0809), Some("Start"));
+        assert_eq!(dictionary.get_name("settings", 0x0804), Some("Settings"));
+        assert_eq!(dictionary.get_name("missing", 0x0804), None);
+        assert_eq!(dictionary.get("start").unwrap().get_names().len(), 2);
+    }
+}
@@ -39,22 +39,43 @@ use windows::Win32::UI::Accessibility::IUIAutomationValuePattern;
 use windows::Win32::UI::Accessibility::IUIAutomationVirtualizedItemPattern;
 use windows::Win32::UI::Accessibility::IUIAutomationWindowPattern;
 use windows::Win32::UI::Accessibility::SynchronizedInputType;
+use windows::Win32::UI::Accessibility::UIA_PROPERTY_ID;
+use windows::Win32::UI::Accessibility::IUIAutomationElement;
+use windows::Win32::UI::Accessibility::UiaGetReservedMixedAttributeValue;
+use windows::Win32::UI::Accessibility::UiaGetReservedNotSupportedValue;
+use windows::Win32::System::Variant::VT_UNKNOWN;
 use windows::core::BSTR;
 use windows::core::IUnknown;
 
+use crate::errors::ERR_FORMAT;
+use crate::errors::ERR_NOTFOUND;
+use crate::errors::ERR_TYPE;
 use crate::errors::Error;
 use crate::Result;
 use crate::UIElement;
+use crate::inputs::Mouse;
+use crate::types::ActiveEnd;
+use crate::types::AnimationStyle;
 use crate::types::AnnotationType;
+use crate::types::BulletStyle;
+use crate::types::CapStyle;
+use crate::types::CaretBidiMode;
+use crate::types::CaretPosition;
+use crate::types::Color;
 use crate::types::DockPosition;
 use crate::types::ExpandCollapseState;
+use crate::types::FlowDirections;
+use crate::types::HorizontalTextAlignment;
 use crate::types::NavigateDirection;
+use crate::types::OutlineStyles;
 use crate::types::Point;
+use crate::types::Rect;
 use crate::types::RowOrColumnMajor;
 use crate::types::ScrollAmount;
 use crate::types::StyleType;
 use crate::types::SupportedTextSelection;
 use crate::types::TextAttribute;
+use crate::types::TextDecorationLineStyle;
 use crate::types::TextPatternRangeEndpoint;
 use crate::types::TextUnit;
 use crate::types::ToggleState;
@@ -63,6 +84,7 @@ use crate::types::WindowInteractionState;
 use crate::types::WindowVisualState;
 use crate::types::ZoomUnit;
 use crate::variants::SafeArray;
+use crate::variants::Value;
 use crate::variants::Variant;
 
 /// `UIPatternType` is an enum wrapper for `windows::Win32::UI::Accessibility::UIA_PATTERN_ID`.
@@ -745,6 +767,24 @@ impl UIItemContainerPattern {
 
         Ok(element.into())
     }
+
+    /// Retrieves the item following `start_after`, or the first item when `start_after` is `None`, including the virtualized placeholders.
+    /// 
+    /// Returns `None` if there are no more items.
+    pub fn get_next_item(&self, start_after: Option<&UIElement>) -> Result<Option<UIElement>> {
+        let start_after: Option<&IUIAutomationElement> = start_after.map(|e| e.as_ref());
+        let value: Variant = Value::EMPTY.into();
+        let element = unsafe {
+            self.pattern.FindItemByProperty(start_after, UIA_PROPERTY_ID(0), value)
+        };
+
+        match element {
+            Ok(element) => Ok(Some(element.into())),
+            // A null element is returned when there are no more items.
+            Err(e) if e.code().is_ok() => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
 }
 
 impl UIPattern for UIItemContainerPattern {
@@ -1727,6 +1767,13 @@ impl UITextPattern {
         Ok(range.into())
     }
 
+    /// Finds the first occurrence of `text` in the document, and clicks the center of its first bounding rectangle.
+    pub fn click_text(&self, text: &str) -> Result<()> {
+        let document = self.get_document_range()?;
+        let found = document.find_text(text, false, false).map_err(|_| Error::new(ERR_NOTFOUND, "can not find the text"))?;
+        found.click()
+    }
+
     pub fn get_supported_text_selection(&self) -> Result<SupportedTextSelection> {
         let selection = unsafe {
             self.pattern.SupportedTextSelection()?
@@ -1863,6 +1910,197 @@ impl AsRef<UITextPattern> for UITextEditPattern {
         &self.text
     }
 }
+
+/// The typed value of a `TextAttribute`.
+#[derive(Debug, Clone)]
+pub enum TextAttributeValue {
+    /// The attribute varies over the text range.
+    Mixed,
+    /// The attribute is not supported by the control.
+    NotSupported,
+    /// A boolean value, such as `IsItalic` and `IsHidden`.
+    Bool(bool),
+    /// An integer value, such as `FontWeight`.
+    Int(i32),
+    /// A floating value in points, such as `FontSize` and `MarginTop`.
+    Float(f64),
+    /// A string value, such as `FontName`, `StyleName` and `LineSpacing`.
+    String(String),
+    /// A color value, such as `ForegroundColor` and `UnderlineColor`.
+    Color(Color),
+    /// The locale identifier (LCID) of the `Culture` attribute.
+    Culture(u32),
+    /// The `AnimationStyle` attribute.
+    AnimationStyle(AnimationStyle),
+    /// The `BulletStyle` attribute.
+    BulletStyle(BulletStyle),
+    /// The `CapStyle` attribute.
+    CapStyle(CapStyle),
+    /// The `HorizontalTextAlignment` attribute.
+    HorizontalTextAlignment(HorizontalTextAlignment),
+    /// The combination of `OutlineStyles` flags.
+    OutlineStyles(OutlineStyles),
+    /// The combination of `FlowDirections` flags of the `TextFlowDirections` attribute.
+    FlowDirections(FlowDirections),
+    /// The line style of `OverlineStyle`, `StrikethroughStyle` and `UnderlineStyle`.
+    LineStyle(TextDecorationLineStyle),
+    /// The `StyleId` attribute.
+    Style(StyleType),
+    /// The tab stops in points of the `Tabs` attribute.
+    Tabs(Vec<f64>),
+    /// The `AnnotationTypes` attribute.
+    Annotations(Vec<AnnotationType>),
+    /// The annotation elements of the `AnnotationObjects` attribute.
+    Elements(Vec<UIElement>),
+    /// The target range of the `Link` attribute.
+    Link(UITextRange),
+    /// The `SelectionActiveEnd` attribute.
+    ActiveEnd(ActiveEnd),
+    /// The `CaretPosition` attribute.
+    CaretPosition(CaretPosition),
+    /// The `CaretBidiMode` attribute.
+    CaretBidiMode(CaretBidiMode)
+}
+
+impl TextAttributeValue {
+    /// Decodes the raw `value` of `attr`.
+    /// 
+    /// `AnnotationObjects` and `Link` hold COM objects, which are decoded by `UITextRange::get_attribute()` instead.
+    pub fn from_value(attr: TextAttribute, value: &Value) -> Result<Self> {
+        let error = || Error::new(ERR_TYPE, &format!("unexpected value type for {:?}", attr));
+        let int = || value_as_i32(value).ok_or_else(error);
+
+        let typed = match attr {
+            TextAttribute::IsHidden | TextAttribute::IsItalic | TextAttribute::IsReadOnly | TextAttribute::IsSubscript
+                | TextAttribute::IsSuperscript | TextAttribute::IsActive => Self::Bool(value_as_bool(value).ok_or_else(error)?),
+            TextAttribute::FontWeight => Self::Int(int()?),
+            TextAttribute::FontSize | TextAttribute::IndentationFirstLine | TextAttribute::IndentationLeading | TextAttribute::IndentationTrailing
+                | TextAttribute::MarginBottom | TextAttribute::MarginLeading | TextAttribute::MarginTop | TextAttribute::MarginTrailing
+                | TextAttribute::BeforeParagraphSpacing | TextAttribute::AfterParagraphSpacing => Self::Float(value_as_f64(value).ok_or_else(error)?),
+            TextAttribute::FontName | TextAttribute::StyleName | TextAttribute::LineSpacing => match value {
+                Value::STRING(text) => Self::String(text.clone()),
+                _ => return Err(error()),
+            },
+            TextAttribute::BackgroundColor | TextAttribute::ForegroundColor | TextAttribute::OverlineColor
+                | TextAttribute::StrikethroughColor | TextAttribute::UnderlineColor => Self::Color(Color::from(int()? as u32)),
+            TextAttribute::Culture => Self::Culture(int()? as u32),
+            TextAttribute::AnimationStyle => Self::AnimationStyle(AnimationStyle::try_from(int()?)?),
+            TextAttribute::BulletStyle => Self::BulletStyle(BulletStyle::try_from(int()?)?),
+            TextAttribute::CapStyle => Self::CapStyle(CapStyle::try_from(int()?)?),
+            TextAttribute::HorizontalTextAlignment => Self::HorizontalTextAlignment(HorizontalTextAlignment::try_from(int()?)?),
+            TextAttribute::OutlineStyles => Self::OutlineStyles(OutlineStyles::from_bits(int()?)),
+            TextAttribute::TextFlowDirections => Self::FlowDirections(FlowDirections::from_bits(int()?)),
+            TextAttribute::OverlineStyle | TextAttribute::StrikethroughStyle | TextAttribute::UnderlineStyle => Self::LineStyle(TextDecorationLineStyle::try_from(int()?)?),
+            TextAttribute::StyleId => Self::Style(StyleType::try_from(int()?)?),
+            TextAttribute::Tabs => match value {
+                Value::ArrayR8(tabs) => Self::Tabs(tabs.clone()),
+                Value::EMPTY => Self::Tabs(Vec::new()),
+                _ => return Err(error()),
+            },
+            TextAttribute::AnnotationTypes => match value {
+                Value::ArrayI4(types) => Self::Annotations(types.iter().filter_map(|t| AnnotationType::try_from(*t).ok()).collect()),
+                Value::EMPTY => Self::Annotations(Vec::new()),
+                _ => return Err(error()),
+            },
+            TextAttribute::SelectionActiveEnd => Self::ActiveEnd(ActiveEnd::try_from(int()?)?),
+            TextAttribute::CaretPosition => Self::CaretPosition(CaretPosition::try_from(int()?)?),
+            TextAttribute::CaretBidiMode => Self::CaretBidiMode(CaretBidiMode::try_from(int()?)?),
+            TextAttribute::AnnotationObjects | TextAttribute::Link => return Err(error()),
+        };
+
+        Ok(typed)
+    }
+
+    /// Encodes the value as the raw value, which can be used by `UITextRange::find_attribute()`.
+    pub fn to_value(&self) -> Result<Value> {
+        let value = match self {
+            Self::Bool(value) => Value::BOOL(*value),
+            Self::Int(value) => Value::I4(*value),
+            Self::OutlineStyles(styles) => Value::I4(styles.get_bits()),
+            Self::FlowDirections(directions) => Value::I4(directions.get_bits()),
+            Self::Float(value) => Value::R8(*value),
+            Self::String(value) => Value::STRING(value.clone()),
+            Self::Color(color) => Value::I4(u32::from(*color) as i32),
+            Self::Culture(lcid) => Value::I4(*lcid as i32),
+            Self::AnimationStyle(style) => Value::I4(*style as i32),
+            Self::BulletStyle(style) => Value::I4(*style as i32),
+            Self::CapStyle(style) => Value::I4(*style as i32),
+            Self::HorizontalTextAlignment(alignment) => Value::I4(*alignment as i32),
+            Self::LineStyle(style) => Value::I4(*style as i32),
+            Self::Style(style) => Value::I4(*style as i32),
+            Self::Tabs(tabs) => Value::ArrayR8(tabs.clone()),
+            Self::Annotations(types) => Value::ArrayI4(types.iter().map(|t| *t as i32).collect()),
+            Self::ActiveEnd(end) => Value::I4(*end as i32),
+            Self::CaretPosition(position) => Value::I4(*position as i32),
+            Self::CaretBidiMode(mode) => Value::I4(*mode as i32),
+            Self::Mixed | Self::NotSupported | Self::Elements(_) | Self::Link(_) => return Err(Error::new(ERR_TYPE, "the value can not be encoded")),
+        };
+
+        Ok(value)
+    }
+
+    /// Returns `true` if the attribute is supported and has the same value over the range.
+    pub fn is_uniform(&self) -> bool {
+        !matches!(self, Self::Mixed | Self::NotSupported)
+    }
+
+    /// Retrieves the boolean value.
+    pub fn as_bool(&self) -> Option<bool> {
+        if let Self::Bool(value) = self { Some(*value) } else { None }
+    }
+
+    /// Retrieves the integer value.
+    pub fn as_i32(&self) -> Option<i32> {
+        if let Self::Int(value) = self { Some(*value) } else { None }
+    }
+
+    /// Retrieves the floating value.
+    pub fn as_f64(&self) -> Option<f64> {
+        if let Self::Float(value) = self { Some(*value) } else { None }
+    }
+
+    /// Retrieves the string value.
+    pub fn as_str(&self) -> Option<&str> {
+        if let Self::String(value) = self { Some(value) } else { None }
+    }
+
+    /// Retrieves the color value.
+    pub fn as_color(&self) -> Option<Color> {
+        if let Self::Color(value) = self { Some(*value) } else { None }
+    }
+}
+
+fn value_as_i32(value: &Value) -> Option<i32> {
+    match value {
+        Value::I1(v) => Some(*v as i32),
+        Value::I2(v) => Some(*v as i32),
+        Value::I4(v) | Value::INT(v) => Some(*v),
+        Value::I8(v) => Some(*v as i32),
+        Value::UI1(v) => Some(*v as i32),
+        Value::UI2(v) => Some(*v as i32),
+        Value::UI4(v) | Value::UINT(v) => Some(*v as i32),
+        Value::UI8(v) => Some(*v as i32),
+        Value::R4(v) => Some(*v as i32),
+        Value::R8(v) => Some(*v as i32),
+        _ => None,
+    }
+}
+
+fn value_as_f64(value: &Value) -> Option<f64> {
+    match value {
+        Value::R4(v) => Some(*v as f64),
+        Value::R8(v) => Some(*v),
+        _ => value_as_i32(value).map(|v| v as f64),
+    }
+}
+
+fn value_as_bool(value: &Value) -> Option<bool> {
+    match value {
+        Value::BOOL(v) => Some(*v),
+        _ => value_as_i32(value).map(|v| v != 0),
+    }
+}
+
 /// A wrapper for `IUIAutomationTextRange`, `IUIAutomationTextRange2` and `IUIAutomationTextRange3`.
 #[derive(Debug, Clone)]
 pub struct UITextRange {
@@ -1911,6 +2149,44 @@ impl UITextRange {
         Ok(value.into())
     }
 
+    /// Retrieves the typed value of `attr`, distinguishing the mixed and not supported values.
+    pub fn get_attribute(&self, attr: TextAttribute) -> Result<TextAttributeValue> {
+        let value = self.get_attribute_value(attr)?;
+
+        if value.get_type() == VT_UNKNOWN {
+            let unknown: Option<IUnknown> = value.get_value().ok().and_then(|v| if let Value::UNKNOWN(unknown) = v { Some(unknown) } else { None });
+            if let Some(unknown) = unknown {
+                if unsafe { UiaGetReservedMixedAttributeValue() }.is_ok_and(|mixed| mixed == unknown) {
+                    return Ok(TextAttributeValue::Mixed);
+                }
+                if unsafe { UiaGetReservedNotSupportedValue() }.is_ok_and(|not_supported| not_supported == unknown) {
+                    return Ok(TextAttributeValue::NotSupported);
+                }
+                if attr == TextAttribute::Link {
+                    let range: IUIAutomationTextRange = unknown.cast()?;
+                    return Ok(TextAttributeValue::Link(range.into()));
+                }
+            }
+        }
+
+        if attr == TextAttribute::AnnotationObjects {
+            let elements: Vec<IUIAutomationElement> = if value.is_array() {
+                value.get_array()?.into_interface_vector()?
+            } else {
+                Vec::new()
+            };
+            return Ok(TextAttributeValue::Elements(elements.into_iter().map(|e| e.into()).collect()));
+        }
+
+        TextAttributeValue::from_value(attr, &value.get_value()?)
+    }
+
+    /// Finds the first range with the typed `value` of `attr`.
+    pub fn find_attribute_value(&self, attr: TextAttribute, value: &TextAttributeValue, backward: bool) -> Result<UITextRange> {
+        let value: Variant = value.to_value()?.into();
+        self.find_attribute(attr, value, backward)
+    }
+
     pub fn get_enclosing_element(&self) -> Result<UIElement> {
         let element = unsafe {
             self.range.GetEnclosingElement()?
@@ -1982,6 +2258,114 @@ impl UITextRange {
         })
     }
 
+    /// Creates a new range with the same endpoints.
+    /// 
+    /// Different from `clone()`, which shares the same range, moving the endpoints of the new range does not affect this range.
+    pub fn clone_range(&self) -> Result<UITextRange> {
+        let range = unsafe {
+            self.range.Clone()?
+        };
+        Ok(range.into())
+    }
+
+    /// Retrieves the bounding rectangles of the visible lines in the range, in screen coordinates.
+    pub fn get_bounding_rectangles(&self) -> Result<Vec<Rect>> {
+        let arr = unsafe {
+            self.range.GetBoundingRectangles()?
+        };
+        if arr.is_null() {
+            return Ok(Vec::new());
+        }
+
+        let arr = SafeArray::from(arr);
+        let values: Vec<f64> = arr.try_into()?;
+        Ok(decode_rectangles(&values))
+    }
+
+    /// Retrieves the sub range which starts at `start` characters and contains `length` characters.
+    /// 
+    /// The sub range is clamped to the end of the range, so it may be shorter than `length` or empty.
+    /// Returns `ERR_FORMAT` if `start` or `length` is negative.
+    pub fn get_range_by_offsets(&self, start: i32, length: i32) -> Result<UITextRange> {
+        if start < 0 || length < 0 {
+            return Err(Error::new(ERR_FORMAT, "the offsets of a text range must not be negative"));
+        }
+
+        let range = self.clone_range()?;
+        range.move_endpoint_by_range(TextPatternRangeEndpoint::End, self, TextPatternRangeEndpoint::Start)?;
+        if start > 0 {
+            range.move_endpoint_by_unit(TextPatternRangeEndpoint::Start, TextUnit::Character, start)?;
+            if range.compare_endpoints(TextPatternRangeEndpoint::Start, self, TextPatternRangeEndpoint::End)? > 0 {
+                range.move_endpoint_by_range(TextPatternRangeEndpoint::Start, self, TextPatternRangeEndpoint::End)?;
+            }
+            range.move_endpoint_by_range(TextPatternRangeEndpoint::End, &range, TextPatternRangeEndpoint::Start)?;
+        }
+        if length > 0 {
+            range.move_endpoint_by_unit(TextPatternRangeEndpoint::End, TextUnit::Character, length)?;
+            if range.compare_endpoints(TextPatternRangeEndpoint::End, self, TextPatternRangeEndpoint::End)? > 0 {
+                range.move_endpoint_by_range(TextPatternRangeEndpoint::End, self, TextPatternRangeEndpoint::End)?;
+            }
+        }
+        Ok(range)
+    }
+
+    /// Selects `length` characters from the offset `start` in the range, and returns the selected range.
+    /// 
+    /// The selection is clamped as `get_range_by_offsets()` does.
+    pub fn select_by_offsets(&self, start: i32, length: i32) -> Result<UITextRange> {
+        let range = self.get_range_by_offsets(start, length)?;
+        range.select()?;
+        Ok(range)
+    }
+
+    /// Simulates mouse left click on the center of the first bounding rectangle of the range.
+    pub fn click(&self) -> Result<()> {
+        let _ = self.scroll_into_view(false);
+
+        let rects = self.get_bounding_rectangles()?;
+        let Some(rect) = rects.first() else {
+            return Err(Error::new(ERR_NOTFOUND, "the text range is invisible"));
+        };
+        let point = Point::new((rect.get_left() + rect.get_right()) / 2, (rect.get_top() + rect.get_bottom()) / 2);
+        Mouse::default().click(point)
+    }
+
+    /// Iterates over the sub ranges of `unit` in the range.
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// use uiautomation::UIAutomation;
+    /// use uiautomation::patterns::UITextPattern;
+    /// 
+    /// let automation = UIAutomation::new().unwrap();
+    /// let element = automation.get_focused_element().unwrap();
+    /// if let Ok(pattern) = element.get_pattern::<UITextPattern>() {
+    ///     let document = pattern.get_document_range().unwrap();
+    ///     for line in document.lines().unwrap() {
+    ///         println!("{}", line.unwrap().get_text(-1).unwrap());
+    ///     }
+    /// }
+    /// ```
+    pub fn iter_units(&self, unit: TextUnit) -> Result<UITextUnits> {
+        UITextUnits::new(self, unit)
+    }
+
+    /// Iterates over the words in the range.
+    pub fn words(&self) -> Result<UITextUnits> {
+        self.iter_units(TextUnit::Word)
+    }
+
+    /// Iterates over the lines in the range.
+    pub fn lines(&self) -> Result<UITextUnits> {
+        self.iter_units(TextUnit::Line)
+    }
+
+    /// Iterates over the paragraphs in the range.
+    pub fn paragraphs(&self) -> Result<UITextUnits> {
+        self.iter_units(TextUnit::Paragraph)
+    }
+
     /// Convert `IUIAutomationTextRangeArray` to `Vec<UITextRange>`.
     pub(crate) fn to_ranges(ranges: IUIAutomationTextRangeArray) -> Result<Vec<UITextRange>> {
         let mut arr: Vec<UITextRange> = Vec::new();
@@ -2017,6 +2401,73 @@ impl AsRef<IUIAutomationTextRange> for UITextRange {
     }
 }
 
+/// An iterator over the sub ranges of a `TextUnit` in a `UITextRange`.
+#[derive(Debug)]
+pub struct UITextUnits {
+    bounds: UITextRange,
+    cursor: UITextRange,
+    unit: TextUnit,
+    done: bool
+}
+
+impl UITextUnits {
+    fn new(range: &UITextRange, unit: TextUnit) -> Result<Self> {
+        let cursor = range.clone_range()?;
+        cursor.move_endpoint_by_range(TextPatternRangeEndpoint::End, range, TextPatternRangeEndpoint::Start)?;
+
+        Ok(Self {
+            bounds: range.clone(),
+            cursor,
+            unit,
+            done: false
+        })
+    }
+
+    fn next_range(&mut self) -> Result<Option<UITextRange>> {
+        let start = TextPatternRangeEndpoint::Start;
+        let end = TextPatternRangeEndpoint::End;
+
+        if self.cursor.compare_endpoints(start, &self.bounds, end)? >= 0 {
+            return Ok(None);
+        }
+        if self.cursor.move_endpoint_by_unit(end, self.unit, 1)? == 0 {
+            return Ok(None);
+        }
+        if self.cursor.compare_endpoints(end, &self.bounds, end)? > 0 {
+            self.cursor.move_endpoint_by_range(end, &self.bounds, end)?;
+        }
+
+        let range = self.cursor.clone_range()?;
+        self.cursor.move_endpoint_by_range(start, &self.cursor, end)?;
+        Ok(Some(range))
+    }
+}
+
+impl Iterator for UITextUnits {
+    type Item = Result<UITextRange>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        let next = self.next_range().transpose();
+        if !matches!(next, Some(Ok(_))) {
+            self.done = true;
+        }
+        next
+    }
+}
+
+/// Decodes the flat array returned by `GetBoundingRectangles()`, which contains `left, top, width, height` for each rectangle.
+fn decode_rectangles(values: &[f64]) -> Vec<Rect> {
+    values.chunks_exact(4).map(|v| {
+        let left = v[0].round() as i32;
+        let top = v[1].round() as i32;
+        Rect::new(left, top, left + v[2].round() as i32, top + v[3].round() as i32)
+    }).collect()
+}
+
 /// A wrapper for `IUIAutomationTogglePattern`
 #[derive(Debug, Clone)]
 pub struct UITogglePattern {
@@ -2418,11 +2869,69 @@ impl AsRef<IUIAutomationWindowPattern> for UIWindowPattern {
 
 #[cfg(test)]
 mod tests {
+    use crate::types::AnnotationType;
+    use crate::types::BulletStyle;
+    use crate::types::Color;
+    use crate::types::Rect;
+    use crate::types::StyleType;
+    use crate::types::TextAttribute;
+    use crate::types::TextDecorationLineStyle;
+    use crate::variants::Value;
+
+    use super::TextAttributeValue;
     use super::UIPatternType;
+    use super::decode_rectangles;
 
     #[test]
     fn test_uipatterntypes() {
         let t = UIPatternType::try_from(10000i32).unwrap();
         assert_eq!(t, UIPatternType::Invoke);
     }
+
+    #[test]
+    fn test_decode_rectangles() {
+        let rects = decode_rectangles(&[10.0, 20.0, 100.0, 16.0, 10.4, 36.6, 50.5, 16.0, 1.0]);
+        assert_eq!(rects, vec![Rect::new(10, 20, 110, 36), Rect::new(10, 37, 61, 53)]);
+        assert!(decode_rectangles(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_text_attribute_values() {
+        let decode = |attr: TextAttribute, value: Value| TextAttributeValue::from_value(attr, &value).unwrap();
+
+        assert!(matches!(decode(TextAttribute::IsItalic, Value::BOOL(true)), TextAttributeValue::Bool(true)));
+        assert!(matches!(decode(TextAttribute::FontWeight, Value::I4(700)), TextAttributeValue::Int(700)));
+        assert_eq!(decode(TextAttribute::FontSize, Value::R8(10.5)).as_f64(), Some(10.5));
+        assert_eq!(decode(TextAttribute::FontName, Value::STRING("Arial".into())).as_str(), Some("Arial"));
+        assert_eq!(decode(TextAttribute::ForegroundColor, Value::I4(0x0000FF)).as_color(), Some(Color::new(255, 0, 0)));
+        assert!(matches!(decode(TextAttribute::Culture, Value::I4(0x0409)), TextAttributeValue::Culture(0x0409)));
+        assert!(matches!(decode(TextAttribute::BulletStyle, Value::I4(-1)), TextAttributeValue::BulletStyle(BulletStyle::Other)));
+        assert!(matches!(decode(TextAttribute::UnderlineStyle, Value::I4(8)), TextAttributeValue::LineStyle(TextDecorationLineStyle::Wavy)));
+        assert!(matches!(decode(TextAttribute::StyleId, Value::I4(70001)), TextAttributeValue::Style(StyleType::Heading1)));
+        assert!(matches!(decode(TextAttribute::AnnotationTypes, Value::ArrayI4(vec![60001, 1])), TextAttributeValue::Annotations(ref types) if types == &vec![AnnotationType::SpellingError]));
+        assert!(matches!(decode(TextAttribute::Tabs, Value::EMPTY), TextAttributeValue::Tabs(ref tabs) if tabs.is_empty()));
+
+        assert!(TextAttributeValue::from_value(TextAttribute::FontName, &Value::I4(1)).is_err());
+        assert!(TextAttributeValue::from_value(TextAttribute::StyleId, &Value::I4(1)).is_err());
+        assert!(TextAttributeValue::from_value(TextAttribute::Link, &Value::EMPTY).is_err());
+    }
+
+    #[test]
+    fn test_text_attribute_round_trip() {
+        let values = [
+            (TextAttribute::IsHidden, TextAttributeValue::Bool(false)),
+            (TextAttribute::MarginTop, TextAttributeValue::Float(72.0)),
+            (TextAttribute::BackgroundColor, TextAttributeValue::Color(Color::new(1, 2, 3))),
+            (TextAttribute::StrikethroughStyle, TextAttributeValue::LineStyle(TextDecorationLineStyle::Double)),
+            (TextAttribute::StyleId, TextAttributeValue::Style(StyleType::Quote)),
+            (TextAttribute::Tabs, TextAttributeValue::Tabs(vec![36.0, 72.0])),
+        ];
+        for (attr, value) in values {
+            let decoded = TextAttributeValue::from_value(attr, &value.to_value().unwrap()).unwrap();
+            assert_eq!(format!("{:?}", decoded), format!("{:?}", value));
+        }
+
+        assert!(TextAttributeValue::Mixed.to_value().is_err());
+        assert!(!TextAttributeValue::NotSupported.is_uniform());
+    }
 }
\ No newline at end of file
@@ -1,26 +1,80 @@
+use std::collections::BTreeMap;
+use std::collections::HashSet;
+use std::ffi::c_void;
 use std::mem;
+use std::thread;
 
 use windows::Win32::Foundation::CloseHandle;
+use windows::Win32::Foundation::FILETIME;
+use windows::Win32::Foundation::ERROR_BROKEN_PIPE;
 use windows::Win32::Foundation::HANDLE;
+use windows::Win32::Foundation::HANDLE_FLAGS;
+use windows::Win32::Foundation::HANDLE_FLAG_INHERIT;
+use windows::Win32::Foundation::SetHandleInformation;
 use windows::Win32::Foundation::WAIT_FAILED;
 use windows::Win32::Foundation::WAIT_OBJECT_0;
 use windows::Win32::Foundation::WAIT_TIMEOUT;
+use windows::Win32::Security::SECURITY_ATTRIBUTES;
+use windows::Win32::Storage::FileSystem::ReadFile;
+use windows::Win32::System::Com::CLSCTX_LOCAL_SERVER;
+use windows::Win32::System::Com::COINIT_MULTITHREADED;
+use windows::Win32::System::Com::CoCreateInstance;
+use windows::Win32::System::Com::CoInitializeEx;
+use windows::Win32::System::Diagnostics::ToolHelp::CreateToolhelp32Snapshot;
+use windows::Win32::System::Diagnostics::ToolHelp::PROCESSENTRY32W;
+use windows::Win32::System::Diagnostics::ToolHelp::Process32FirstW;
+use windows::Win32::System::Diagnostics::ToolHelp::Process32NextW;
+use windows::Win32::System::Diagnostics::ToolHelp::TH32CS_SNAPPROCESS;
+use windows::Win32::System::JobObjects::AssignProcessToJobObject;
+use windows::Win32::System::JobObjects::CreateJobObjectW;
+use windows::Win32::System::JobObjects::JOBOBJECT_EXTENDED_LIMIT_INFORMATION;
+use windows::Win32::System::JobObjects::JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE;
+use windows::Win32::System::JobObjects::JobObjectExtendedLimitInformation;
+use windows::Win32::System::JobObjects::SetInformationJobObject;
+use windows::Win32::System::JobObjects::TerminateJobObject;
+use windows::Win32::System::Pipes::CreatePipe;
+use windows::Win32::System::Threading::CREATE_SUSPENDED;
+use windows::Win32::System::Threading::CREATE_UNICODE_ENVIRONMENT;
 use windows::Win32::System::Threading::CreateProcessW;
 use windows::Win32::System::Threading::GetExitCodeProcess;
+use windows::Win32::System::Threading::GetProcessTimes;
 use windows::Win32::System::Threading::INFINITE;
+use windows::Win32::System::Threading::OpenProcess;
 use windows::Win32::System::Threading::PROCESS_CREATION_FLAGS;
 use windows::Win32::System::Threading::PROCESS_INFORMATION;
+use windows::Win32::System::Threading::PROCESS_QUERY_LIMITED_INFORMATION;
+use windows::Win32::System::Threading::PROCESS_SYNCHRONIZE;
+use windows::Win32::System::Threading::PROCESS_TERMINATE;
+use windows::Win32::System::Threading::ResumeThread;
+use windows::Win32::System::Threading::STARTF_USESHOWWINDOW;
+use windows::Win32::System::Threading::STARTF_USESTDHANDLES;
 use windows::Win32::System::Threading::STARTUPINFOW;
+use windows::Win32::System::Threading::TerminateProcess;
 use windows::Win32::System::Threading::WaitForInputIdle;
 use windows::Win32::System::Threading::WaitForSingleObject;
+use windows::Win32::UI::Shell::AO_NONE;
+use windows::Win32::UI::Shell::ApplicationActivationManager;
+use windows::Win32::UI::Shell::IApplicationActivationManager;
+use windows::Win32::UI::WindowsAndMessaging::SHOW_WINDOW_CMD;
 use windows::core::PCWSTR;
 use windows::core::PWSTR;
 
 use super::Error;
 use super::Result;
 use crate::errors::ERR_ALREADY_RUNNING;
+use super::errors::ERR_FORMAT;
 use super::errors::ERR_NONE;
+use super::errors::ERR_NOTFOUND;
 use super::errors::ERR_TIMEOUT;
+use super::core::UIAutomation;
+use super::core::UIElement;
+use super::timers::RetrySchedule;
+use super::timers::retry;
+use super::types::TreeScope;
+#[cfg(feature = "async")]
+use super::timers::AsyncTimer;
+#[cfg(feature = "async")]
+use super::timers::wait_until;
 
 /// Windows process wrapper.
 #[derive(Debug)]
@@ -29,8 +83,39 @@ pub struct Process {
     command: Option<String>,
     cur_dir: Option<String>,
     wait_for_idle: Option<u32>,
+    env_clear: bool,
+    env_vars: Vec<(String, Option<String>)>,
+    creation_flags: PROCESS_CREATION_FLAGS,
+    capture_output: bool,
+    kill_on_drop: bool,
     startup_info: STARTUPINFOW,
-    proc_info: PROCESS_INFORMATION
+    proc_info: PROCESS_INFORMATION,
+    stdout_pipe: HANDLE,
+    stderr_pipe: HANDLE,
+    job: HANDLE
+}
+
+/// The output of a finished process.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ProcessOutput {
+    /// The exit code of the process.
+    pub exit_code: u32,
+    /// The captured bytes from stdout.
+    pub stdout: Vec<u8>,
+    /// The captured bytes from stderr.
+    pub stderr: Vec<u8>
+}
+
+impl ProcessOutput {
+    /// Retrieves the stdout as text. Invalid UTF-8 sequences are replaced.
+    pub fn stdout_text(&self) -> String {
+        String::from_utf8_lossy(&self.stdout).into_owned()
+    }
+
+    /// Retrieves the stderr as text. Invalid UTF-8 sequences are replaced.
+    pub fn stderr_text(&self) -> String {
+        String::from_utf8_lossy(&self.stderr).into_owned()
+    }
 }
 
 macro_rules! to_pcwstr {
@@ -55,6 +140,15 @@ macro_rules! to_pwstr {
     };
 }
 
+macro_rules! close_handle {
+    ($handle: expr) => {
+        if !$handle.is_invalid() {
+            let _ = unsafe { CloseHandle($handle) };
+            $handle = HANDLE::default();
+        }
+    };
+}
+
 impl Process {
     /// Create and run a process by command line.
     /// 
@@ -71,6 +165,47 @@ impl Process {
         Ok(process)
     }
 
+    /// Attach to a running process by its identifier. The attached process can be waited, terminated and used to look up its windows.
+    pub fn attach(pid: u32) -> Result<Self> {
+        let handle = unsafe {
+            OpenProcess(PROCESS_SYNCHRONIZE | PROCESS_QUERY_LIMITED_INFORMATION | PROCESS_TERMINATE, false, pid)?
+        };
+
+        let mut process = Self::default();
+        process.proc_info.hProcess = handle;
+        process.proc_info.dwProcessId = pid;
+        Ok(process)
+    }
+
+    /// Launch a packaged (UWP/MSIX) application by its AppUserModelID, such as `Microsoft.WindowsCalculator_8wekyb3d8bbwe!App`.
+    /// 
+    /// `args` is passed to the application as its activation arguments. The launched process is attached by `attach()`.
+    /// 
+    /// # Examples
+    /// ```
+    /// use uiautomation::UIAutomation;
+    /// use uiautomation::processes::Process;
+    /// 
+    /// let settings = Process::launch_packaged("windows.immersivecontrolpanel_cw5n1h2txyewy!microsoft.windows.immersivecontrolpanel", "").unwrap();
+    /// let automation = UIAutomation::new().unwrap();
+    /// let window = settings.main_window(&automation, 10000);
+    /// assert!(window.is_ok());
+    /// ```
+    pub fn launch_packaged(aumid: &str, args: &str) -> Result<Self> {
+        split_aumid(aumid)?;
+
+        let pid = unsafe {
+            let _ = CoInitializeEx(None, COINIT_MULTITHREADED);
+            let manager: IApplicationActivationManager = CoCreateInstance(&ApplicationActivationManager, None, CLSCTX_LOCAL_SERVER)?;
+
+            let aumid: Vec<u16> = aumid.encode_utf16().chain(std::iter::once(0)).collect();
+            let args: Vec<u16> = args.encode_utf16().chain(std::iter::once(0)).collect();
+            manager.ActivateApplication(PCWSTR::from_raw(aumid.as_ptr()), PCWSTR::from_raw(args.as_ptr()), AO_NONE)?
+        };
+
+        Self::attach(pid)
+    }
+
     #[inline]
     fn startupinfo() -> STARTUPINFOW {
         let mut si = STARTUPINFOW::default();
@@ -85,8 +220,16 @@ impl Process {
             command: Some(command.into()),
             cur_dir: None,
             wait_for_idle: None,
+            env_clear: false,
+            env_vars: Vec::new(),
+            creation_flags: PROCESS_CREATION_FLAGS::default(),
+            capture_output: false,
+            kill_on_drop: false,
             startup_info: Self::startupinfo(), 
             proc_info: PROCESS_INFORMATION::default(),
+            stdout_pipe: HANDLE::default(),
+            stderr_pipe: HANDLE::default(),
+            job: HANDLE::default()
         }
     }
 
@@ -102,6 +245,70 @@ impl Process {
         self
     }
 
+    /// Append an argument to the command line. The `arg` is quoted by the Windows command-line rules. See `quote_arg()`.
+    pub fn arg<S: AsRef<str>>(mut self, arg: S) -> Self {
+        let arg = quote_arg(arg.as_ref());
+        self.command = match self.command.take() {
+            Some(command) if !command.is_empty() => Some(format!("{} {}", command, arg)),
+            _ => Some(arg),
+        };
+        self
+    }
+
+    /// Append arguments to the command line. Every argument is quoted by the Windows command-line rules.
+    pub fn args<I, S>(mut self, args: I) -> Self where I: IntoIterator<Item = S>, S: AsRef<str> {
+        for arg in args {
+            self = self.arg(arg);
+        }
+        self
+    }
+
+    /// Set an environment variable of the process. The other variables are inherited from the current process unless `env_clear()` is called.
+    pub fn env<K: Into<String>, V: Into<String>>(mut self, key: K, value: V) -> Self {
+        self.env_vars.push((key.into(), Some(value.into())));
+        self
+    }
+
+    /// Remove an inherited environment variable from the process.
+    pub fn env_remove<K: Into<String>>(mut self, key: K) -> Self {
+        self.env_vars.push((key.into(), None));
+        self
+    }
+
+    /// Do not inherit the environment variables of the current process.
+    pub fn env_clear(mut self) -> Self {
+        self.env_clear = true;
+        self.env_vars.clear();
+        self
+    }
+
+    /// Set how the main window of the process is shown, such as `SW_HIDE`, `SW_MAXIMIZE`.
+    pub fn show_window(mut self, cmd: SHOW_WINDOW_CMD) -> Self {
+        self.startup_info.dwFlags |= STARTF_USESHOWWINDOW;
+        self.startup_info.wShowWindow = cmd.0 as _;
+        self
+    }
+
+    /// Set the flags that control the priority class and the creation of the process, such as `CREATE_NO_WINDOW`.
+    pub fn creation_flags(mut self, flags: PROCESS_CREATION_FLAGS) -> Self {
+        self.creation_flags = flags;
+        self
+    }
+
+    /// Set to capture stdout and stderr of the process, which can be retrieved by `wait_with_output()`.
+    /// 
+    /// The process has no stdin when the output is captured.
+    pub fn capture_output(mut self, capture: bool) -> Self {
+        self.capture_output = capture;
+        self
+    }
+
+    /// Set to run the process in a job object, which kills the whole process tree when the `Process` is dropped.
+    pub fn kill_on_drop(mut self, kill_on_drop: bool) -> Self {
+        self.kill_on_drop = kill_on_drop;
+        self
+    }
+
     /// Set the current directory as `dir`, which is the full path to the current directory for the process. 
     pub fn current_directory<S: Into<String>>(mut self, dir: S) -> Self {
         self.cur_dir = Some(dir.into());
@@ -124,19 +331,58 @@ impl Process {
             let cmd = to_pwstr!(self.command);
             let cur_dir = to_pcwstr!(self.cur_dir);
 
-            unsafe {
+            let mut flags = self.creation_flags;
+            let env = if self.env_clear || !self.env_vars.is_empty() {
+                flags |= CREATE_UNICODE_ENVIRONMENT;
+                let base: Vec<(String, String)> = if self.env_clear {
+                    Vec::new()
+                } else {
+                    std::env::vars_os().map(|(k, v)| (k.to_string_lossy().into_owned(), v.to_string_lossy().into_owned())).collect()
+                };
+                Some(build_env_block(base, &self.env_vars))
+            } else {
+                None
+            };
+
+            if self.capture_output {
+                (self.stdout_pipe, self.startup_info.hStdOutput) = create_pipe()?;
+                (self.stderr_pipe, self.startup_info.hStdError) = create_pipe()?;
+                self.startup_info.dwFlags |= STARTF_USESTDHANDLES;
+            }
+
+            if self.kill_on_drop {
+                self.job = create_job()?;
+                flags |= CREATE_SUSPENDED;
+            }
+
+            let ret = unsafe {
                 CreateProcessW(app, 
                     cmd,
                     None, 
                     None, 
                     true, 
-                    PROCESS_CREATION_FLAGS::default(), 
-                    None,
+                    flags, 
+                    env.as_ref().map(|block| block.as_ptr() as *const c_void),
                     cur_dir,
                     &self.startup_info,
-                    &mut self.proc_info)?
+                    &mut self.proc_info)
             };
 
+            close_handle!(self.startup_info.hStdOutput);
+            close_handle!(self.startup_info.hStdError);
+            ret?;
+
+            if !self.job.is_invalid() {
+                if let Err(e) = unsafe { AssignProcessToJobObject(self.job, self.proc_info.hProcess) } {
+                    let _ = self.terminate(1);
+                    return Err(e.into());
+                }
+
+                if !self.creation_flags.contains(CREATE_SUSPENDED) {
+                    unsafe { ResumeThread(self.proc_info.hThread) };
+                }
+            }
+
             if let Some(timeout) = self.wait_for_idle {
                 unsafe { WaitForInputIdle(self.proc_info.hProcess, timeout) };
             }
@@ -145,13 +391,50 @@ impl Process {
         }
     }
 
-    // /// Exit the process with `exit_code` by force.
-    // pub fn terminate(&self, exit_code: u32) -> Result<()> {
-    //     unsafe {
-    //         TerminateProcess(self.proc_info.hProcess, exit_code)?
-    //     };
-    //     Ok(())
-    // }
+    /// Get the identifier of the process. `0` is returned when the process is not started.
+    pub fn get_process_id(&self) -> u32 {
+        self.proc_info.dwProcessId
+    }
+
+    /// Describes the process as the root of its process tree from the held handle, which works even after the process
+    /// exits, such as a launcher which exits right after spawning the application.
+    fn get_root_entry(&self) -> ProcessEntry {
+        ProcessEntry {
+            process_id: self.get_process_id(),
+            creation_time: get_creation_time(self.proc_info.hProcess).unwrap_or_default(),
+            ..Default::default()
+        }
+    }
+
+    /// Exit the process with `exit_code` by force.
+    pub fn terminate(&self, exit_code: u32) -> Result<()> {
+        unsafe {
+            TerminateProcess(self.proc_info.hProcess, exit_code)?
+        };
+        Ok(())
+    }
+
+    /// Exit the process and all its descendant processes with `exit_code` by force.
+    pub fn kill_tree(&self, exit_code: u32) -> Result<()> {
+        if !self.job.is_invalid() {
+            unsafe { TerminateJobObject(self.job, exit_code)? };
+            return Ok(());
+        }
+
+        // without a job, the tree is rebuilt from the parent links, which are checked by the creation times
+        // because the process identifiers are reused.
+        let entries = get_processes()?;
+        for entry in get_descendants(&self.get_root_entry(), &entries) {
+            if let Ok(handle) = unsafe { OpenProcess(PROCESS_TERMINATE | PROCESS_QUERY_LIMITED_INFORMATION, false, entry.process_id) } {
+                if get_creation_time(handle) == Some(entry.creation_time) {
+                    let _ = unsafe { TerminateProcess(handle, exit_code) };
+                }
+                let _ = unsafe { CloseHandle(handle) };
+            }
+        }
+
+        self.terminate(exit_code)
+    }
 
     /// Wait for the process to exit.
     /// 
@@ -177,6 +460,79 @@ impl Process {
         self.wait_for(INFINITE)
     }
 
+    /// Checks whether the process has exited, without waiting.
+    pub fn is_exited(&self) -> Result<bool> {
+        match self.wait_for(0) {
+            Ok(_) => Ok(true),
+            Err(e) if e.code() == ERR_TIMEOUT => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Wait for the process to exit without blocking the thread, checking every `interval` milliseconds by `timer`.
+    /// 
+    /// `timeout` is the milliseconds to wait for.
+    #[cfg(feature = "async")]
+    pub async fn wait_async<T: AsyncTimer>(&self, timer: &T, timeout: u64, interval: u64) -> Result<()> {
+        wait_until(timer, timeout, interval, || self.is_exited()).await
+    }
+
+    /// Retrieves the top-level elements of the process and its descendant processes, such as the windows created by a launcher.
+    pub fn windows(&self, automation: &UIAutomation) -> Result<Vec<UIElement>> {
+        let mut pids: HashSet<u32> = HashSet::from([self.get_process_id()]);
+        pids.extend(get_descendants(&self.get_root_entry(), &get_processes()?).iter().map(|entry| entry.process_id));
+
+        get_process_windows(automation, &pids)
+    }
+
+    /// Waits up to `timeout` milliseconds for the main window of the process.
+    /// 
+    /// The window of the process itself is preferred over the windows of its descendant processes.
+    /// 
+    /// # Examples
+    /// ```
+    /// use uiautomation::UIAutomation;
+    /// use uiautomation::processes::Process;
+    /// 
+    /// let notepad = Process::create("notepad.exe").unwrap();
+    /// let automation = UIAutomation::new().unwrap();
+    /// let window = notepad.main_window(&automation, 5000).unwrap();
+    /// assert!(window.get_classname().is_ok());
+    /// ```
+    pub fn main_window(&self, automation: &UIAutomation, timeout: u64) -> Result<UIElement> {
+        let pid = self.get_process_id() as i32;
+        let ret = retry(RetrySchedule::new(timeout, 100), None, || {
+            let mut windows = self.windows(automation)?;
+            let main = windows.iter().position(|w| w.get_process_id() == Ok(pid)).unwrap_or(0);
+            Ok(if windows.is_empty() { None } else { Some(windows.swap_remove(main)) })
+        });
+
+        match ret {
+            Ok(Some(window)) => Ok(window),
+            Ok(None) => Err(Error::new(ERR_NOTFOUND, "can not find the main window")),
+            Err(e) if e.code() == ERR_TIMEOUT => Err(Error::new(ERR_TIMEOUT, "wait for the main window time out")),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Wait until the process exits, and collect its exit code and the captured output. See `capture_output()`.
+    pub fn wait_with_output(&mut self) -> Result<ProcessOutput> {
+        let stderr_pipe = mem::take(&mut self.stderr_pipe);
+        let stderr_reader = thread::spawn(move || read_pipe(stderr_pipe));
+
+        let stdout_pipe = mem::take(&mut self.stdout_pipe);
+        let stdout = read_pipe(stdout_pipe);
+        let stderr = stderr_reader.join().unwrap_or_else(|_| Err(Error::new(ERR_NONE, "failed to read stderr")));
+
+        self.wait()?;
+
+        Ok(ProcessOutput {
+            exit_code: self.get_exit_code()?,
+            stdout: stdout?,
+            stderr: stderr?
+        })
+    }
+
     /// Get the exit code of the process.
     pub fn get_exit_code(&self) -> Result<u32> {
         let mut exit_code: u32 = 0;
@@ -188,15 +544,6 @@ impl Process {
     }
 }
 
-macro_rules! close_handle {
-    ($handle: expr) => {
-        if !$handle.is_invalid() {
-            let _ = unsafe { CloseHandle($handle) };
-            $handle = HANDLE::default();
-        }
-    };
-}
-
 impl Drop for Process {
     fn drop(&mut self) {
         close_handle!(self.startup_info.hStdInput);
@@ -205,6 +552,10 @@ impl Drop for Process {
 
         close_handle!(self.proc_info.hThread);
         close_handle!(self.proc_info.hProcess);
+
+        close_handle!(self.stdout_pipe);
+        close_handle!(self.stderr_pipe);
+        close_handle!(self.job);
     }
 }
 
@@ -215,15 +566,317 @@ impl Default for Process {
             command: None, 
             cur_dir: None,
             wait_for_idle: None,
+            env_clear: false,
+            env_vars: Vec::new(),
+            creation_flags: PROCESS_CREATION_FLAGS::default(),
+            capture_output: false,
+            kill_on_drop: false,
             startup_info: Self::startupinfo(), 
-            proc_info: Default::default() 
+            proc_info: Default::default(),
+            stdout_pipe: HANDLE::default(),
+            stderr_pipe: HANDLE::default(),
+            job: HANDLE::default()
+        }
+    }
+}
+
+/// Quotes `arg` by the Windows command-line rules, so that it is parsed as one argument by `CommandLineToArgvW` and the C runtime.
+/// 
+/// # Examples
+/// ```
+/// use uiautomation::processes::quote_arg;
+/// 
+/// assert_eq!(quote_arg("notepad.exe"), "notepad.exe");
+/// assert_eq!(quote_arg("C:\\My Files\\"), "\"C:\\My Files\\\\\"");
+/// ```
+pub fn quote_arg(arg: &str) -> String {
+    if !arg.is_empty() && !arg.contains([' ', '\t', '\n', '\x0b', '"']) {
+        return arg.to_string();
+    }
+
+    let mut quoted = String::with_capacity(arg.len() + 2);
+    quoted.push('"');
+
+    let mut backslashes = 0;
+    for ch in arg.chars() {
+        match ch {
+            '\\' => backslashes += 1,
+            '"' => {
+                quoted.extend(std::iter::repeat_n('\\', backslashes * 2 + 1));
+                quoted.push('"');
+                backslashes = 0;
+            },
+            _ => {
+                quoted.extend(std::iter::repeat_n('\\', backslashes));
+                quoted.push(ch);
+                backslashes = 0;
+            }
+        }
+    }
+
+    quoted.extend(std::iter::repeat_n('\\', backslashes * 2));
+    quoted.push('"');
+    quoted
+}
+
+/// Builds a command line from `args`. Every argument is quoted by `quote_arg()`.
+pub fn build_command_line<I, S>(args: I) -> String where I: IntoIterator<Item = S>, S: AsRef<str> {
+    args.into_iter().map(|arg| quote_arg(arg.as_ref())).collect::<Vec<_>>().join(" ")
+}
+
+/// Builds a sorted unicode environment block from `base` variables with `changes` applied. The variable names are case-insensitive.
+fn build_env_block(base: Vec<(String, String)>, changes: &[(String, Option<String>)]) -> Vec<u16> {
+    let mut vars: BTreeMap<String, (String, String)> = BTreeMap::new();
+    for (key, value) in base {
+        vars.insert(key.to_uppercase(), (key, value));
+    }
+    for (key, value) in changes {
+        match value {
+            Some(value) => vars.insert(key.to_uppercase(), (key.clone(), value.clone())),
+            None => vars.remove(&key.to_uppercase()),
+        };
+    }
+
+    let mut block: Vec<u16> = Vec::new();
+    for (key, value) in vars.values() {
+        block.extend(format!("{}={}", key, value).encode_utf16());
+        block.push(0);
+    }
+    if block.is_empty() {
+        block.push(0);
+    }
+    block.push(0);
+
+    block
+}
+
+fn create_pipe() -> Result<(HANDLE, HANDLE)> {
+    let attributes = SECURITY_ATTRIBUTES {
+        nLength: mem::size_of::<SECURITY_ATTRIBUTES>() as _,
+        lpSecurityDescriptor: std::ptr::null_mut(),
+        bInheritHandle: true.into()
+    };
+
+    let mut read = HANDLE::default();
+    let mut write = HANDLE::default();
+    unsafe {
+        CreatePipe(&mut read, &mut write, Some(&attributes), 0)?;
+        SetHandleInformation(read, HANDLE_FLAG_INHERIT.0, HANDLE_FLAGS(0))?;
+    }
+
+    Ok((read, write))
+}
+
+fn read_pipe(pipe: HANDLE) -> Result<Vec<u8>> {
+    let mut output: Vec<u8> = Vec::new();
+    let mut buffer = [0u8; 4096];
+    let mut ret = Ok(());
+    while !pipe.is_invalid() {
+        let mut read: u32 = 0;
+        match unsafe { ReadFile(pipe, Some(&mut buffer), Some(&mut read), None) } {
+            Ok(_) if read > 0 => output.extend_from_slice(&buffer[..read as usize]),
+            Ok(_) => break,
+            Err(e) if e.code() == ERROR_BROKEN_PIPE.to_hresult() => break,
+            Err(e) => {
+                ret = Err(e.into());
+                break;
+            }
         }
     }
+
+    if !pipe.is_invalid() {
+        let _ = unsafe { CloseHandle(pipe) };
+    }
+    ret.map(|_| output)
+}
+
+fn create_job() -> Result<HANDLE> {
+    let job = unsafe { CreateJobObjectW(None, None)? };
+
+    let mut info = JOBOBJECT_EXTENDED_LIMIT_INFORMATION::default();
+    info.BasicLimitInformation.LimitFlags = JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE;
+    let ret = unsafe {
+        SetInformationJobObject(job, 
+            JobObjectExtendedLimitInformation, 
+            &info as *const _ as *const c_void, 
+            mem::size_of::<JOBOBJECT_EXTENDED_LIMIT_INFORMATION>() as _)
+    };
+
+    if let Err(e) = ret {
+        let _ = unsafe { CloseHandle(job) };
+        Err(e.into())
+    } else {
+        Ok(job)
+    }
+}
+
+/// The information of a running process.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ProcessEntry {
+    /// The identifier of the process.
+    pub process_id: u32,
+    /// The identifier of the process that created this process.
+    pub parent_process_id: u32,
+    /// The name of the executable file, such as `notepad.exe`.
+    pub name: String,
+    /// The creation time of the process in 100-nanosecond intervals since January 1, 1601 (UTC), or 0 if it cannot be queried.
+    pub creation_time: u64
+}
+
+impl ProcessEntry {
+    /// Returns `true` if the executable name matches `name` case-insensitively. The `.exe` extension of `name` is optional.
+    pub fn is_named(&self, name: &str) -> bool {
+        let exe = self.name.to_lowercase();
+        let name = name.to_lowercase();
+        exe == name || exe.strip_suffix(".exe") == Some(name.as_str())
+    }
+}
+
+/// Retrieves all the running processes.
+pub fn get_processes() -> Result<Vec<ProcessEntry>> {
+    let snapshot = unsafe { CreateToolhelp32Snapshot(TH32CS_SNAPPROCESS, 0)? };
+
+    let mut entries: Vec<ProcessEntry> = Vec::new();
+    let mut entry = PROCESSENTRY32W {
+        dwSize: mem::size_of::<PROCESSENTRY32W>() as _,
+        ..Default::default()
+    };
+    let mut next = unsafe { Process32FirstW(snapshot, &mut entry) };
+    while next.is_ok() {
+        let len = entry.szExeFile.iter().position(|c| *c == 0).unwrap_or(entry.szExeFile.len());
+        entries.push(ProcessEntry {
+            process_id: entry.th32ProcessID,
+            parent_process_id: entry.th32ParentProcessID,
+            name: String::from_utf16_lossy(&entry.szExeFile[..len]),
+            creation_time: query_creation_time(entry.th32ProcessID).unwrap_or_default()
+        });
+        next = unsafe { Process32NextW(snapshot, &mut entry) };
+    }
+
+    let _ = unsafe { CloseHandle(snapshot) };
+    Ok(entries)
+}
+
+/// Retrieves the running processes whose executable name is `name`, such as `notepad` or `notepad.exe`.
+pub fn find_processes(name: &str) -> Result<Vec<ProcessEntry>> {
+    let entries = get_processes()?;
+    Ok(entries.into_iter().filter(|entry| entry.is_named(name)).collect())
+}
+
+/// Splits an AppUserModelID into the package family name and the application id.
+/// 
+/// # Examples
+/// ```
+/// use uiautomation::processes::split_aumid;
+/// 
+/// let (family, app) = split_aumid("Microsoft.WindowsCalculator_8wekyb3d8bbwe!App").unwrap();
+/// assert_eq!(family, "Microsoft.WindowsCalculator_8wekyb3d8bbwe");
+/// assert_eq!(app, "App");
+/// ```
+pub fn split_aumid(aumid: &str) -> Result<(&str, &str)> {
+    match aumid.split_once('!') {
+        Some((family, app)) if !family.is_empty() && !app.is_empty() && !app.contains('!') && !aumid.contains(char::is_whitespace) => Ok((family, app)),
+        _ => Err(Error::new(ERR_FORMAT, "invalid AppUserModelID")),
+    }
+}
+
+/// The class name of the frame window which hosts packaged applications.
+const APPLICATION_FRAME_WINDOW: &str = "ApplicationFrameWindow";
+
+/// Retrieves the top-level elements which belong to the processes of `pids`.
+/// 
+/// Packaged applications are hosted by `ApplicationFrameHost.exe`, so their frame windows are matched by the children.
+pub(crate) fn get_process_windows(automation: &UIAutomation, pids: &HashSet<u32>) -> Result<Vec<UIElement>> {
+    let root = automation.get_root_element()?;
+    let condition = automation.create_true_condition()?;
+    let contains = |element: &UIElement| element.get_process_id().is_ok_and(|pid| pids.contains(&(pid as u32)));
+
+    let mut windows: Vec<UIElement> = Vec::new();
+    for element in root.find_all(TreeScope::Children, &condition)? {
+        if contains(&element) {
+            windows.push(element);
+        } else if element.get_classname().is_ok_and(|name| name == APPLICATION_FRAME_WINDOW) {
+            let children = element.find_all(TreeScope::Children, &condition).unwrap_or_default();
+            if children.iter().any(contains) {
+                windows.push(element);
+            }
+        }
+    }
+
+    Ok(windows)
+}
+
+/// Retrieves the creation time of the process `handle`.
+fn get_creation_time(handle: HANDLE) -> Option<u64> {
+    let mut creation = FILETIME::default();
+    let mut exit = FILETIME::default();
+    let mut kernel = FILETIME::default();
+    let mut user = FILETIME::default();
+    unsafe { GetProcessTimes(handle, &mut creation, &mut exit, &mut kernel, &mut user) }.ok()?;
+    Some(((creation.dwHighDateTime as u64) << 32) | creation.dwLowDateTime as u64)
+}
+
+/// Opens the process `pid` and retrieves its creation time.
+fn query_creation_time(pid: u32) -> Option<u64> {
+    let handle = unsafe { OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, pid) }.ok()?;
+    let creation_time = get_creation_time(handle);
+    let _ = unsafe { CloseHandle(handle) };
+    creation_time
+}
+
+/// Returns `true` if `child` is created by `parent`.
+/// 
+/// A parent link is stale when the parent exited and its identifier was reused by a newer process, so the child must not
+/// be created before the parent. The link is trusted when either creation time is unavailable, such as an elevated
+/// child which can not be queried.
+fn is_child_of(child: &ProcessEntry, parent: &ProcessEntry) -> bool {
+    let unknown = child.creation_time == 0 || parent.creation_time == 0;
+    child.parent_process_id == parent.process_id && child.process_id != parent.process_id && (unknown || child.creation_time >= parent.creation_time)
+}
+
+/// Collects the descendants of `root` from the process entries, in breadth-first order.
+/// 
+/// `root` may be absent from the entries if it has exited. The stale parent links are skipped, and the ancestors of
+/// `root` are never included.
+fn get_descendants<'a>(root: &ProcessEntry, entries: &'a [ProcessEntry]) -> Vec<&'a ProcessEntry> {
+    let mut visited: HashSet<u32> = HashSet::from([root.process_id]);
+    let mut current = entries.iter().find(|entry| entry.process_id == root.process_id).unwrap_or(root);
+    while let Some(parent) = entries.iter().find(|entry| is_child_of(current, entry)) {
+        if !visited.insert(parent.process_id) {
+            break;
+        }
+        current = parent;
+    }
+
+    let mut descendants: Vec<&ProcessEntry> = Vec::new();
+    let mut index = 0;
+    let mut parent = root;
+    loop {
+        for entry in entries {
+            if is_child_of(entry, parent) && visited.insert(entry.process_id) {
+                descendants.push(entry);
+            }
+        }
+
+        if index >= descendants.len() {
+            break;
+        }
+        parent = descendants[index];
+        index += 1;
+    }
+
+    descendants
 }
 
 #[cfg(test)]
 mod tests {
     use crate::processes::Process;
+    use crate::processes::build_command_line;
+    use crate::processes::build_env_block;
+    use crate::processes::ProcessEntry;
+    use crate::processes::get_descendants;
+    use crate::processes::quote_arg;
+    use crate::processes::split_aumid;
 
     #[test]
     fn run_notepad() {
@@ -246,4 +899,120 @@ mod tests {
             .wait_for_idle(5000);
         calc.run().unwrap();
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn run_capture_output() {
+        let mut echo = Process::new("cmd.exe /c echo hello&& echo oops 1>&2 && exit 3")
+            .env("UIA_TEST", "1")
+            .capture_output(true)
+            .kill_on_drop(true);
+        echo.run().unwrap();
+
+        let output = echo.wait_with_output().unwrap();
+        assert_eq!(output.exit_code, 3);
+        assert_eq!(output.stdout_text().trim(), "hello");
+        assert_eq!(output.stderr_text().trim(), "oops");
+    }
+
+    #[test]
+    fn test_quote_arg() {
+        assert_eq!(quote_arg("notepad.exe"), "notepad.exe");
+        assert_eq!(quote_arg(r"C:\Windows\notepad.exe"), r"C:\Windows\notepad.exe");
+        assert_eq!(quote_arg(""), r#""""#);
+        assert_eq!(quote_arg("hello world"), r#""hello world""#);
+        assert_eq!(quote_arg(r#"say "hi""#), r#""say \"hi\"""#);
+        assert_eq!(quote_arg(r#"a\"b"#), r#""a\\\"b""#);
+        assert_eq!(quote_arg(r"C:\My Files\"), r#""C:\My Files\\""#);
+        assert_eq!(quote_arg(r"a\\b c"), r#""a\\b c""#);
+        assert_eq!(quote_arg("tab\there"), "\"tab\there\"");
+    }
+
+    #[test]
+    fn test_command_line() {
+        assert_eq!(build_command_line(["ping.exe", "localhost", "-n", "1"]), "ping.exe localhost -n 1");
+        assert_eq!(build_command_line(["app.exe", "a b", ""]), r#"app.exe "a b" """#);
+
+        let p = Process::new("notepad.exe").arg("my file.txt").args(["-x", "y z"]);
+        assert_eq!(p.command.as_deref(), Some(r#"notepad.exe "my file.txt" -x "y z""#));
+
+        let p = Process::default().args(["cmd.exe", "/c"]);
+        assert_eq!(p.command.as_deref(), Some("cmd.exe /c"));
+    }
+
+    fn decode_env_block(block: &[u16]) -> Vec<String> {
+        assert_eq!(&block[block.len() - 2..], &[0, 0]);
+        String::from_utf16(&block[..block.len() - 2]).unwrap().split('\0').filter(|s| !s.is_empty()).map(String::from).collect()
+    }
+
+    #[test]
+    fn test_env_block() {
+        let base = vec![("Path".to_string(), "C:\\Windows".to_string()), ("TEMP".to_string(), "C:\\Temp".to_string()), ("b".to_string(), "2".to_string())];
+        let changes = vec![("PATH".to_string(), Some("D:\\bin".to_string())), ("temp".to_string(), None), ("A".to_string(), Some("1".to_string()))];
+
+        let block = build_env_block(base, &changes);
+        assert_eq!(decode_env_block(&block), vec!["A=1", "b=2", "PATH=D:\\bin"]);
+
+        let empty = build_env_block(Vec::new(), &[]);
+        assert_eq!(empty, vec![0, 0]);
+    }
+
+    #[test]
+    fn test_descendants() {
+        // process 1 claims the reused identifier 30 as its parent, and process 5 was created before its claimed parent 30.
+        // the creation time of process 12 is unknown, so its link is trusted.
+        let entries: Vec<ProcessEntry> = [(1, 30, 100), (10, 1, 110), (11, 1, 111), (12, 1, 0), (20, 10, 120), (30, 20, 130), (5, 30, 90), (99, 7, 199)].iter().map(|&(process_id, parent_process_id, creation_time)| ProcessEntry {
+            process_id,
+            parent_process_id,
+            name: String::new(),
+            creation_time
+        }).collect();
+        let get_pids = |pid: u32| {
+            let root = entries.iter().find(|entry| entry.process_id == pid).cloned().unwrap_or(ProcessEntry { process_id: pid, ..Default::default() });
+            get_descendants(&root, &entries).iter().map(|entry| entry.process_id).collect::<Vec<u32>>()
+        };
+        assert_eq!(get_pids(1), vec![10, 11, 12, 20, 30]);
+        assert_eq!(get_pids(10), vec![20, 30]);
+        assert!(get_pids(42).is_empty());
+        assert!(get_pids(99).is_empty());
+
+        // the launcher 50 has exited, so its children are found from the root built by its handle.
+        let launched: Vec<ProcessEntry> = [(60, 50, 150), (61, 60, 160), (62, 50, 95)].iter().map(|&(process_id, parent_process_id, creation_time)| ProcessEntry {
+            process_id,
+            parent_process_id,
+            name: String::new(),
+            creation_time
+        }).collect();
+        let launcher = ProcessEntry { process_id: 50, creation_time: 140, ..Default::default() };
+        assert_eq!(get_descendants(&launcher, &launched).iter().map(|entry| entry.process_id).collect::<Vec<u32>>(), vec![60, 61]);
+
+        // without the creation times, the parent links are trusted but the ancestors are still excluded.
+        let entries: Vec<ProcessEntry> = entries.iter().map(|entry| ProcessEntry {
+            parent_process_id: if entry.process_id == 1 { 0 } else { entry.parent_process_id },
+            creation_time: 0,
+            ..entry.clone()
+        }).collect();
+        assert_eq!(get_descendants(&entries[1], &entries).iter().map(|entry| entry.process_id).collect::<Vec<u32>>(), vec![20, 30, 5]);
+    }
+
+    #[test]
+    fn test_process_name() {
+        let entry = ProcessEntry {
+            name: "Notepad.exe".into(),
+            ..Default::default()
+        };
+        assert!(entry.is_named("notepad.exe"));
+        assert!(entry.is_named("NOTEPAD"));
+        assert!(!entry.is_named("note"));
+        assert!(!entry.is_named("notepad.com"));
+    }
+
+    #[test]
+    fn test_split_aumid() {
+        assert_eq!(split_aumid("windows.immersivecontrolpanel_cw5n1h2txyewy!microsoft.windows.immersivecontrolpanel"), Ok(("windows.immersivecontrolpanel_cw5n1h2txyewy", "microsoft.windows.immersivecontrolpanel")));
+        assert!(split_aumid("notepad.exe").is_err());
+        assert!(split_aumid("!App").is_err());
+        assert!(split_aumid("Family!").is_err());
+        assert!(split_aumid("a!b!c").is_err());
+        assert!(split_aumid("a b!App").is_err());
+    }
+}
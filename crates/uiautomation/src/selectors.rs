@@ -0,0 +1,417 @@
+use std::fmt::Display;
+use std::str::FromStr;
+
+use super::controls::ControlType;
+use super::core::UIAutomation;
+use super::core::UIElement;
+use super::core::UIMatcher;
+use super::errors::ERR_FORMAT;
+use super::errors::ERR_NOTFOUND;
+use super::errors::Error;
+use super::errors::Result;
+use super::snapshots::ElementSnapshot;
+
+/// One step of a `Selector`, which matches a child of the element matched by the previous step.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SelectorStep {
+    pub control_type: Option<ControlType>,
+    pub automation_id: Option<String>,
+    pub name: Option<String>,
+    pub classname: Option<String>,
+    /// The zero-based index among the siblings matched by the other attributes.
+    pub index: usize
+}
+
+impl SelectorStep {
+    /// Returns `true` if `snapshot` matches the attributes of this step, ignoring the index.
+    pub fn matches(&self, snapshot: &ElementSnapshot) -> bool {
+        let same = |expected: &Option<String>, actual: &String| expected.as_ref().is_none_or(|v| v == actual);
+
+        self.control_type.is_none_or(|t| snapshot.control_type == Some(t))
+            && same(&self.automation_id, &snapshot.automation_id)
+            && same(&self.name, &snapshot.name)
+            && same(&self.classname, &snapshot.classname)
+    }
+
+    /// Creates a matcher which finds the element of this step among the children of `parent`.
+    pub fn to_matcher(&self, automation: &UIAutomation, parent: &UIElement) -> UIMatcher {
+        let mut matcher = automation.create_matcher().from_ref(parent).depth(2).timeout(0).nth(self.index);
+        if let Some(control_type) = self.control_type {
+            matcher = matcher.control_type(control_type);
+        }
+        if let Some(ref automation_id) = self.automation_id {
+            matcher = matcher.automation_id(automation_id);
+        }
+        if let Some(ref name) = self.name {
+            matcher = matcher.name(name);
+        }
+        if let Some(ref classname) = self.classname {
+            matcher = matcher.classname(classname);
+        }
+        matcher
+    }
+
+    /// Selects the most stable attributes to identify `siblings[index]` among its siblings.
+    ///
+    /// The automation id is preferred, then the name and the control type, then the classname and the control type.
+    /// If none of them is unique, the index among the siblings of the same control type (and name) is used.
+    pub fn select(siblings: &[ElementSnapshot], index: usize) -> Result<Self> {
+        let Some(node) = siblings.get(index) else {
+            return Err(Error::new(ERR_NOTFOUND, "the element is not found among its siblings"));
+        };
+
+        let non_empty = |value: &String| if value.is_empty() { None } else { Some(value.clone()) };
+        let candidates = [
+            Self {
+                automation_id: non_empty(&node.automation_id),
+                ..Default::default()
+            },
+            Self {
+                control_type: node.control_type,
+                name: non_empty(&node.name),
+                ..Default::default()
+            },
+            Self {
+                control_type: node.control_type,
+                classname: non_empty(&node.classname),
+                ..Default::default()
+            },
+        ];
+
+        let unique = candidates.into_iter()
+            .filter(|step| step.automation_id.is_some() || step.name.is_some() || step.classname.is_some())
+            .find(|step| siblings.iter().filter(|s| step.matches(s)).count() == 1);
+        if let Some(step) = unique {
+            return Ok(step);
+        }
+
+        let mut step = Self {
+            control_type: node.control_type,
+            name: non_empty(&node.name),
+            ..Default::default()
+        };
+        step.index = siblings[..index].iter().filter(|s| step.matches(s)).count();
+        Ok(step)
+    }
+}
+
+impl Display for SelectorStep {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.control_type {
+            Some(control_type) => write!(f, "{:?}", control_type)?,
+            None => write!(f, "*")?,
+        }
+
+        let attributes = [("id", &self.automation_id), ("name", &self.name), ("class", &self.classname)];
+        for (key, value) in attributes {
+            if let Some(value) = value {
+                write!(f, "[@{}=\"{}\"]", key, value.replace('\\', "\\\\").replace('"', "\\\""))?;
+            }
+        }
+
+        if self.index > 0 {
+            write!(f, "[{}]", self.index + 1)?;
+        }
+        Ok(())
+    }
+}
+
+/// A path of steps from the root element to an element, which re-finds the element in the UI.
+///
+/// The string form is like `/Window[@name="Untitled - Notepad"]/Pane[@class="Edit"]/Button[2]`. The indexes in the string are one-based.
+///
+/// # Examples
+/// ```
+/// use uiautomation::UIAutomation;
+/// use uiautomation::selectors::Selector;
+///
+/// let automation = UIAutomation::new().unwrap();
+/// if let Ok(element) = automation.get_focused_element() {
+///     if let Ok(selector) = Selector::from_element(&automation, &element) {
+///         println!("{}", selector);
+///         let found = selector.find(&automation);
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Selector {
+    pub steps: Vec<SelectorStep>
+}
+
+impl Selector {
+    /// Creates a selector of the descendant of `root` at `path`, which lists the indexes of children from `root`.
+    pub fn from_snapshot(root: &ElementSnapshot, path: &[usize]) -> Result<Self> {
+        let mut steps: Vec<SelectorStep> = Vec::with_capacity(path.len());
+        let mut node = root;
+        for index in path {
+            steps.push(SelectorStep::select(&node.children, *index)?);
+            node = &node.children[*index];
+        }
+
+        Ok(Self {
+            steps
+        })
+    }
+
+    /// Creates a selector of `element` from the root element, walking the control view.
+    pub fn from_element(automation: &UIAutomation, element: &UIElement) -> Result<Self> {
+        let walker = automation.get_control_view_walker()?;
+        let mut chain: Vec<UIElement> = element.ancestors(&walker).collect::<Result<Vec<UIElement>>>()?;
+        // the last ancestor is the root element.
+        let Some(root) = chain.pop() else {
+            return Ok(Self::default());
+        };
+        chain.reverse();
+        chain.push(element.clone());
+
+        let mut steps: Vec<SelectorStep> = Vec::with_capacity(chain.len());
+        let mut parent = root;
+        for node in chain {
+            let runtime_id = node.get_runtime_id()?;
+            let siblings: Vec<ElementSnapshot> = parent.children(&walker)
+                .map(|child| ElementSnapshot::from_element(&child?))
+                .collect::<Result<Vec<ElementSnapshot>>>()?;
+            let Some(index) = siblings.iter().position(|s| s.runtime_id == runtime_id) else {
+                return Err(Error::new(ERR_NOTFOUND, "the element is not found among its siblings"));
+            };
+
+            steps.push(SelectorStep::select(&siblings, index)?);
+            parent = node;
+        }
+
+        Ok(Self {
+            steps
+        })
+    }
+
+    /// Finds the element from the root element.
+    pub fn find(&self, automation: &UIAutomation) -> Result<UIElement> {
+        let mut element = automation.get_root_element()?;
+        for step in &self.steps {
+            element = step.to_matcher(automation, &element).find_first()?;
+        }
+        Ok(element)
+    }
+
+    /// Finds the path of the element in the snapshot of the root element.
+    pub fn find_in_snapshot(&self, root: &ElementSnapshot) -> Option<Vec<usize>> {
+        let mut path: Vec<usize> = Vec::with_capacity(self.steps.len());
+        let mut node = root;
+        for step in &self.steps {
+            let (index, child) = node.children.iter()
+                .enumerate()
+                .filter(|(_, child)| step.matches(child))
+                .nth(step.index)?;
+            path.push(index);
+            node = child;
+        }
+        Some(path)
+    }
+}
+
+impl Display for Selector {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for step in &self.steps {
+            write!(f, "/{}", step)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Selector {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        SelectorParser::new(s).parse()
+    }
+}
+
+/// Parses the string form of `Selector`.
+struct SelectorParser<'a> {
+    chars: std::iter::Peekable<std::str::Chars<'a>>
+}
+
+impl<'a> SelectorParser<'a> {
+    fn new(text: &'a str) -> Self {
+        Self {
+            chars: text.trim().chars().peekable()
+        }
+    }
+
+    fn error(message: &str) -> Error {
+        Error::new(ERR_FORMAT, &format!("invalid selector: {}", message))
+    }
+
+    fn expect(&mut self, expected: char) -> Result<()> {
+        match self.chars.next() {
+            Some(c) if c == expected => Ok(()),
+            Some(c) => Err(Self::error(&format!("expected '{}', but got '{}'", expected, c))),
+            None => Err(Self::error(&format!("expected '{}'", expected))),
+        }
+    }
+
+    fn take_while<F: Fn(char) -> bool>(&mut self, predicate: F) -> String {
+        let mut text = String::new();
+        while let Some(c) = self.chars.next_if(|c| predicate(*c)) {
+            text.push(c);
+        }
+        text
+    }
+
+    fn parse(mut self) -> Result<Selector> {
+        let mut steps: Vec<SelectorStep> = Vec::new();
+        while self.chars.peek().is_some() {
+            self.expect('/')?;
+            steps.push(self.parse_step()?);
+        }
+
+        Ok(Selector {
+            steps
+        })
+    }
+
+    fn parse_step(&mut self) -> Result<SelectorStep> {
+        let mut step = SelectorStep::default();
+        let control_type = self.take_while(|c| c.is_alphanumeric() || c == '*');
+        step.control_type = match control_type.as_str() {
+            "*" => None,
+            "" => return Err(Self::error("missing control type")),
+            name => Some(name.parse::<ControlType>().map_err(|e| Self::error(e.message()))?),
+        };
+
+        while self.chars.next_if_eq(&'[').is_some() {
+            if self.chars.next_if_eq(&'@').is_some() {
+                let key = self.take_while(|c| c.is_alphanumeric());
+                self.expect('=')?;
+                let value = Some(self.parse_string()?);
+                match key.as_str() {
+                    "id" => step.automation_id = value,
+                    "name" => step.name = value,
+                    "class" => step.classname = value,
+                    _ => return Err(Self::error(&format!("unknown attribute '{}'", key))),
+                }
+            } else {
+                let index: usize = self.take_while(|c| c.is_ascii_digit()).parse().map_err(|_| Self::error("invalid index"))?;
+                if index == 0 {
+                    return Err(Self::error("the index is one-based"));
+                }
+                step.index = index - 1;
+            }
+            self.expect(']')?;
+        }
+
+        Ok(step)
+    }
+
+    fn parse_string(&mut self) -> Result<String> {
+        self.expect('"')?;
+        let mut text = String::new();
+        loop {
+            match self.chars.next() {
+                Some('"') => return Ok(text),
+                Some('\\') => match self.chars.next() {
+                    Some(c) => text.push(c),
+                    None => break,
+                },
+                Some(c) => text.push(c),
+                None => break,
+            }
+        }
+        Err(Self::error("unterminated string"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::controls::ControlType;
+    use crate::errors::ERR_FORMAT;
+    use crate::snapshots::ElementSnapshot;
+
+    use super::Selector;
+    use super::SelectorStep;
+
+    fn snapshot() -> ElementSnapshot {
+        let button = |name: &str| ElementSnapshot::new().name(name).control_type(ControlType::Button);
+        ElementSnapshot::new().name("Desktop").control_type(ControlType::Pane)
+            .child(ElementSnapshot::new().name("Notepad").classname("Notepad").control_type(ControlType::Window)
+                .child(ElementSnapshot::new().automation_id("15").classname("Edit").control_type(ControlType::Document))
+                .child(ElementSnapshot::new().classname("Toolbar").control_type(ControlType::ToolBar)
+                    .child(button("Open"))
+                    .child(button(""))
+                    .child(button("Save"))
+                    .child(button(""))))
+            .child(ElementSnapshot::new().name("Notepad").classname("Notepad").control_type(ControlType::Window)
+                .child(ElementSnapshot::new().automation_id("15").classname("Edit").control_type(ControlType::Document)))
+            .child(ElementSnapshot::new().name("Notepad").classname("Shell").control_type(ControlType::Pane))
+    }
+
+    #[test]
+    fn test_select_step() {
+        let root = snapshot();
+        let windows = &root.children;
+
+        let step = SelectorStep::select(&windows[0].children, 0).unwrap();
+        assert_eq!(step.automation_id.as_deref(), Some("15"));
+        assert_eq!(step.control_type, None);
+
+        let step = SelectorStep::select(&windows[0].children[1].children, 2).unwrap();
+        assert_eq!(step.name.as_deref(), Some("Save"));
+        assert_eq!(step.control_type, Some(ControlType::Button));
+
+        let step = SelectorStep::select(&windows[0].children[1].children, 3).unwrap();
+        assert_eq!(step.name, None);
+        assert_eq!(step.index, 3);
+
+        // the same name and class, distinguished by index.
+        let step = SelectorStep::select(windows, 1).unwrap();
+        assert_eq!(step.name.as_deref(), Some("Notepad"));
+        assert_eq!(step.index, 1);
+
+        // the same name, distinguished by control type.
+        let step = SelectorStep::select(windows, 2).unwrap();
+        assert_eq!(step.control_type, Some(ControlType::Pane));
+        assert_eq!(step.index, 0);
+
+        assert!(SelectorStep::select(windows, 3).is_err());
+    }
+
+    #[test]
+    fn test_refind_all() {
+        let root = snapshot();
+        let mut paths: Vec<Vec<usize>> = vec![vec![]];
+        while let Some(path) = paths.pop() {
+            let node = root.get_descendant(&path).unwrap();
+            for index in 0..node.children.len() {
+                let mut child = path.clone();
+                child.push(index);
+
+                let selector = Selector::from_snapshot(&root, &child).unwrap();
+                assert_eq!(selector.find_in_snapshot(&root), Some(child.clone()), "{}", selector);
+
+                let parsed: Selector = selector.to_string().parse().unwrap();
+                assert_eq!(parsed, selector);
+
+                paths.push(child);
+            }
+        }
+    }
+
+    #[test]
+    fn test_selector_string() {
+        let root = snapshot();
+        let selector = Selector::from_snapshot(&root, &[1, 0]).unwrap();
+        assert_eq!(selector.to_string(), r#"/Window[@name="Notepad"][2]/*[@id="15"]"#);
+
+        let selector: Selector = r#"/Window[@name="Say \"Hi\" / Bye"][@class="C:\\X"]/Button[3]"#.parse().unwrap();
+        assert_eq!(selector.steps.len(), 2);
+        assert_eq!(selector.steps[0].name.as_deref(), Some("Say \"Hi\" / Bye"));
+        assert_eq!(selector.steps[0].classname.as_deref(), Some("C:\\X"));
+        assert_eq!(selector.steps[1].index, 2);
+        assert_eq!(selector.to_string().parse::<Selector>().unwrap(), selector);
+
+        assert!("".parse::<Selector>().unwrap().steps.is_empty());
+        for invalid in ["Window", "/Unknown", "/Button[0]", "/Button[@foo=\"x\"]", "/Button[@name=\"x]", "/Button[@name=x]"] {
+            assert_eq!(invalid.parse::<Selector>().unwrap_err().code(), ERR_FORMAT, "{}", invalid);
+        }
+    }
+}
@@ -0,0 +1,152 @@
+use super::controls::ControlType;
+use super::core::UIElement;
+use super::core::UITreeWalker;
+use super::errors::Result;
+use super::types::Rect;
+
+/// A snapshot of the properties of an element and its descendants.
+///
+/// The snapshot is detached from the UI, so it can be inspected, compared or saved after the element is gone.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ElementSnapshot {
+    pub name: String,
+    pub automation_id: String,
+    pub classname: String,
+    pub control_type: Option<ControlType>,
+    pub framework_id: String,
+    pub process_id: i32,
+    pub bounding_rectangle: Rect,
+    pub runtime_id: Vec<i32>,
+    pub is_enabled: bool,
+    pub is_offscreen: bool,
+    pub children: Vec<ElementSnapshot>
+}
+
+impl ElementSnapshot {
+    /// Creates an empty snapshot, which can be filled by the builder functions.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the name.
+    pub fn name<S: Into<String>>(mut self, name: S) -> Self {
+        self.name = name.into();
+        self
+    }
+
+    /// Sets the automation id.
+    pub fn automation_id<S: Into<String>>(mut self, automation_id: S) -> Self {
+        self.automation_id = automation_id.into();
+        self
+    }
+
+    /// Sets the classname.
+    pub fn classname<S: Into<String>>(mut self, classname: S) -> Self {
+        self.classname = classname.into();
+        self
+    }
+
+    /// Sets the control type.
+    pub fn control_type(mut self, control_type: ControlType) -> Self {
+        self.control_type = Some(control_type);
+        self
+    }
+
+    /// Appends a child.
+    pub fn child(mut self, child: ElementSnapshot) -> Self {
+        self.children.push(child);
+        self
+    }
+
+    /// Captures the properties of `element`, without its children.
+    pub fn from_element(element: &UIElement) -> Result<Self> {
+        Ok(Self {
+            name: element.get_name()?,
+            automation_id: element.get_automation_id()?,
+            classname: element.get_classname()?,
+            control_type: element.get_control_type().ok(),
+            framework_id: element.get_framework_id()?,
+            process_id: element.get_process_id()?,
+            bounding_rectangle: element.get_bounding_rectangle()?,
+            runtime_id: element.get_runtime_id()?,
+            is_enabled: element.is_enabled()?,
+            is_offscreen: element.is_offscreen()?,
+            children: Vec::new()
+        })
+    }
+
+    /// Captures `element` and its descendants by `walker`, until `depth`. The children are at depth 1.
+    ///
+    /// The descendants which disappear or fail to be captured are skipped.
+    pub fn capture(element: &UIElement, walker: &UITreeWalker, depth: u32) -> Result<Self> {
+        let mut snapshot = Self::from_element(element)?;
+        if depth > 0 {
+            for child in element.children(walker).map_while(|c| c.ok()) {
+                if let Ok(child) = Self::capture(&child, walker, depth - 1) {
+                    snapshot.children.push(child);
+                }
+            }
+        }
+
+        Ok(snapshot)
+    }
+
+    /// Retrieves the descendant at `path`, which lists the indexes of children from this snapshot.
+    pub fn get_descendant(&self, path: &[usize]) -> Option<&ElementSnapshot> {
+        let mut node = self;
+        for index in path {
+            node = node.children.get(*index)?;
+        }
+        Some(node)
+    }
+
+    /// Finds the path of the first descendant, in depth first order, which matches `predicate`.
+    pub fn find_path<F>(&self, predicate: F) -> Option<Vec<usize>> where F: Fn(&ElementSnapshot) -> bool {
+        fn search<F: Fn(&ElementSnapshot) -> bool>(node: &ElementSnapshot, predicate: &F, path: &mut Vec<usize>) -> bool {
+            for (index, child) in node.children.iter().enumerate() {
+                path.push(index);
+                if predicate(child) || search(child, predicate, path) {
+                    return true;
+                }
+                path.pop();
+            }
+            false
+        }
+
+        let mut path = Vec::new();
+        if search(self, &predicate, &mut path) {
+            Some(path)
+        } else {
+            None
+        }
+    }
+
+    /// Counts this snapshot and all its descendants.
+    pub fn count(&self) -> usize {
+        1 + self.children.iter().map(|c| c.count()).sum::<usize>()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::controls::ControlType;
+
+    use super::ElementSnapshot;
+
+    #[test]
+    fn test_navigate() {
+        let root = ElementSnapshot::new().name("Desktop")
+            .child(ElementSnapshot::new().name("Notepad").control_type(ControlType::Window)
+                .child(ElementSnapshot::new().automation_id("15").control_type(ControlType::Document))
+                .child(ElementSnapshot::new().name("Close").control_type(ControlType::Button)))
+            .child(ElementSnapshot::new().name("Taskbar").control_type(ControlType::Pane));
+
+        assert_eq!(root.count(), 5);
+        assert_eq!(root.get_descendant(&[0, 1]).unwrap().name, "Close");
+        assert!(root.get_descendant(&[1, 0]).is_none());
+        assert_eq!(root.get_descendant(&[]).unwrap().name, "Desktop");
+        assert_eq!(root.find_path(|e| e.automation_id == "15"), Some(vec![0, 0]));
+        assert_eq!(root.find_path(|e| e.name == "Taskbar"), Some(vec![1]));
+        assert_eq!(root.find_path(|e| e.name == "Desktop"), None);
+    }
+}
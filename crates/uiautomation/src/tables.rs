@@ -0,0 +1,465 @@
+use std::collections::HashSet;
+use std::io::Write;
+
+use windows::Win32::Foundation::E_INVALIDARG;
+
+use super::controls::DataGridControl;
+use super::controls::TableControl;
+use super::core::UIElement;
+use super::errors::ERR_NONE;
+use super::errors::ERR_NOTFOUND;
+use super::errors::Error;
+use super::errors::Result;
+use super::patterns::UIGridItemPattern;
+use super::patterns::UIGridPattern;
+use super::patterns::UITablePattern;
+use super::patterns::UIValuePattern;
+use super::types::RowOrColumnMajor;
+
+/// A cell of a grid, which may span several rows or columns.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct GridCell {
+    /// The zero-based row index of the top-left position of the cell.
+    pub row: i32,
+    /// The zero-based column index of the top-left position of the cell.
+    pub column: i32,
+    /// The number of rows spanned by the cell.
+    pub row_span: i32,
+    /// The number of columns spanned by the cell.
+    pub column_span: i32,
+    /// The text of the cell.
+    pub text: String
+}
+
+/// A grid whose cells can be read one by one.
+pub trait GridSource {
+    /// Retrieves the number of rows.
+    fn get_row_count(&self) -> Result<i32>;
+
+    /// Retrieves the number of columns.
+    fn get_column_count(&self) -> Result<i32>;
+
+    /// Retrieves the texts of the column headers. An empty list means the grid has no headers.
+    fn get_column_headers(&self) -> Result<Vec<String>>;
+
+    /// Retrieves the primary direction of traversal.
+    fn get_row_or_column_major(&self) -> Result<RowOrColumnMajor>;
+
+    /// Retrieves the cell at `row` and `column`, or `None` if the position is empty.
+    fn get_cell(&self, row: i32, column: i32) -> Result<Option<GridCell>>;
+}
+
+/// The data extracted from a grid or table.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Table {
+    /// The column headers, which are empty if the grid has no headers.
+    pub headers: Vec<String>,
+    /// The rows of cell texts.
+    pub rows: Vec<Vec<String>>
+}
+
+impl Table {
+    /// Creates a table.
+    pub fn new(headers: Vec<String>, rows: Vec<Vec<String>>) -> Self {
+        Self {
+            headers,
+            rows
+        }
+    }
+
+    /// Reads all the cells of `source` into a table.
+    ///
+    /// The cells are read in the order of the `RowOrColumnMajor` hint. A cell spanning several positions is read once,
+    /// and its text is put at its top-left position, leaving the other spanned positions empty.
+    pub fn from_source<S: GridSource>(source: &S) -> Result<Self> {
+        let row_count = source.get_row_count()?.max(0);
+        let column_count = source.get_column_count()?.max(0);
+        let column_major = source.get_row_or_column_major().is_ok_and(|major| major == RowOrColumnMajor::ColumnMajor);
+
+        let mut rows = vec![vec![String::new(); column_count as usize]; row_count as usize];
+        let mut covered: HashSet<(i32, i32)> = HashSet::new();
+
+        let (outer, inner) = if column_major { (column_count, row_count) } else { (row_count, column_count) };
+        for i in 0..outer {
+            for j in 0..inner {
+                let (row, column) = if column_major { (j, i) } else { (i, j) };
+                if covered.contains(&(row, column)) {
+                    continue;
+                }
+
+                let Some(cell) = source.get_cell(row, column)? else {
+                    continue;
+                };
+
+                for r in cell.row..cell.row + cell.row_span.max(1) {
+                    for c in cell.column..cell.column + cell.column_span.max(1) {
+                        covered.insert((r, c));
+                    }
+                }
+                if let Some(text) = rows.get_mut(cell.row as usize).and_then(|r| r.get_mut(cell.column as usize)) {
+                    *text = cell.text;
+                }
+            }
+        }
+
+        Ok(Self {
+            headers: source.get_column_headers()?,
+            rows
+        })
+    }
+
+    /// Reads all the cells of the grid `element`, which must support `GridPattern`.
+    pub fn from_element(element: &UIElement) -> Result<Self> {
+        Self::from_source(&ElementGrid::new(element)?)
+    }
+
+    /// Writes the table as CSV, with the headers as the first line if they exist.
+    pub fn write_csv<W: Write>(&self, mut writer: W) -> std::io::Result<()> {
+        if !self.headers.is_empty() {
+            write_csv_line(&mut writer, &self.headers)?;
+        }
+        for row in &self.rows {
+            write_csv_line(&mut writer, row)?;
+        }
+        Ok(())
+    }
+
+    /// Formats the table as CSV.
+    ///
+    /// # Examples
+    /// ```
+    /// use uiautomation::tables::Table;
+    ///
+    /// let table = Table::new(vec!["Name".into(), "Note".into()], vec![vec!["Tom".into(), "say \"hi\", bye".into()]]);
+    /// assert_eq!(table.to_csv(), "Name,Note\r\nTom,\"say \"\"hi\"\", bye\"\r\n");
+    /// ```
+    pub fn to_csv(&self) -> String {
+        let mut buf: Vec<u8> = Vec::new();
+        self.write_csv(&mut buf).unwrap();
+        String::from_utf8(buf).unwrap()
+    }
+
+    /// Writes the table as JSON.
+    ///
+    /// With headers, each row is written as an object keyed by the headers. Otherwise, each row is written as an array.
+    pub fn write_json<W: Write>(&self, mut writer: W) -> std::io::Result<()> {
+        let keys = self.get_keys();
+
+        write!(writer, "[")?;
+        for (index, row) in self.rows.iter().enumerate() {
+            write!(writer, "{}\n  ", if index == 0 { "" } else { "," })?;
+            if keys.is_empty() {
+                let values: Vec<String> = row.iter().map(|value| quote_json(value)).collect();
+                write!(writer, "[{}]", values.join(", "))?;
+            } else {
+                let values: Vec<String> = keys.iter().enumerate().map(|(i, key)| {
+                    format!("{}: {}", quote_json(key), quote_json(row.get(i).map(String::as_str).unwrap_or_default()))
+                }).collect();
+                write!(writer, "{{{}}}", values.join(", "))?;
+            }
+        }
+        write!(writer, "{}]", if self.rows.is_empty() { "" } else { "\n" })
+    }
+
+    /// Formats the table as JSON.
+    ///
+    /// # Examples
+    /// ```
+    /// use uiautomation::tables::Table;
+    ///
+    /// let table = Table::new(vec!["Name".into()], vec![vec!["Tom".into()]]);
+    /// assert_eq!(table.to_json(), "[\n  {\"Name\": \"Tom\"}\n]");
+    /// ```
+    pub fn to_json(&self) -> String {
+        let mut buf: Vec<u8> = Vec::new();
+        self.write_json(&mut buf).unwrap();
+        String::from_utf8(buf).unwrap()
+    }
+
+    /// Retrieves the JSON keys of the columns. Empty or duplicated headers are replaced by `columnN`.
+    fn get_keys(&self) -> Vec<String> {
+        if self.headers.is_empty() {
+            return Vec::new();
+        }
+
+        let columns = self.rows.iter().map(|row| row.len()).max().unwrap_or_default().max(self.headers.len());
+        let mut keys: Vec<String> = Vec::with_capacity(columns);
+        for i in 0..columns {
+            let header = self.headers.get(i).map(|h| h.trim()).unwrap_or_default();
+            if header.is_empty() || keys.iter().any(|key| key == header) {
+                keys.push(format!("column{}", i + 1));
+            } else {
+                keys.push(header.to_string());
+            }
+        }
+        keys
+    }
+}
+
+impl DataGridControl {
+    /// Extracts the headers and cells of the data grid.
+    pub fn extract(&self) -> Result<Table> {
+        Table::from_element(self.as_ref())
+    }
+}
+
+impl TableControl {
+    /// Extracts the headers and cells of the table.
+    pub fn extract(&self) -> Result<Table> {
+        Table::from_element(self.as_ref())
+    }
+}
+
+/// A grid element, which supports `GridPattern` and optional `TablePattern`.
+struct ElementGrid {
+    grid: UIGridPattern,
+    table: Option<UITablePattern>
+}
+
+impl ElementGrid {
+    fn new(element: &UIElement) -> Result<Self> {
+        Ok(Self {
+            grid: element.get_pattern()?,
+            table: element.get_pattern().ok()
+        })
+    }
+}
+
+impl GridSource for ElementGrid {
+    fn get_row_count(&self) -> Result<i32> {
+        self.grid.get_row_count()
+    }
+
+    fn get_column_count(&self) -> Result<i32> {
+        self.grid.get_column_count()
+    }
+
+    fn get_column_headers(&self) -> Result<Vec<String>> {
+        let headers = match self.table {
+            Some(ref table) => table.get_column_headers().unwrap_or_default(),
+            None => Vec::new(),
+        };
+        Ok(headers.iter().map(get_cell_text).collect())
+    }
+
+    fn get_row_or_column_major(&self) -> Result<RowOrColumnMajor> {
+        match self.table {
+            Some(ref table) => table.get_row_or_column_major(),
+            None => Ok(RowOrColumnMajor::Indeterminate),
+        }
+    }
+
+    fn get_cell(&self, row: i32, column: i32) -> Result<Option<GridCell>> {
+        let item = match self.grid.get_item(row, column) {
+            Ok(item) => item,
+            Err(e) if is_missing_cell(&e) => return Ok(None),
+            Err(e) => return Err(e),
+        };
+
+        let mut cell = GridCell {
+            row,
+            column,
+            row_span: 1,
+            column_span: 1,
+            text: get_cell_text(&item)
+        };
+        if let Ok(pattern) = item.get_pattern::<UIGridItemPattern>() {
+            cell.row = pattern.get_row().unwrap_or(row);
+            cell.column = pattern.get_column().unwrap_or(column);
+            cell.row_span = pattern.get_row_span().unwrap_or(1);
+            cell.column_span = pattern.get_column_span().unwrap_or(1);
+        }
+
+        Ok(Some(cell))
+    }
+}
+
+/// Returns `true` if `error` means that there is no cell at the position, such as a null item or an index out of range.
+fn is_missing_cell(error: &Error) -> bool {
+    error.code() == ERR_NONE || error.code() == ERR_NOTFOUND || error.result() == Some(E_INVALIDARG)
+}
+
+/// Retrieves the text of a cell, preferring the value to the name.
+fn get_cell_text(item: &UIElement) -> String {
+    if let Ok(pattern) = item.get_pattern::<UIValuePattern>() {
+        if let Ok(value) = pattern.get_value() {
+            return value;
+        }
+    }
+    item.get_name().unwrap_or_default()
+}
+
+fn write_csv_line<W: Write, S: AsRef<str>>(writer: &mut W, fields: &[S]) -> std::io::Result<()> {
+    let fields: Vec<String> = fields.iter().map(|field| quote_csv(field.as_ref())).collect();
+    write!(writer, "{}\r\n", fields.join(","))
+}
+
+fn quote_csv(field: &str) -> String {
+    if field.contains([',', '"', '\r', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+fn quote_json(value: &str) -> String {
+    let mut quoted = String::with_capacity(value.len() + 2);
+    quoted.push('"');
+    for ch in value.chars() {
+        match ch {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c if (c as u32) < 0x20 => quoted.push_str(&format!("\\u{:04x}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::collections::HashMap;
+
+    use windows::Win32::Foundation::E_ACCESSDENIED;
+    use windows::Win32::Foundation::E_INVALIDARG;
+    use windows::core::HRESULT;
+
+    use crate::errors::ERR_NOTFOUND;
+    use crate::errors::ERR_TIMEOUT;
+    use crate::errors::Error;
+    use crate::errors::Result;
+    use crate::types::RowOrColumnMajor;
+
+    use super::GridCell;
+    use super::GridSource;
+    use super::Table;
+    use super::is_missing_cell;
+    use super::quote_json;
+
+    /// A fake grid, whose spanning cells are returned for every spanned position.
+    struct FakeGrid {
+        rows: i32,
+        columns: i32,
+        headers: Vec<String>,
+        major: RowOrColumnMajor,
+        cells: Vec<GridCell>,
+        visits: RefCell<Vec<(i32, i32)>>
+    }
+
+    impl FakeGrid {
+        fn new(rows: i32, columns: i32, major: RowOrColumnMajor) -> Self {
+            let mut cells = Vec::new();
+            for row in 0..rows {
+                for column in 0..columns {
+                    cells.push(GridCell { row, column, row_span: 1, column_span: 1, text: format!("{}{}", row, column) });
+                }
+            }
+            Self {
+                rows,
+                columns,
+                headers: Vec::new(),
+                major,
+                cells,
+                visits: RefCell::new(Vec::new())
+            }
+        }
+
+        fn merge(&mut self, row: i32, column: i32, row_span: i32, column_span: i32) {
+            self.cells.retain(|c| c.row < row || c.row >= row + row_span || c.column < column || c.column >= column + column_span);
+            self.cells.push(GridCell { row, column, row_span, column_span, text: "merged".into() });
+        }
+    }
+
+    impl GridSource for FakeGrid {
+        fn get_row_count(&self) -> Result<i32> {
+            Ok(self.rows)
+        }
+
+        fn get_column_count(&self) -> Result<i32> {
+            Ok(self.columns)
+        }
+
+        fn get_column_headers(&self) -> Result<Vec<String>> {
+            Ok(self.headers.clone())
+        }
+
+        fn get_row_or_column_major(&self) -> Result<RowOrColumnMajor> {
+            Ok(self.major)
+        }
+
+        fn get_cell(&self, row: i32, column: i32) -> Result<Option<GridCell>> {
+            self.visits.borrow_mut().push((row, column));
+            Ok(self.cells.iter().find(|c| row >= c.row && row < c.row + c.row_span && column >= c.column && column < c.column + c.column_span).cloned())
+        }
+    }
+
+    fn strings(values: &[&str]) -> Vec<String> {
+        values.iter().map(|v| v.to_string()).collect()
+    }
+
+    #[test]
+    fn test_extract() {
+        let mut grid = FakeGrid::new(2, 3, RowOrColumnMajor::RowMajor);
+        grid.headers = strings(&["A", "B", "C"]);
+
+        let table = Table::from_source(&grid).unwrap();
+        assert_eq!(table.headers, strings(&["A", "B", "C"]));
+        assert_eq!(table.rows, vec![strings(&["00", "01", "02"]), strings(&["10", "11", "12"])]);
+        assert_eq!(grid.visits.borrow()[..3], [(0, 0), (0, 1), (0, 2)]);
+    }
+
+    #[test]
+    fn test_column_major() {
+        let grid = FakeGrid::new(2, 2, RowOrColumnMajor::ColumnMajor);
+        let table = Table::from_source(&grid).unwrap();
+        assert_eq!(table.rows, vec![strings(&["00", "01"]), strings(&["10", "11"])]);
+        assert_eq!(*grid.visits.borrow(), vec![(0, 0), (1, 0), (0, 1), (1, 1)]);
+    }
+
+    #[test]
+    fn test_spans() {
+        let mut grid = FakeGrid::new(3, 3, RowOrColumnMajor::RowMajor);
+        grid.merge(0, 1, 2, 2);
+        grid.cells.retain(|c| (c.row, c.column) != (2, 2));
+
+        let table = Table::from_source(&grid).unwrap();
+        assert_eq!(table.rows, vec![strings(&["00", "merged", ""]), strings(&["10", "", ""]), strings(&["20", "21", ""])]);
+
+        let visits: HashMap<(i32, i32), usize> = grid.visits.borrow().iter().fold(HashMap::new(), |mut m, v| { *m.entry(*v).or_default() += 1; m });
+        assert_eq!(visits.len(), 6);
+        assert!(!visits.contains_key(&(0, 2)) && !visits.contains_key(&(1, 1)));
+    }
+
+    #[test]
+    fn test_csv() {
+        let table = Table::new(Vec::new(), vec![strings(&["a,b", "line\nbreak", "plain"])]);
+        assert_eq!(table.to_csv(), "\"a,b\",\"line\nbreak\",plain\r\n");
+        assert_eq!(Table::default().to_csv(), "");
+    }
+
+    #[test]
+    fn test_json() {
+        let table = Table::new(strings(&["Name", "", "Name"]), vec![strings(&["x", "y", "z"]), strings(&["1"])]);
+        assert_eq!(table.to_json(), "[\n  {\"Name\": \"x\", \"column2\": \"y\", \"column3\": \"z\"},\n  {\"Name\": \"1\", \"column2\": \"\", \"column3\": \"\"}\n]");
+
+        let table = Table::new(Vec::new(), vec![strings(&["a", "b"])]);
+        assert_eq!(table.to_json(), "[\n  [\"a\", \"b\"]\n]");
+        assert_eq!(Table::default().to_json(), "[]");
+
+        assert_eq!(quote_json("say \"hi\"\\\t\u{1}"), "\"say \\\"hi\\\"\\\\\\t\\u0001\"");
+    }
+
+    #[test]
+    fn test_missing_cell() {
+        assert!(is_missing_cell(&Error::from(E_INVALIDARG)));
+        assert!(is_missing_cell(&Error::new(ERR_NOTFOUND, "no item")));
+        assert!(is_missing_cell(&Error::from(windows::core::Error::from(HRESULT(0)))));
+        assert!(!is_missing_cell(&Error::from(E_ACCESSDENIED)));
+        assert!(!is_missing_cell(&Error::new(ERR_TIMEOUT, "timeout")));
+    }
+}
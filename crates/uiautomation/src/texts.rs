@@ -0,0 +1,339 @@
+use std::fmt::Display;
+use std::time::Duration;
+
+use super::clipboard;
+use super::core::UIElement;
+use super::errors::ERR_MISMATCH;
+use super::errors::ERR_NOTFOUND;
+use super::errors::Error;
+use super::errors::Result;
+use super::inputs::Keyboard;
+use super::inputs::escape_keys;
+use super::patterns::UITextPattern;
+use super::patterns::UIValuePattern;
+use super::timers::sleep;
+
+/// The ways to input text into a control.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum InputStrategy {
+    /// Sets the text by `ValuePattern`.
+    Value,
+    /// Selects all the text by `Ctrl+A`, then types the new text.
+    Typing,
+    /// Selects all the text by `Ctrl+A`, then pastes the new text from the clipboard.
+    Paste
+}
+
+impl Display for InputStrategy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            InputStrategy::Value => "value",
+            InputStrategy::Typing => "typing",
+            InputStrategy::Paste => "paste",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// A control which can receive text input.
+pub trait TextTarget {
+    /// Sets the text by `ValuePattern`.
+    fn set_value(&self, text: &str) -> Result<()>;
+
+    /// Replaces the text by typing on keyboard. `interval` is the milliseconds between keys.
+    fn type_text(&self, text: &str, interval: u64) -> Result<()>;
+
+    /// Replaces the text by pasting from the clipboard.
+    fn paste_text(&self, text: &str) -> Result<()>;
+
+    /// Reads back the current text.
+    fn read_text(&self) -> Result<String>;
+}
+
+/// The milliseconds to wait for the target to read the clipboard before it is restored.
+const PASTE_DELAY: u64 = 100;
+
+impl TextTarget for UIElement {
+    fn set_value(&self, text: &str) -> Result<()> {
+        let pattern: UIValuePattern = self.get_pattern()?;
+        pattern.set_value(text)
+    }
+
+    fn type_text(&self, text: &str, interval: u64) -> Result<()> {
+        self.set_focus()?;
+
+        let kb = Keyboard::new().interval(interval);
+        kb.send_keys("{ctrl}a{delete}")?;
+        kb.send_keys(&escape_keys(text))
+    }
+
+    fn paste_text(&self, text: &str) -> Result<()> {
+        self.set_focus()?;
+
+        clipboard::preserve(|| {
+            clipboard::set_text(text)?;
+
+            let kb = Keyboard::new();
+            kb.send_keys("{ctrl}a")?;
+            kb.send_keys("{ctrl}v")?;
+
+            sleep(Duration::from_millis(PASTE_DELAY), None)
+        })
+    }
+
+    fn read_text(&self) -> Result<String> {
+        if let Ok(pattern) = self.get_pattern::<UIValuePattern>() {
+            pattern.get_value()
+        } else if let Ok(pattern) = self.get_pattern::<UITextPattern>() {
+            pattern.get_document_range()?.get_text(-1)
+        } else {
+            Err(Error::new(ERR_NOTFOUND, "can not read the text of the element"))
+        }
+    }
+}
+
+/// The result of `TextInput::input()`.
+#[derive(Debug)]
+pub struct InputReport {
+    /// The strategy which succeeded.
+    pub strategy: InputStrategy,
+    /// `true` if the text is read back and matches the input.
+    pub verified: bool,
+    /// The strategies which failed before, with their errors.
+    pub failures: Vec<(InputStrategy, Error)>
+}
+
+/// Inputs text into controls, trying several strategies until one of them succeeds.
+///
+/// The strategies are `Value`, `Typing` and `Paste` by default. After each strategy, the text is read back to verify the result.
+/// If the text can not be read back, such as a password box, the strategy is accepted without verification.
+///
+/// # Examples
+/// ```
+/// use uiautomation::UIAutomation;
+/// use uiautomation::texts::TextInput;
+///
+/// let automation = UIAutomation::new().unwrap();
+/// let element = automation.get_focused_element().unwrap();
+/// let report = TextInput::new().input(&element, "Hello, world!");
+/// ```
+#[derive(Debug, Clone)]
+pub struct TextInput {
+    strategies: Vec<InputStrategy>,
+    verify: bool,
+    interval: u64
+}
+
+impl Default for TextInput {
+    fn default() -> Self {
+        Self {
+            strategies: vec![InputStrategy::Value, InputStrategy::Typing, InputStrategy::Paste],
+            verify: true,
+            interval: 0
+        }
+    }
+}
+
+impl TextInput {
+    /// Creates a text input with the default strategies.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the strategies to try in order.
+    pub fn strategies(mut self, strategies: &[InputStrategy]) -> Self {
+        self.strategies = strategies.to_vec();
+        self
+    }
+
+    /// Sets whether to verify the result by reading back the text. The default value is `true`.
+    pub fn verify(mut self, verify: bool) -> Self {
+        self.verify = verify;
+        self
+    }
+
+    /// Sets the milliseconds between keys for the `Typing` strategy.
+    pub fn interval(mut self, interval: u64) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Retrieves the strategies to try.
+    pub fn get_strategies(&self) -> &[InputStrategy] {
+        &self.strategies
+    }
+
+    /// Inputs `text` into `target`, and reports which strategy succeeded.
+    pub fn input<T: TextTarget>(&self, target: &T, text: &str) -> Result<InputReport> {
+        let mut failures: Vec<(InputStrategy, Error)> = Vec::new();
+        for strategy in &self.strategies {
+            let ret = match strategy {
+                InputStrategy::Value => target.set_value(text),
+                InputStrategy::Typing => target.type_text(text, self.interval),
+                InputStrategy::Paste => target.paste_text(text),
+            }.and_then(|_| self.check(target, text));
+
+            match ret {
+                Ok(verified) => return Ok(InputReport {
+                    strategy: *strategy,
+                    verified,
+                    failures
+                }),
+                Err(e) => failures.push((*strategy, e)),
+            }
+        }
+
+        let reasons: Vec<String> = failures.iter().map(|(strategy, e)| format!("{}: {}", strategy, e)).collect();
+        Err(Error::new(ERR_MISMATCH, &format!("failed to input text ({})", reasons.join("; "))))
+    }
+
+    fn check<T: TextTarget>(&self, target: &T, expected: &str) -> Result<bool> {
+        if !self.verify {
+            return Ok(false);
+        }
+
+        match target.read_text() {
+            Ok(actual) if is_same_text(&actual, expected) => Ok(true),
+            Ok(actual) => Err(Error::new(ERR_MISMATCH, &format!("expected {:?}, but got {:?}", expected, actual))),
+            Err(_) => Ok(false),
+        }
+    }
+}
+
+/// Compares the text ignoring the line endings and trailing line breaks, which some controls add or convert.
+fn is_same_text(actual: &str, expected: &str) -> bool {
+    let normalize = |text: &str| text.replace("\r\n", "\n").replace('\r', "\n").trim_end_matches('\n').to_string();
+    normalize(actual) == normalize(expected)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+
+    use crate::errors::ERR_MISMATCH;
+    use crate::errors::ERR_NOTFOUND;
+    use crate::errors::Error;
+    use crate::errors::Result;
+
+    use super::InputStrategy;
+    use super::TextInput;
+    use super::TextTarget;
+    use super::is_same_text;
+
+    /// A fake control, which supports some of the strategies and may mangle the text.
+    struct FakeTarget {
+        supported: Vec<InputStrategy>,
+        readable: bool,
+        mangle: Option<InputStrategy>,
+        text: RefCell<String>,
+        calls: RefCell<Vec<InputStrategy>>
+    }
+
+    impl FakeTarget {
+        fn new(supported: &[InputStrategy]) -> Self {
+            Self {
+                supported: supported.to_vec(),
+                readable: true,
+                mangle: None,
+                text: RefCell::new(String::new()),
+                calls: RefCell::new(Vec::new())
+            }
+        }
+
+        fn apply(&self, strategy: InputStrategy, text: &str) -> Result<()> {
+            self.calls.borrow_mut().push(strategy);
+            if !self.supported.contains(&strategy) {
+                return Err(Error::new(ERR_NOTFOUND, "unsupported"));
+            }
+
+            if self.mangle == Some(strategy) {
+                *self.text.borrow_mut() = text.chars().skip(1).collect();
+            } else {
+                *self.text.borrow_mut() = text.to_string();
+            }
+            Ok(())
+        }
+    }
+
+    impl TextTarget for FakeTarget {
+        fn set_value(&self, text: &str) -> Result<()> {
+            self.apply(InputStrategy::Value, text)
+        }
+
+        fn type_text(&self, text: &str, _interval: u64) -> Result<()> {
+            self.apply(InputStrategy::Typing, text)
+        }
+
+        fn paste_text(&self, text: &str) -> Result<()> {
+            self.apply(InputStrategy::Paste, text)
+        }
+
+        fn read_text(&self) -> Result<String> {
+            if self.readable {
+                Ok(self.text.borrow().clone())
+            } else {
+                Err(Error::new(ERR_NOTFOUND, "unreadable"))
+            }
+        }
+    }
+
+    #[test]
+    fn test_value_first() {
+        let target = FakeTarget::new(&[InputStrategy::Value, InputStrategy::Typing]);
+        let report = TextInput::new().input(&target, "hello").unwrap();
+        assert_eq!(report.strategy, InputStrategy::Value);
+        assert!(report.verified);
+        assert!(report.failures.is_empty());
+        assert_eq!(*target.calls.borrow(), vec![InputStrategy::Value]);
+    }
+
+    #[test]
+    fn test_fallback() {
+        let target = FakeTarget::new(&[InputStrategy::Paste]);
+        let report = TextInput::new().input(&target, "hello").unwrap();
+        assert_eq!(report.strategy, InputStrategy::Paste);
+        assert_eq!(report.failures.len(), 2);
+        assert_eq!(*target.calls.borrow(), vec![InputStrategy::Value, InputStrategy::Typing, InputStrategy::Paste]);
+    }
+
+    #[test]
+    fn test_verify_mismatch() {
+        let mut target = FakeTarget::new(&[InputStrategy::Value, InputStrategy::Typing]);
+        target.mangle = Some(InputStrategy::Value);
+
+        let report = TextInput::new().input(&target, "hello").unwrap();
+        assert_eq!(report.strategy, InputStrategy::Typing);
+        assert_eq!(report.failures[0].0, InputStrategy::Value);
+        assert_eq!(report.failures[0].1.code(), ERR_MISMATCH);
+
+        let report = TextInput::new().verify(false).input(&target, "hello").unwrap();
+        assert_eq!(report.strategy, InputStrategy::Value);
+        assert!(!report.verified);
+    }
+
+    #[test]
+    fn test_unreadable() {
+        let mut target = FakeTarget::new(&[InputStrategy::Typing]);
+        target.readable = false;
+
+        let report = TextInput::new().input(&target, "secret").unwrap();
+        assert_eq!(report.strategy, InputStrategy::Typing);
+        assert!(!report.verified);
+    }
+
+    #[test]
+    fn test_all_failed() {
+        let target = FakeTarget::new(&[InputStrategy::Value]);
+        let err = TextInput::new().strategies(&[InputStrategy::Paste, InputStrategy::Typing]).input(&target, "hello").unwrap_err();
+        assert_eq!(err.code(), ERR_MISMATCH);
+        assert!(err.message().contains("paste: unsupported"));
+        assert_eq!(*target.calls.borrow(), vec![InputStrategy::Paste, InputStrategy::Typing]);
+    }
+
+    #[test]
+    fn test_same_text() {
+        assert!(is_same_text("a\r\nb\r\n", "a\nb"));
+        assert!(is_same_text("a\rb", "a\r\nb"));
+        assert!(!is_same_text("a b", "ab"));
+    }
+}
@@ -0,0 +1,466 @@
+use std::fmt::Debug;
+use std::sync::Arc;
+use std::sync::Condvar;
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+use std::time::Instant;
+#[cfg(feature = "async")]
+use std::future::Future;
+
+use super::errors::ERR_CANCELLED;
+use super::errors::ERR_TIMEOUT;
+use super::errors::Error;
+use super::errors::Result;
+
+/// Defines the retry timing of a polling loop, such as `UIMatcher::find_first()`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetrySchedule {
+    timeout: u64,
+    interval: u64
+}
+
+impl RetrySchedule {
+    /// Creates a schedule which retries every `interval` milliseconds until `timeout` milliseconds elapsed.
+    ///
+    /// There is no retry when `timeout` is `0`.
+    pub fn new(timeout: u64, interval: u64) -> Self {
+        Self {
+            timeout,
+            interval
+        }
+    }
+
+    /// Retrieves the timeout in milliseconds.
+    pub fn get_timeout(&self) -> u64 {
+        self.timeout
+    }
+
+    /// Retrieves the interval in milliseconds.
+    pub fn get_interval(&self) -> u64 {
+        self.interval
+    }
+
+    /// Decides the next step after a failed attempt, `elapsed` milliseconds after the first attempt.
+    ///
+    /// Returns `Ok(Some(delay))` to retry after `delay`, `Ok(None)` to give up without retry,
+    /// or an `ERR_TIMEOUT` error when the timeout has elapsed.
+    pub fn next_delay(&self, elapsed: u64) -> Result<Option<Duration>> {
+        if self.timeout == 0 {
+            Ok(None)
+        } else if elapsed >= self.timeout {
+            Err(Error::new(ERR_TIMEOUT, "time out"))
+        } else {
+            Ok(Some(Duration::from_millis(self.interval)))
+        }
+    }
+}
+
+/// A token to cancel long-running operations, such as matcher searches, waits, mouse moves and keyboard sequences.
+/// 
+/// The token is cheap to clone, and all the clones share the same state. Cancelled operations return an `ERR_CANCELLED` error.
+/// 
+/// # Examples
+/// 
+/// ```
+/// use uiautomation::timers::CancellationToken;
+/// 
+/// let token = CancellationToken::new();
+/// let worker = token.clone();
+/// std::thread::spawn(move || worker.cancel());
+/// ```
+#[derive(Clone, Default)]
+pub struct CancellationToken {
+    state: Arc<(Mutex<bool>, Condvar)>
+}
+
+impl CancellationToken {
+    /// Creates a token which is not cancelled.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cancels the operations which accept this token, and wakes up their waits.
+    pub fn cancel(&self) {
+        let (cancelled, condvar) = &*self.state;
+        *cancelled.lock().unwrap() = true;
+        condvar.notify_all();
+    }
+
+    /// Returns `true` if the token has been cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        *self.state.0.lock().unwrap()
+    }
+
+    /// Returns an `ERR_CANCELLED` error if the token has been cancelled.
+    pub fn check(&self) -> Result<()> {
+        if self.is_cancelled() {
+            Err(Error::new(ERR_CANCELLED, "operation is cancelled"))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Sleeps for `duration`, and wakes up early with an `ERR_CANCELLED` error when the token is cancelled.
+    pub fn sleep(&self, duration: Duration) -> Result<()> {
+        let (cancelled, condvar) = &*self.state;
+        let guard = cancelled.lock().unwrap();
+        let (guard, _) = condvar.wait_timeout_while(guard, duration, |cancelled| !*cancelled).unwrap();
+        if *guard {
+            Err(Error::new(ERR_CANCELLED, "operation is cancelled"))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl Debug for CancellationToken {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CancellationToken").field("cancelled", &self.is_cancelled()).finish()
+    }
+}
+
+/// Sleeps for `duration`, which can be cancelled by `cancel`.
+pub fn sleep(duration: Duration, cancel: Option<&CancellationToken>) -> Result<()> {
+    match cancel {
+        Some(token) => token.sleep(duration),
+        None => {
+            thread::sleep(duration);
+            Ok(())
+        }
+    }
+}
+
+/// Polls `attempt` by `schedule` until it returns `Some` value. The token is checked before every attempt and during the waits.
+/// 
+/// Returns `Ok(None)` when the schedule gives up without retry.
+pub fn retry<R, F>(schedule: RetrySchedule, cancel: Option<&CancellationToken>, mut attempt: F) -> Result<Option<R>>
+where
+    F: FnMut() -> Result<Option<R>>
+{
+    let start = Instant::now();
+    loop {
+        if let Some(token) = cancel {
+            token.check()?;
+        }
+
+        if let Some(value) = attempt()? {
+            return Ok(Some(value));
+        }
+
+        match schedule.next_delay(start.elapsed().as_millis() as u64)? {
+            Some(delay) => sleep(delay, cancel)?,
+            None => return Ok(None),
+        }
+    }
+}
+
+/// Waits until `condition` returns `true`, checking every `interval` milliseconds until `timeout` milliseconds elapsed.
+pub fn wait(timeout: u64, interval: u64, cancel: Option<&CancellationToken>, mut condition: impl FnMut() -> Result<bool>) -> Result<()> {
+    let ret = retry(RetrySchedule::new(timeout, interval), cancel, || {
+        Ok(if condition()? { Some(()) } else { None })
+    })?;
+
+    match ret {
+        Some(_) => Ok(()),
+        None => Err(Error::new(ERR_TIMEOUT, "wait time out")),
+    }
+}
+
+/// Runs `step` for every item of `steps`, waiting `interval` after each step. The token is checked between steps.
+pub fn run_steps<T, I, F>(steps: I, interval: Duration, cancel: Option<&CancellationToken>, mut step: F) -> Result<()>
+where
+    I: IntoIterator<Item = T>,
+    F: FnMut(T) -> Result<()>
+{
+    for item in steps {
+        if let Some(token) = cancel {
+            token.check()?;
+        }
+
+        step(item)?;
+
+        if !interval.is_zero() {
+            sleep(interval, cancel)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// A runtime-agnostic timer used by the async APIs.
+#[cfg(feature = "async")]
+pub trait AsyncTimer {
+    /// The future which completes after the delay.
+    type Delay: Future<Output = ()>;
+
+    /// Creates a future which completes after `duration`.
+    fn delay(&self, duration: Duration) -> Self::Delay;
+
+    /// Retrieves the monotonic time in milliseconds.
+    fn now(&self) -> u64;
+}
+
+/// The default timer based on `futures-timer`, which works with any async runtime.
+#[cfg(feature = "async")]
+#[derive(Debug, Clone, Copy)]
+pub struct DefaultTimer {
+    origin: Instant
+}
+
+#[cfg(feature = "async")]
+impl DefaultTimer {
+    /// Creates a default timer.
+    pub fn new() -> Self {
+        Self {
+            origin: Instant::now()
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+impl Default for DefaultTimer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "async")]
+impl AsyncTimer for DefaultTimer {
+    type Delay = futures_timer::Delay;
+
+    fn delay(&self, duration: Duration) -> Self::Delay {
+        futures_timer::Delay::new(duration)
+    }
+
+    fn now(&self) -> u64 {
+        self.origin.elapsed().as_millis() as u64
+    }
+}
+
+/// Polls `attempt` by `schedule` until it returns `Some` value, without blocking the thread.
+///
+/// Returns `Ok(None)` when the schedule gives up without retry.
+#[cfg(feature = "async")]
+pub async fn poll_until<T, R, F>(timer: &T, schedule: RetrySchedule, mut attempt: F) -> Result<Option<R>>
+where
+    T: AsyncTimer,
+    F: FnMut() -> Result<Option<R>>
+{
+    let start = timer.now();
+    loop {
+        if let Some(value) = attempt()? {
+            return Ok(Some(value));
+        }
+
+        match schedule.next_delay(timer.now().saturating_sub(start))? {
+            Some(delay) => timer.delay(delay).await,
+            None => return Ok(None),
+        }
+    }
+}
+
+/// Waits until `condition` returns `true`, checking every `interval` milliseconds until `timeout` milliseconds elapsed.
+#[cfg(feature = "async")]
+pub async fn wait_until<T, F>(timer: &T, timeout: u64, interval: u64, mut condition: F) -> Result<()>
+where
+    T: AsyncTimer,
+    F: FnMut() -> Result<bool>
+{
+    let ret = poll_until(timer, RetrySchedule::new(timeout, interval), || {
+        Ok(if condition()? { Some(()) } else { None })
+    }).await?;
+
+    match ret {
+        Some(_) => Ok(()),
+        None => Err(Error::new(ERR_TIMEOUT, "wait time out")),
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use std::cell::Cell;
+    use std::thread;
+    use std::time::Duration;
+    use std::time::Instant;
+
+    use crate::errors::ERR_CANCELLED;
+    use crate::errors::ERR_TIMEOUT;
+
+    use super::CancellationToken;
+    use super::RetrySchedule;
+    use super::retry;
+    use super::run_steps;
+    use super::wait;
+
+    #[test]
+    fn test_retry_schedule() {
+        let schedule = RetrySchedule::new(300, 100);
+        assert_eq!(schedule.next_delay(0), Ok(Some(Duration::from_millis(100))));
+        assert_eq!(schedule.next_delay(299), Ok(Some(Duration::from_millis(100))));
+        assert_eq!(schedule.next_delay(300).unwrap_err().code(), ERR_TIMEOUT);
+
+        let once = RetrySchedule::new(0, 100);
+        assert_eq!(once.next_delay(0), Ok(None));
+    }
+
+    #[test]
+    fn test_cancel_retry() {
+        let token = CancellationToken::new();
+        let attempts = Cell::new(0);
+        let ret = retry(RetrySchedule::new(60000, 10), Some(&token), || {
+            attempts.set(attempts.get() + 1);
+            if attempts.get() == 3 {
+                token.cancel();
+            }
+            Ok(None::<()>)
+        });
+
+        assert_eq!(ret.unwrap_err().code(), ERR_CANCELLED);
+        assert_eq!(attempts.get(), 3);
+    }
+
+    #[test]
+    fn test_cancel_steps() {
+        let token = CancellationToken::new();
+        let mut done = Vec::new();
+        let ret = run_steps(1..=5, Duration::ZERO, Some(&token), |i| {
+            done.push(i);
+            if i == 2 {
+                token.cancel();
+            }
+            Ok(())
+        });
+
+        assert_eq!(ret.unwrap_err().code(), ERR_CANCELLED);
+        assert_eq!(done, vec![1, 2]);
+    }
+
+    #[test]
+    fn test_cancel_sleep() {
+        let token = CancellationToken::new();
+        let canceller = token.clone();
+        let start = Instant::now();
+        let worker = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(20));
+            canceller.cancel();
+        });
+
+        let ret = wait(60000, 30000, Some(&token), || Ok(false));
+        worker.join().unwrap();
+
+        assert_eq!(ret.unwrap_err().code(), ERR_CANCELLED);
+        assert!(start.elapsed() < Duration::from_secs(10));
+        assert!(token.check().is_err());
+    }
+
+    #[test]
+    fn test_uncancelled() {
+        let token = CancellationToken::new();
+        assert!(token.sleep(Duration::from_millis(1)).is_ok());
+        assert!(run_steps(0..3, Duration::from_millis(1), Some(&token), |_| Ok(())).is_ok());
+        assert_eq!(wait(0, 10, None, || Ok(false)).unwrap_err().code(), ERR_TIMEOUT);
+    }
+
+    #[cfg(feature = "async")]
+    pub(crate) use mock::*;
+
+    #[cfg(feature = "async")]
+    mod mock {
+        use std::cell::Cell;
+        use std::cell::RefCell;
+        use std::future::Future;
+        use std::future::Ready;
+        use std::future::ready;
+        use std::pin::pin;
+        use std::task::Context;
+        use std::task::Poll;
+        use std::task::Waker;
+        use std::time::Duration;
+
+        use crate::timers::AsyncTimer;
+
+        /// A virtual timer whose delays complete immediately and advance the clock.
+        #[derive(Debug, Default)]
+        pub(crate) struct MockTimer {
+            pub(crate) now: Cell<u64>,
+            pub(crate) delays: RefCell<Vec<u64>>
+        }
+
+        impl AsyncTimer for MockTimer {
+            type Delay = Ready<()>;
+
+            fn delay(&self, duration: Duration) -> Self::Delay {
+                let millis = duration.as_millis() as u64;
+                self.now.set(self.now.get() + millis);
+                self.delays.borrow_mut().push(millis);
+                ready(())
+            }
+
+            fn now(&self) -> u64 {
+                self.now.get()
+            }
+        }
+
+        /// Runs a future which never waits for an external wake-up.
+        pub(crate) fn block_on<F: Future>(future: F) -> F::Output {
+            let mut future = pin!(future);
+            let mut cx = Context::from_waker(Waker::noop());
+            loop {
+                if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+                    return output;
+                }
+            }
+        }
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn test_poll_until() {
+        use std::cell::Cell;
+
+        use super::poll_until;
+
+        let timer = MockTimer::default();
+        let attempts = Cell::new(0);
+        let ret = block_on(poll_until(&timer, RetrySchedule::new(1000, 100), || {
+            attempts.set(attempts.get() + 1);
+            Ok(if attempts.get() == 3 { Some("found") } else { None })
+        }));
+
+        assert_eq!(ret, Ok(Some("found")));
+        assert_eq!(*timer.delays.borrow(), vec![100, 100]);
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn test_poll_timeout() {
+        use super::poll_until;
+
+        let timer = MockTimer::default();
+        let ret = block_on(poll_until(&timer, RetrySchedule::new(250, 100), || Ok(None::<()>)));
+
+        assert_eq!(ret.unwrap_err().code(), ERR_TIMEOUT);
+        assert_eq!(*timer.delays.borrow(), vec![100, 100, 100]);
+
+        let timer = MockTimer::default();
+        let ret = block_on(poll_until(&timer, RetrySchedule::new(0, 100), || Ok(None::<()>)));
+        assert_eq!(ret, Ok(None));
+        assert!(timer.delays.borrow().is_empty());
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn test_wait_until() {
+        use super::wait_until;
+
+        let timer = MockTimer::default();
+        let ret = block_on(wait_until(&timer, 1000, 50, || Ok(timer.now.get() >= 200)));
+        assert!(ret.is_ok());
+        assert_eq!(timer.now.get(), 200);
+
+        let timer = MockTimer::default();
+        let ret = block_on(wait_until(&timer, 0, 50, || Ok(false)));
+        assert_eq!(ret.unwrap_err().code(), ERR_TIMEOUT);
+    }
+}
@@ -0,0 +1,445 @@
+use std::collections::VecDeque;
+
+use super::core::UICacheRequest;
+use super::core::UIElement;
+use super::core::UITreeWalker;
+use super::errors::ERR_NONE;
+use super::errors::Result;
+
+/// Navigates a tree of nodes, such as the UI Automation tree.
+pub trait TreeNavigator {
+    /// The node type.
+    type Node: Clone;
+
+    /// Retrieves the parent of `node`, or `None` if `node` is the root.
+    fn get_parent(&self, node: &Self::Node) -> Result<Option<Self::Node>>;
+
+    /// Retrieves the first child of `node`, or `None` if `node` has no children.
+    fn get_first_child(&self, node: &Self::Node) -> Result<Option<Self::Node>>;
+
+    /// Retrieves the next sibling of `node`, or `None` if `node` is the last child.
+    fn get_next_sibling(&self, node: &Self::Node) -> Result<Option<Self::Node>>;
+}
+
+/// Navigates the UI Automation tree by a `UITreeWalker`, optionally caching properties and control patterns.
+#[derive(Clone, Copy)]
+pub struct ElementNavigator<'a> {
+    walker: &'a UITreeWalker,
+    cache_request: Option<&'a UICacheRequest>
+}
+
+impl<'a> ElementNavigator<'a> {
+    /// Creates a navigator with `walker`.
+    pub fn new(walker: &'a UITreeWalker) -> Self {
+        Self {
+            walker,
+            cache_request: None
+        }
+    }
+
+    /// Sets the cache request for the navigated elements.
+    pub fn cache_request(mut self, cache_request: &'a UICacheRequest) -> Self {
+        self.cache_request = Some(cache_request);
+        self
+    }
+}
+
+/// The walker returns a null element, which is converted to an error without code, when there is no more element.
+fn optional(element: Result<UIElement>) -> Result<Option<UIElement>> {
+    match element {
+        Ok(element) => Ok(Some(element)),
+        Err(e) if e.code() == ERR_NONE => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+impl TreeNavigator for ElementNavigator<'_> {
+    type Node = UIElement;
+
+    fn get_parent(&self, node: &UIElement) -> Result<Option<UIElement>> {
+        optional(match self.cache_request {
+            Some(cache_request) => self.walker.get_parent_build_cache(node, cache_request),
+            None => self.walker.get_parent(node),
+        })
+    }
+
+    fn get_first_child(&self, node: &UIElement) -> Result<Option<UIElement>> {
+        optional(match self.cache_request {
+            Some(cache_request) => self.walker.get_first_child_build_cache(node, cache_request),
+            None => self.walker.get_first_child(node),
+        })
+    }
+
+    fn get_next_sibling(&self, node: &UIElement) -> Result<Option<UIElement>> {
+        optional(match self.cache_request {
+            Some(cache_request) => self.walker.get_next_sibling_build_cache(node, cache_request),
+            None => self.walker.get_next_sibling(node),
+        })
+    }
+}
+
+/// An iterator over the children of a node, or the siblings following a node.
+///
+/// The iteration stops after an error is returned.
+pub struct Siblings<N: TreeNavigator> {
+    navigator: N,
+    parent: Option<N::Node>,
+    current: Option<N::Node>
+}
+
+impl<N: TreeNavigator> Siblings<N> {
+    /// Creates an iterator over the children of `parent`.
+    pub fn children(navigator: N, parent: N::Node) -> Self {
+        Self {
+            navigator,
+            parent: Some(parent),
+            current: None
+        }
+    }
+
+    /// Creates an iterator over the siblings following `node`.
+    pub fn following(navigator: N, node: N::Node) -> Self {
+        Self {
+            navigator,
+            parent: None,
+            current: Some(node)
+        }
+    }
+}
+
+impl<'a> Siblings<ElementNavigator<'a>> {
+    /// Sets the cache request for the returned elements.
+    pub fn cache_request(mut self, cache_request: &'a UICacheRequest) -> Self {
+        self.navigator = self.navigator.cache_request(cache_request);
+        self
+    }
+}
+
+impl<N: TreeNavigator> Iterator for Siblings<N> {
+    type Item = Result<N::Node>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let next = if let Some(parent) = self.parent.take() {
+            self.navigator.get_first_child(&parent)
+        } else {
+            let current = self.current.take()?;
+            self.navigator.get_next_sibling(&current)
+        };
+
+        match next {
+            Ok(node) => {
+                self.current = node.clone();
+                node.map(Ok)
+            },
+            Err(e) => Some(Err(e)),
+        }
+    }
+}
+
+/// An iterator over the ancestors of a node, from its parent up to the root.
+///
+/// The iteration stops after an error is returned.
+pub struct Ancestors<N: TreeNavigator> {
+    navigator: N,
+    current: Option<N::Node>
+}
+
+impl<N: TreeNavigator> Ancestors<N> {
+    /// Creates an iterator over the ancestors of `node`.
+    pub fn new(navigator: N, node: N::Node) -> Self {
+        Self {
+            navigator,
+            current: Some(node)
+        }
+    }
+}
+
+impl<'a> Ancestors<ElementNavigator<'a>> {
+    /// Sets the cache request for the returned elements.
+    pub fn cache_request(mut self, cache_request: &'a UICacheRequest) -> Self {
+        self.navigator = self.navigator.cache_request(cache_request);
+        self
+    }
+}
+
+impl<N: TreeNavigator> Iterator for Ancestors<N> {
+    type Item = Result<N::Node>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let current = self.current.take()?;
+        match self.navigator.get_parent(&current) {
+            Ok(parent) => {
+                self.current = parent.clone();
+                parent.map(Ok)
+            },
+            Err(e) => Some(Err(e)),
+        }
+    }
+}
+
+/// Defines the order to traverse the descendants.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TraversalOrder {
+    /// Visits a node, then its descendants before its next sibling.
+    #[default]
+    DepthFirst,
+    /// Visits all the nodes at one depth before the nodes at the next depth.
+    BreadthFirst
+}
+
+/// A pending navigation of `Descendants`.
+enum Step<T> {
+    FirstChild(T, u32),
+    NextSibling(T, u32)
+}
+
+/// An iterator over the descendants of a node, excluding the node itself.
+///
+/// The children of the node are at depth 1. When an error occurs, it is returned and the traversal continues with
+/// the remaining nodes, skipping the nodes which can not be reached.
+pub struct Descendants<N: TreeNavigator> {
+    navigator: N,
+    order: TraversalOrder,
+    depth: u32,
+    steps: VecDeque<Step<N::Node>>
+}
+
+impl<N: TreeNavigator> Descendants<N> {
+    /// Creates an iterator over the descendants of `root`.
+    pub fn new(navigator: N, root: N::Node) -> Self {
+        Self {
+            navigator,
+            order: TraversalOrder::DepthFirst,
+            depth: u32::MAX,
+            steps: VecDeque::from([Step::FirstChild(root, 1)])
+        }
+    }
+
+    /// Sets the traversal order. The default value is `TraversalOrder::DepthFirst`.
+    pub fn order(mut self, order: TraversalOrder) -> Self {
+        self.order = order;
+        self
+    }
+
+    /// Sets the max depth of the returned descendants. The children are at depth 1.
+    pub fn depth(mut self, depth: u32) -> Self {
+        self.depth = depth;
+        self
+    }
+}
+
+impl<'a> Descendants<ElementNavigator<'a>> {
+    /// Sets the cache request for the returned elements.
+    pub fn cache_request(mut self, cache_request: &'a UICacheRequest) -> Self {
+        self.navigator = self.navigator.cache_request(cache_request);
+        self
+    }
+}
+
+impl<N: TreeNavigator> Iterator for Descendants<N> {
+    type Item = Result<N::Node>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let step = match self.order {
+                TraversalOrder::DepthFirst => self.steps.pop_back()?,
+                TraversalOrder::BreadthFirst => self.steps.pop_front()?,
+            };
+
+            let (next, depth) = match step {
+                Step::FirstChild(parent, depth) if depth <= self.depth => (self.navigator.get_first_child(&parent), depth),
+                Step::FirstChild(..) => continue,
+                Step::NextSibling(node, depth) => (self.navigator.get_next_sibling(&node), depth),
+            };
+
+            match next {
+                Ok(Some(node)) => {
+                    // depth first: the children are popped before the next sibling.
+                    // breadth first: the next sibling is visited at once, and the children are queued after the nodes at this depth.
+                    match self.order {
+                        TraversalOrder::DepthFirst => {
+                            self.steps.push_back(Step::NextSibling(node.clone(), depth));
+                            self.steps.push_back(Step::FirstChild(node.clone(), depth + 1));
+                        },
+                        TraversalOrder::BreadthFirst => {
+                            self.steps.push_front(Step::NextSibling(node.clone(), depth));
+                            self.steps.push_back(Step::FirstChild(node.clone(), depth + 1));
+                        },
+                    }
+                    return Some(Ok(node));
+                },
+                Ok(None) => continue,
+                Err(e) => return Some(Err(e)),
+            }
+        }
+    }
+}
+
+impl UIElement {
+    /// Iterates over the children of the element.
+    ///
+    /// # Examples
+    /// ```
+    /// use uiautomation::UIAutomation;
+    ///
+    /// let automation = UIAutomation::new().unwrap();
+    /// let walker = automation.get_control_view_walker().unwrap();
+    /// let root = automation.get_root_element().unwrap();
+    /// for child in root.children(&walker).filter_map(|c| c.ok()) {
+    ///     println!("{}", child);
+    /// }
+    /// ```
+    pub fn children<'a>(&self, walker: &'a UITreeWalker) -> Siblings<ElementNavigator<'a>> {
+        Siblings::children(ElementNavigator::new(walker), self.clone())
+    }
+
+    /// Iterates over the descendants of the element, in depth first order by default.
+    ///
+    /// # Examples
+    /// ```
+    /// use uiautomation::UIAutomation;
+    /// use uiautomation::trees::TraversalOrder;
+    ///
+    /// let automation = UIAutomation::new().unwrap();
+    /// let walker = automation.get_control_view_walker().unwrap();
+    /// let root = automation.get_root_element().unwrap();
+    /// let count = root.descendants(&walker).order(TraversalOrder::BreadthFirst).depth(2).count();
+    /// ```
+    pub fn descendants<'a>(&self, walker: &'a UITreeWalker) -> Descendants<ElementNavigator<'a>> {
+        Descendants::new(ElementNavigator::new(walker), self.clone())
+    }
+
+    /// Iterates over the ancestors of the element, from its parent up to the root.
+    pub fn ancestors<'a>(&self, walker: &'a UITreeWalker) -> Ancestors<ElementNavigator<'a>> {
+        Ancestors::new(ElementNavigator::new(walker), self.clone())
+    }
+
+    /// Iterates over the siblings following the element.
+    pub fn following_siblings<'a>(&self, walker: &'a UITreeWalker) -> Siblings<ElementNavigator<'a>> {
+        Siblings::following(ElementNavigator::new(walker), self.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::errors::ERR_NOTFOUND;
+    use crate::errors::Error;
+    use crate::errors::Result;
+
+    use super::Ancestors;
+    use super::Descendants;
+    use super::Siblings;
+    use super::TraversalOrder;
+    use super::TreeNavigator;
+
+    /// An in-memory tree, whose nodes are the indexes of `parents`.
+    struct FakeTree {
+        parents: Vec<Option<usize>>,
+        broken: Option<usize>
+    }
+
+    impl FakeTree {
+        ///        0
+        ///     /  |  \
+        ///    1   2   3
+        ///   / \      |
+        ///  4   5     6
+        ///      |
+        ///      7
+        fn new() -> Self {
+            Self {
+                parents: vec![None, Some(0), Some(0), Some(0), Some(1), Some(1), Some(3), Some(5)],
+                broken: None
+            }
+        }
+
+        fn check(&self, node: usize) -> Result<()> {
+            if self.broken == Some(node) {
+                Err(Error::new(ERR_NOTFOUND, "broken node"))
+            } else {
+                Ok(())
+            }
+        }
+    }
+
+    impl TreeNavigator for &FakeTree {
+        type Node = usize;
+
+        fn get_parent(&self, node: &usize) -> Result<Option<usize>> {
+            self.check(*node)?;
+            Ok(self.parents[*node])
+        }
+
+        fn get_first_child(&self, node: &usize) -> Result<Option<usize>> {
+            self.check(*node)?;
+            Ok(self.parents.iter().position(|p| *p == Some(*node)))
+        }
+
+        fn get_next_sibling(&self, node: &usize) -> Result<Option<usize>> {
+            self.check(*node)?;
+            let parent = self.parents[*node];
+            Ok(parent.and_then(|_| (node + 1..self.parents.len()).find(|n| self.parents[*n] == parent)))
+        }
+    }
+
+    fn collect<I: Iterator<Item = Result<usize>>>(iter: I) -> Vec<usize> {
+        iter.map(|n| n.unwrap()).collect()
+    }
+
+    #[test]
+    fn test_children() {
+        let tree = FakeTree::new();
+        assert_eq!(collect(Siblings::children(&tree, 0)), vec![1, 2, 3]);
+        assert_eq!(collect(Siblings::children(&tree, 1)), vec![4, 5]);
+        assert!(collect(Siblings::children(&tree, 2)).is_empty());
+        assert_eq!(collect(Siblings::following(&tree, 1)), vec![2, 3]);
+        assert!(collect(Siblings::following(&tree, 0)).is_empty());
+    }
+
+    #[test]
+    fn test_ancestors() {
+        let tree = FakeTree::new();
+        assert_eq!(collect(Ancestors::new(&tree, 7)), vec![5, 1, 0]);
+        assert!(collect(Ancestors::new(&tree, 0)).is_empty());
+    }
+
+    #[test]
+    fn test_descendants() {
+        let tree = FakeTree::new();
+        assert_eq!(collect(Descendants::new(&tree, 0)), vec![1, 4, 5, 7, 2, 3, 6]);
+        assert_eq!(collect(Descendants::new(&tree, 0).order(TraversalOrder::BreadthFirst)), vec![1, 2, 3, 4, 5, 6, 7]);
+        assert_eq!(collect(Descendants::new(&tree, 0).depth(1)), vec![1, 2, 3]);
+        assert_eq!(collect(Descendants::new(&tree, 0).order(TraversalOrder::BreadthFirst).depth(2)), vec![1, 2, 3, 4, 5, 6]);
+        assert_eq!(collect(Descendants::new(&tree, 1)), vec![4, 5, 7]);
+        assert!(collect(Descendants::new(&tree, 0).depth(0)).is_empty());
+    }
+
+    #[test]
+    fn test_adapters() {
+        let tree = FakeTree::new();
+        let leaves: Vec<usize> = Descendants::new(&tree, 0)
+            .filter_map(|n| n.ok())
+            .filter(|n| Siblings::children(&tree, *n).next().is_none())
+            .collect();
+        assert_eq!(leaves, vec![4, 7, 2, 6]);
+        assert_eq!(Descendants::new(&tree, 0).nth(3).unwrap().unwrap(), 7);
+    }
+
+    #[test]
+    fn test_errors() {
+        let mut tree = FakeTree::new();
+        tree.broken = Some(1);
+
+        // the children and the siblings of the broken node are skipped.
+        let nodes: Vec<Result<usize>> = Descendants::new(&tree, 0).collect();
+        assert_eq!(nodes.len(), 3);
+        assert_eq!(*nodes[0].as_ref().unwrap(), 1);
+        assert!(nodes[1].is_err());
+        assert!(nodes[2].is_err());
+
+        let mut ancestors = Ancestors::new(&tree, 4);
+        assert_eq!(ancestors.next().unwrap().unwrap(), 1);
+        assert_eq!(ancestors.next().unwrap().unwrap_err().code(), ERR_NOTFOUND);
+        assert!(ancestors.next().is_none());
+    }
+}
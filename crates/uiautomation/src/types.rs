@@ -4,6 +4,7 @@ use std::fmt::Display;
 use uiautomation_derive::EnumConvert;
 use uiautomation_derive::map_as;
 use windows::core::Param;
+use windows::Win32::Foundation::COLORREF;
 use windows::Win32::Foundation::HWND;
 use windows::Win32::Foundation::POINT;
 use windows::Win32::Foundation::RECT;
@@ -254,6 +255,73 @@ impl AsRef<isize> for Handle {
     }
 }
 
+/// A Wrapper for windows `COLORREF`, which stores a RGB color as `0x00BBGGRR`.
+#[derive(Clone, Copy, PartialEq, Eq, Default)]
+pub struct Color(COLORREF);
+
+impl Color {
+    /// Creates a color by the red, green and blue components.
+    pub fn new(red: u8, green: u8, blue: u8) -> Self {
+        Self(COLORREF(red as u32 | (green as u32) << 8 | (blue as u32) << 16))
+    }
+
+    /// Retrieves the red component.
+    pub fn get_red(&self) -> u8 {
+        (self.0.0 & 0xFF) as u8
+    }
+
+    /// Retrieves the green component.
+    pub fn get_green(&self) -> u8 {
+        (self.0.0 >> 8 & 0xFF) as u8
+    }
+
+    /// Retrieves the blue component.
+    pub fn get_blue(&self) -> u8 {
+        (self.0.0 >> 16 & 0xFF) as u8
+    }
+
+    /// Formats the color as `#RRGGBB`.
+    pub fn to_hex(&self) -> String {
+        format!("#{:02X}{:02X}{:02X}", self.get_red(), self.get_green(), self.get_blue())
+    }
+}
+
+impl Debug for Color {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Color").field("red", &self.get_red()).field("green", &self.get_green()).field("blue", &self.get_blue()).finish()
+    }
+}
+
+impl Display for Color {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.to_hex())
+    }
+}
+
+impl From<COLORREF> for Color {
+    fn from(color: COLORREF) -> Self {
+        Self(COLORREF(color.0 & 0x00FFFFFF))
+    }
+}
+
+impl From<Color> for COLORREF {
+    fn from(color: Color) -> Self {
+        color.0
+    }
+}
+
+impl From<u32> for Color {
+    fn from(value: u32) -> Self {
+        COLORREF(value).into()
+    }
+}
+
+impl From<Color> for u32 {
+    fn from(color: Color) -> Self {
+        color.0.0
+    }
+}
+
 /// Defines enum for `windows::Win32::UI::Accessibility::UIA_PROPERTY_ID`.
 /// 
 /// Describes the named constants that identify the properties of Microsoft UI Automation elements.
@@ -986,6 +1054,299 @@ pub enum StyleType {
     NumberedList = 70016i32,
 }
 
+/// Defines enum for `windows::Win32::UI::Accessibility::AnimationStyle`.
+/// 
+/// Contains values for the AnimationStyle text attribute.
+#[repr(i32)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumConvert)]
+#[map_as(windows::Win32::UI::Accessibility::AnimationStyle)]
+pub enum AnimationStyle {
+    /// No animation.
+    None = 0i32,
+    /// The bounding rectangle displays a border of alternating icons of different colors.
+    LasVegasLights = 1i32,
+    /// The font and background alternate between assigned colors and contrasting colors.
+    BlinkingBackground = 2i32,
+    /// The background displays flashing, multicolored icons.
+    SparkleText = 3i32,
+    /// The bounding rectangle displays moving black dashes.
+    MarchingBlackAnts = 4i32,
+    /// The bounding rectangle displays moving red dashes.
+    MarchingRedAnts = 5i32,
+    /// The font alternates between solid and blurred.
+    Shimmer = 6i32,
+    /// Some other animation style.
+    Other = -1i32
+}
+
+/// Defines enum for `windows::Win32::UI::Accessibility::BulletStyle`.
+/// 
+/// Contains values for the BulletStyle text attribute.
+#[repr(i32)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumConvert)]
+#[map_as(windows::Win32::UI::Accessibility::BulletStyle)]
+pub enum BulletStyle {
+    /// No bullets.
+    None = 0i32,
+    /// Hollow round bullets.
+    HollowRoundBullet = 1i32,
+    /// Filled round bullets.
+    FilledRoundBullet = 2i32,
+    /// Hollow square bullets.
+    HollowSquareBullet = 3i32,
+    /// Filled square bullets.
+    FilledSquareBullet = 4i32,
+    /// Dash bullets.
+    DashBullet = 5i32,
+    /// Some other bullet style.
+    Other = -1i32
+}
+
+/// Defines enum for `windows::Win32::UI::Accessibility::CapStyle`.
+/// 
+/// Contains values that specify the value of the CapStyle text attribute.
+#[repr(i32)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumConvert)]
+#[map_as(windows::Win32::UI::Accessibility::CapStyle)]
+pub enum CapStyle {
+    /// No capitalization.
+    None = 0i32,
+    /// Small capitals.
+    SmallCap = 1i32,
+    /// All capitals.
+    AllCap = 2i32,
+    /// All petite capitals.
+    AllPetiteCaps = 3i32,
+    /// Petite capitals.
+    PetiteCaps = 4i32,
+    /// Single case.
+    Unicase = 5i32,
+    /// Title case.
+    Titling = 6i32,
+    /// Some other capitalization style.
+    Other = -1i32
+}
+
+/// Defines enum for `windows::Win32::UI::Accessibility::HorizontalTextAlignment`.
+/// 
+/// Contains values that specify the horizontal alignment of text.
+#[repr(i32)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumConvert)]
+#[map_as(windows::Win32::UI::Accessibility::HorizontalTextAlignment)]
+pub enum HorizontalTextAlignment {
+    /// Text is aligned to the left.
+    Left = 0i32,
+    /// Text is centered.
+    Centered = 1i32,
+    /// Text is aligned to the right.
+    Right = 2i32,
+    /// Text is justified.
+    Justified = 3i32
+}
+
+/// Implements the bit operations and conversions of a flags type which wraps an `i32` value.
+macro_rules! impl_flags {
+    ($name:ident, $raw:path) => {
+        impl $name {
+            /// Creates the flags from the raw bits, keeping the unknown bits.
+            pub fn from_bits(bits: i32) -> Self {
+                Self(bits)
+            }
+
+            /// Retrieves the raw bits.
+            pub fn get_bits(&self) -> i32 {
+                self.0
+            }
+
+            /// Returns `true` if all the bits of `flags` are set.
+            pub fn contains(&self, flags: Self) -> bool {
+                self.0 & flags.0 == flags.0
+            }
+
+            /// Returns `true` if no bit is set.
+            pub fn is_empty(&self) -> bool {
+                self.0 == 0
+            }
+        }
+
+        impl std::ops::BitOr for $name {
+            type Output = Self;
+
+            fn bitor(self, rhs: Self) -> Self {
+                Self(self.0 | rhs.0)
+            }
+        }
+
+        impl std::ops::BitOrAssign for $name {
+            fn bitor_assign(&mut self, rhs: Self) {
+                self.0 |= rhs.0;
+            }
+        }
+
+        impl std::ops::BitAnd for $name {
+            type Output = Self;
+
+            fn bitand(self, rhs: Self) -> Self {
+                Self(self.0 & rhs.0)
+            }
+        }
+
+        impl From<i32> for $name {
+            fn from(bits: i32) -> Self {
+                Self(bits)
+            }
+        }
+
+        impl From<$name> for i32 {
+            fn from(flags: $name) -> Self {
+                flags.0
+            }
+        }
+
+        impl From<$raw> for $name {
+            fn from(flags: $raw) -> Self {
+                Self(flags.0)
+            }
+        }
+
+        impl From<$name> for $raw {
+            fn from(flags: $name) -> Self {
+                $raw(flags.0)
+            }
+        }
+    };
+}
+
+/// Defines flags for `windows::Win32::UI::Accessibility::OutlineStyles`.
+/// 
+/// Contains values for the OutlineStyles text attribute, which can be combined by `|`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct OutlineStyles(i32);
+
+impl OutlineStyles {
+    /// No outline style.
+    pub const NONE: Self = Self(0);
+    /// A simple solid line.
+    pub const OUTLINE: Self = Self(1);
+    /// A shadow.
+    pub const SHADOW: Self = Self(2);
+    /// An engraved appearance.
+    pub const ENGRAVED: Self = Self(4);
+    /// An embossed appearance.
+    pub const EMBOSSED: Self = Self(8);
+}
+
+impl_flags!(OutlineStyles, windows::Win32::UI::Accessibility::OutlineStyles);
+
+/// Defines flags for `windows::Win32::UI::Accessibility::FlowDirections`.
+/// 
+/// Contains values for the TextFlowDirections text attribute, which can be combined by `|`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct FlowDirections(i32);
+
+impl FlowDirections {
+    /// The default flow direction, which is left to right and top to bottom.
+    pub const DEFAULT: Self = Self(0);
+    /// The text flows from right to left.
+    pub const RIGHT_TO_LEFT: Self = Self(1);
+    /// The text flows from bottom to top.
+    pub const BOTTOM_TO_TOP: Self = Self(2);
+    /// The text flows vertically.
+    pub const VERTICAL: Self = Self(4);
+}
+
+impl_flags!(FlowDirections, windows::Win32::UI::Accessibility::FlowDirections);
+
+/// Defines enum for `windows::Win32::UI::Accessibility::TextDecorationLineStyle`.
+/// 
+/// Contains values that specify the OverlineStyle, StrikethroughStyle, and UnderlineStyle text attributes.
+#[repr(i32)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumConvert)]
+#[map_as(windows::Win32::UI::Accessibility::TextDecorationLineStyle)]
+pub enum TextDecorationLineStyle {
+    /// No line style.
+    None = 0i32,
+    /// A single solid line.
+    Single = 1i32,
+    /// Only words (not spaces) are underlined.
+    WordsOnly = 2i32,
+    /// A double line.
+    Double = 3i32,
+    /// A dotted line.
+    Dot = 4i32,
+    /// A dashed line.
+    Dash = 5i32,
+    /// A dash-dot line.
+    DashDot = 6i32,
+    /// A dash-dot-dot line.
+    DashDotDot = 7i32,
+    /// A wavy line.
+    Wavy = 8i32,
+    /// A thick single line.
+    ThickSingle = 9i32,
+    /// A double wavy line.
+    DoubleWavy = 11i32,
+    /// A thick wavy line.
+    ThickWavy = 12i32,
+    /// A long dash line.
+    LongDash = 13i32,
+    /// A thick dash line.
+    ThickDash = 14i32,
+    /// A thick dash-dot line.
+    ThickDashDot = 15i32,
+    /// A thick dash-dot-dot line.
+    ThickDashDotDot = 16i32,
+    /// A thick dotted line.
+    ThickDot = 17i32,
+    /// A thick long dash line.
+    ThickLongDash = 18i32,
+    /// Some other line style.
+    Other = -1i32
+}
+
+/// Defines enum for `windows::Win32::UI::Accessibility::ActiveEnd`.
+/// 
+/// Contains possible values for the SelectionActiveEnd text attribute, which indicates the location of the caret relative to a text range that represents the currently selected text.
+#[repr(i32)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumConvert)]
+#[map_as(windows::Win32::UI::Accessibility::ActiveEnd)]
+pub enum ActiveEnd {
+    /// The caret is not at the start or the end of the text range.
+    None = 0i32,
+    /// The caret is at the start of the text range.
+    Start = 1i32,
+    /// The caret is at the end of the text range.
+    End = 2i32
+}
+
+/// Defines enum for `windows::Win32::UI::Accessibility::CaretPosition`.
+/// 
+/// Contains possible values for the CaretPosition text attribute, which indicates the location of the caret relative to a line of text in a text range.
+#[repr(i32)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumConvert)]
+#[map_as(windows::Win32::UI::Accessibility::CaretPosition)]
+pub enum CaretPosition {
+    /// The caret is not at the beginning or the end of a line.
+    Unknown = 0i32,
+    /// The caret is at the end of a line.
+    EndOfLine = 1i32,
+    /// The caret is at the beginning of a line.
+    BeginningOfLine = 2i32
+}
+
+/// Defines enum for `windows::Win32::UI::Accessibility::CaretBidiMode`.
+/// 
+/// Contains possible values for the CaretBidiMode text attribute, which indicates whether the caret is in text that flows from left to right, or from right to left.
+#[repr(i32)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumConvert)]
+#[map_as(windows::Win32::UI::Accessibility::CaretBidiMode)]
+pub enum CaretBidiMode {
+    /// The caret is in text that flows from left to right.
+    LTR = 0i32,
+    /// The caret is in text that flows from right to left.
+    RTL = 1i32
+}
+
 /// Defines enum for `windows::Win32::UI::Accessibility::UIA_TEXTATTRIBUTE_ID`.
 /// 
 /// This type describes the named constants used to identify text attributes of a Microsoft UI Automation text range.
@@ -1099,7 +1460,10 @@ mod tests {
     use windows::Win32::Foundation::HWND;
     use windows::Win32::UI::Accessibility;
 
+    use super::Color;
+    use super::FlowDirections;
     use super::Handle;
+    use super::OutlineStyles;
     use super::WindowInteractionState;
 
     #[test]
@@ -1119,4 +1483,31 @@ mod tests {
         let handle = Handle::from(0x001);
         assert_eq!(HWND(0x001), handle.into());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_color() {
+        let color = Color::from(0x00336699u32);
+        assert_eq!((color.get_red(), color.get_green(), color.get_blue()), (0x99, 0x66, 0x33));
+        assert_eq!(color, Color::new(0x99, 0x66, 0x33));
+        assert_eq!(color.to_hex(), "#996633");
+        assert_eq!(Color::from(0xFF000000u32), Color::new(0, 0, 0));
+    }
+
+    #[test]
+    fn test_flags() {
+        let styles = OutlineStyles::OUTLINE | OutlineStyles::SHADOW;
+        assert_eq!(styles.get_bits(), 3);
+        assert!(styles.contains(OutlineStyles::SHADOW));
+        assert!(!styles.contains(OutlineStyles::SHADOW | OutlineStyles::EMBOSSED));
+        assert!(OutlineStyles::NONE.is_empty());
+        assert_eq!(OutlineStyles::from(Accessibility::OutlineStyles_Embossed), OutlineStyles::EMBOSSED);
+
+        let mut directions = FlowDirections::DEFAULT;
+        directions |= FlowDirections::RIGHT_TO_LEFT;
+        directions |= FlowDirections::VERTICAL;
+        assert_eq!(i32::from(directions), 5);
+        assert_eq!(directions & FlowDirections::VERTICAL, FlowDirections::VERTICAL);
+        assert_eq!(Accessibility::FlowDirections_BottomToTop, FlowDirections::BOTTOM_TO_TOP.into());
+        assert_eq!(FlowDirections::from_bits(0x10).get_bits(), 0x10);
+    }
+}
@@ -0,0 +1,428 @@
+use windows::Win32::Foundation::BOOL;
+use windows::Win32::Foundation::HWND;
+use windows::Win32::Foundation::LPARAM;
+use windows::Win32::Foundation::TRUE;
+use windows::Win32::UI::WindowsAndMessaging::BringWindowToTop;
+use windows::Win32::UI::WindowsAndMessaging::EnumChildWindows;
+use windows::Win32::UI::WindowsAndMessaging::EnumWindows;
+use windows::Win32::UI::WindowsAndMessaging::GetClassNameW;
+use windows::Win32::UI::WindowsAndMessaging::GetWindowRect;
+use windows::Win32::UI::WindowsAndMessaging::GetWindowTextLengthW;
+use windows::Win32::UI::WindowsAndMessaging::GetWindowTextW;
+use windows::Win32::UI::WindowsAndMessaging::GetWindowThreadProcessId;
+use windows::Win32::UI::WindowsAndMessaging::HWND_BOTTOM;
+use windows::Win32::UI::WindowsAndMessaging::HWND_NOTOPMOST;
+use windows::Win32::UI::WindowsAndMessaging::HWND_TOP;
+use windows::Win32::UI::WindowsAndMessaging::HWND_TOPMOST;
+use windows::Win32::UI::WindowsAndMessaging::IsIconic;
+use windows::Win32::UI::WindowsAndMessaging::IsWindow;
+use windows::Win32::UI::WindowsAndMessaging::IsWindowVisible;
+use windows::Win32::UI::WindowsAndMessaging::IsZoomed;
+use windows::Win32::UI::WindowsAndMessaging::SET_WINDOW_POS_FLAGS;
+use windows::Win32::UI::WindowsAndMessaging::SHOW_WINDOW_CMD;
+use windows::Win32::UI::WindowsAndMessaging::SWP_NOACTIVATE;
+use windows::Win32::UI::WindowsAndMessaging::SWP_NOMOVE;
+use windows::Win32::UI::WindowsAndMessaging::SWP_NOSIZE;
+use windows::Win32::UI::WindowsAndMessaging::SWP_NOZORDER;
+use windows::Win32::UI::WindowsAndMessaging::SW_HIDE;
+use windows::Win32::UI::WindowsAndMessaging::SW_MAXIMIZE;
+use windows::Win32::UI::WindowsAndMessaging::SW_MINIMIZE;
+use windows::Win32::UI::WindowsAndMessaging::SW_RESTORE;
+use windows::Win32::UI::WindowsAndMessaging::SW_SHOW;
+use windows::Win32::UI::WindowsAndMessaging::SetForegroundWindow;
+use windows::Win32::UI::WindowsAndMessaging::SetWindowPos;
+use windows::Win32::UI::WindowsAndMessaging::ShowWindow;
+
+use super::errors::ERR_INVALID_OBJECT;
+use super::errors::Error;
+use super::errors::Result;
+use super::types::Handle;
+use super::types::Rect;
+
+/// Defines the position of a window in the z-order.
+#[derive(Debug, Clone, Copy)]
+pub enum WindowZOrder {
+    /// Places the window at the top of the z-order.
+    Top,
+    /// Places the window at the bottom of the z-order.
+    Bottom,
+    /// Places the window above all non-topmost windows, and keeps it there even when it is deactivated.
+    TopMost,
+    /// Places the window above all non-topmost windows, behind all topmost windows.
+    NoTopMost,
+    /// Places the window behind the specified window.
+    After(Handle)
+}
+
+impl From<WindowZOrder> for HWND {
+    fn from(order: WindowZOrder) -> Self {
+        match order {
+            WindowZOrder::Top => HWND_TOP,
+            WindowZOrder::Bottom => HWND_BOTTOM,
+            WindowZOrder::TopMost => HWND_TOPMOST,
+            WindowZOrder::NoTopMost => HWND_NOTOPMOST,
+            WindowZOrder::After(handle) => handle.into(),
+        }
+    }
+}
+
+/// The properties of a window, which are used to filter windows.
+#[derive(Debug, Clone, Default)]
+pub struct WindowInfo {
+    /// The handle of the window.
+    pub handle: Handle,
+    /// The title of the window.
+    pub title: String,
+    /// The class name of the window.
+    pub classname: String,
+    /// The identifier of the process which created the window.
+    pub process_id: u32,
+    /// Whether the window is visible.
+    pub visible: bool
+}
+
+/// Defines the conditions to filter windows. All the conditions are used as `and` logic.
+///
+/// # Examples
+///
+/// ```
+/// use uiautomation::UIAutomation;
+/// use uiautomation::windows::WindowFilter;
+/// use uiautomation::windows::find_windows;
+///
+/// let filter = WindowFilter::new().classname("Shell_TrayWnd").visible(true);
+/// let taskbars = find_windows(&filter).unwrap();
+///
+/// let automation = UIAutomation::new().unwrap();
+/// for taskbar in taskbars {
+///     let element = automation.element_from_handle(taskbar).unwrap();
+///     println!("{}", element);
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct WindowFilter {
+    title: Option<String>,
+    partial_title: bool,
+    classname: Option<String>,
+    process_id: Option<u32>,
+    visible: bool
+}
+
+impl WindowFilter {
+    /// Creates a filter which matches all windows.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Matches the windows whose title is `title`.
+    pub fn title<S: Into<String>>(mut self, title: S) -> Self {
+        self.title = Some(title.into());
+        self.partial_title = false;
+        self
+    }
+
+    /// Matches the windows whose title contains `title`.
+    pub fn contains_title<S: Into<String>>(mut self, title: S) -> Self {
+        self.title = Some(title.into());
+        self.partial_title = true;
+        self
+    }
+
+    /// Matches the windows whose class name is `classname`.
+    pub fn classname<S: Into<String>>(mut self, classname: S) -> Self {
+        self.classname = Some(classname.into());
+        self
+    }
+
+    /// Matches the windows created by the process of `process_id`.
+    pub fn process_id(mut self, process_id: u32) -> Self {
+        self.process_id = Some(process_id);
+        self
+    }
+
+    /// Sets whether to match visible windows only. The default value is `false`.
+    pub fn visible(mut self, visible: bool) -> Self {
+        self.visible = visible;
+        self
+    }
+
+    /// Returns `true` if the window of `info` is matched.
+    pub fn matches(&self, info: &WindowInfo) -> bool {
+        if self.visible && !info.visible {
+            return false;
+        }
+
+        if let Some(process_id) = self.process_id {
+            if info.process_id != process_id {
+                return false;
+            }
+        }
+
+        if let Some(ref classname) = self.classname {
+            if &info.classname != classname {
+                return false;
+            }
+        }
+
+        match self.title {
+            Some(ref title) if self.partial_title => info.title.contains(title.as_str()),
+            Some(ref title) => &info.title == title,
+            None => true,
+        }
+    }
+}
+
+unsafe extern "system" fn collect_window(hwnd: HWND, lparam: LPARAM) -> BOOL {
+    let handles = &mut *(lparam.0 as *mut Vec<Handle>);
+    handles.push(hwnd.into());
+    TRUE
+}
+
+/// Enumerates all the top-level windows.
+pub fn get_top_windows() -> Result<Vec<Handle>> {
+    let mut handles: Vec<Handle> = Vec::new();
+    unsafe {
+        EnumWindows(Some(collect_window), LPARAM(&mut handles as *mut _ as _))?
+    };
+    Ok(handles)
+}
+
+/// Enumerates all the descendant windows of `parent`.
+pub fn get_child_windows(parent: Handle) -> Result<Vec<Handle>> {
+    let mut handles: Vec<Handle> = Vec::new();
+    unsafe {
+        // The return value of `EnumChildWindows` is not used.
+        let _ = EnumChildWindows(parent, Some(collect_window), LPARAM(&mut handles as *mut _ as _));
+    };
+    Ok(handles)
+}
+
+/// Finds the top-level windows which are matched by `filter`.
+pub fn find_windows(filter: &WindowFilter) -> Result<Vec<Handle>> {
+    filter_windows(get_top_windows()?, filter)
+}
+
+/// Finds the descendant windows of `parent` which are matched by `filter`.
+pub fn find_child_windows(parent: Handle, filter: &WindowFilter) -> Result<Vec<Handle>> {
+    filter_windows(get_child_windows(parent)?, filter)
+}
+
+fn filter_windows(handles: Vec<Handle>, filter: &WindowFilter) -> Result<Vec<Handle>> {
+    let mut windows: Vec<Handle> = Vec::new();
+    for handle in handles {
+        // The window may be destroyed during the enumeration.
+        if let Ok(info) = handle.get_info() {
+            if filter.matches(&info) {
+                windows.push(handle);
+            }
+        }
+    }
+
+    Ok(windows)
+}
+
+impl Handle {
+    /// Returns `true` if the handle identifies an existing window.
+    pub fn is_window(&self) -> bool {
+        unsafe { IsWindow(*self) }.as_bool()
+    }
+
+    /// Retrieves the title of the window.
+    pub fn get_title(&self) -> Result<String> {
+        self.check()?;
+
+        let len = unsafe { GetWindowTextLengthW(*self) };
+        let mut buffer: Vec<u16> = vec![0; len as usize + 1];
+        let len = unsafe { GetWindowTextW(*self, &mut buffer) };
+        Ok(String::from_utf16_lossy(&buffer[..len as usize]))
+    }
+
+    /// Retrieves the class name of the window.
+    pub fn get_classname(&self) -> Result<String> {
+        let mut buffer = [0u16; 256];
+        let len = unsafe { GetClassNameW(*self, &mut buffer) };
+        if len == 0 {
+            Err(Error::last_os_error())
+        } else {
+            Ok(String::from_utf16_lossy(&buffer[..len as usize]))
+        }
+    }
+
+    /// Retrieves the identifier of the process which created the window.
+    pub fn get_process_id(&self) -> Result<u32> {
+        let mut process_id: u32 = 0;
+        let thread_id = unsafe { GetWindowThreadProcessId(*self, Some(&mut process_id)) };
+        if thread_id == 0 {
+            Err(Error::last_os_error())
+        } else {
+            Ok(process_id)
+        }
+    }
+
+    /// Retrieves the properties of the window.
+    pub fn get_info(&self) -> Result<WindowInfo> {
+        Ok(WindowInfo {
+            handle: *self,
+            title: self.get_title()?,
+            classname: self.get_classname()?,
+            process_id: self.get_process_id()?,
+            visible: self.is_visible()
+        })
+    }
+
+    /// Returns `true` if the window is visible.
+    pub fn is_visible(&self) -> bool {
+        unsafe { IsWindowVisible(*self) }.as_bool()
+    }
+
+    /// Returns `true` if the window is minimized.
+    pub fn is_minimized(&self) -> bool {
+        unsafe { IsIconic(*self) }.as_bool()
+    }
+
+    /// Returns `true` if the window is maximized.
+    pub fn is_maximized(&self) -> bool {
+        unsafe { IsZoomed(*self) }.as_bool()
+    }
+
+    /// Retrieves the bounding rectangle of the window in screen coordinates.
+    pub fn get_rect(&self) -> Result<Rect> {
+        let mut rect = Rect::default();
+        unsafe { GetWindowRect(*self, rect.as_mut())? };
+        Ok(rect)
+    }
+
+    /// Moves the window to (`x`, `y`) in screen coordinates, keeping its size.
+    pub fn move_to(&self, x: i32, y: i32) -> Result<()> {
+        self.set_pos(HWND_TOP, x, y, 0, 0, SWP_NOSIZE | SWP_NOZORDER | SWP_NOACTIVATE)
+    }
+
+    /// Resizes the window to `width` x `height`, keeping its position.
+    pub fn resize(&self, width: i32, height: i32) -> Result<()> {
+        self.set_pos(HWND_TOP, 0, 0, width, height, SWP_NOMOVE | SWP_NOZORDER | SWP_NOACTIVATE)
+    }
+
+    /// Moves and resizes the window to `rect` in screen coordinates.
+    pub fn set_rect(&self, rect: Rect) -> Result<()> {
+        self.set_pos(HWND_TOP, rect.get_left(), rect.get_top(), rect.get_width(), rect.get_height(), SWP_NOZORDER | SWP_NOACTIVATE)
+    }
+
+    /// Changes the position of the window in the z-order.
+    pub fn set_z_order(&self, order: WindowZOrder) -> Result<()> {
+        self.set_pos(order.into(), 0, 0, 0, 0, SWP_NOMOVE | SWP_NOSIZE | SWP_NOACTIVATE)
+    }
+
+    /// Minimizes the window.
+    pub fn minimize(&self) -> Result<()> {
+        self.show_window(SW_MINIMIZE)
+    }
+
+    /// Maximizes the window.
+    pub fn maximize(&self) -> Result<()> {
+        self.show_window(SW_MAXIMIZE)
+    }
+
+    /// Restores the minimized or maximized window to its original size and position.
+    pub fn restore(&self) -> Result<()> {
+        self.show_window(SW_RESTORE)
+    }
+
+    /// Shows the window.
+    pub fn show(&self) -> Result<()> {
+        self.show_window(SW_SHOW)
+    }
+
+    /// Hides the window.
+    pub fn hide(&self) -> Result<()> {
+        self.show_window(SW_HIDE)
+    }
+
+    /// Restores the window if it is minimized, brings it to the top of the z-order and activates it.
+    ///
+    /// Returns `false` if the system refused to bring the window into the foreground.
+    pub fn bring_to_front(&self) -> Result<bool> {
+        if self.is_minimized() {
+            self.restore()?;
+        }
+
+        unsafe { BringWindowToTop(*self)? };
+        Ok(unsafe { SetForegroundWindow(*self) }.as_bool())
+    }
+
+    fn show_window(&self, cmd: SHOW_WINDOW_CMD) -> Result<()> {
+        self.check()?;
+
+        // The return value of `ShowWindow` is the previous visibility.
+        let _ = unsafe { ShowWindow(*self, cmd) };
+        Ok(())
+    }
+
+    fn set_pos(&self, insert_after: HWND, x: i32, y: i32, width: i32, height: i32, flags: SET_WINDOW_POS_FLAGS) -> Result<()> {
+        unsafe { SetWindowPos(*self, insert_after, x, y, width, height, flags)? };
+        Ok(())
+    }
+
+    fn check(&self) -> Result<()> {
+        if self.is_window() {
+            Ok(())
+        } else {
+            Err(Error::new(ERR_INVALID_OBJECT, "invalid window handle"))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use windows::Win32::Foundation::HWND;
+    use windows::Win32::UI::WindowsAndMessaging::HWND_BOTTOM;
+    use windows::Win32::UI::WindowsAndMessaging::HWND_TOPMOST;
+
+    use crate::types::Handle;
+
+    use super::WindowFilter;
+    use super::WindowInfo;
+    use super::WindowZOrder;
+
+    fn notepad() -> WindowInfo {
+        WindowInfo {
+            handle: Handle::from(0x1234),
+            title: "readme.txt - Notepad".into(),
+            classname: "Notepad".into(),
+            process_id: 42,
+            visible: true
+        }
+    }
+
+    #[test]
+    fn test_filter_all() {
+        assert!(WindowFilter::new().matches(&notepad()));
+        assert!(WindowFilter::new().matches(&WindowInfo::default()));
+    }
+
+    #[test]
+    fn test_filter_title() {
+        assert!(WindowFilter::new().title("readme.txt - Notepad").matches(&notepad()));
+        assert!(!WindowFilter::new().title("Notepad").matches(&notepad()));
+        assert!(WindowFilter::new().contains_title("Notepad").matches(&notepad()));
+        assert!(!WindowFilter::new().contains_title("notepad").matches(&notepad()));
+    }
+
+    #[test]
+    fn test_filter_properties() {
+        let info = notepad();
+        assert!(WindowFilter::new().classname("Notepad").process_id(42).visible(true).matches(&info));
+        assert!(!WindowFilter::new().classname("Edit").matches(&info));
+        assert!(!WindowFilter::new().process_id(7).matches(&info));
+
+        let hidden = WindowInfo { visible: false, ..notepad() };
+        assert!(WindowFilter::new().matches(&hidden));
+        assert!(!WindowFilter::new().visible(true).matches(&hidden));
+    }
+
+    #[test]
+    fn test_z_order() {
+        assert_eq!(HWND::from(WindowZOrder::TopMost), HWND_TOPMOST);
+        assert_eq!(HWND::from(WindowZOrder::Bottom), HWND_BOTTOM);
+        assert_eq!(HWND::from(WindowZOrder::After(Handle::from(0x10))), HWND(0x10));
+    }
+}
@@ -4,12 +4,10 @@ use uiautomation::core::UIAutomation;
 use uiautomation::processes::Process;
 
 fn main() {
-    Process::create("notepad.exe").unwrap();
+    let process = Process::create("notepad.exe").unwrap();
 
     let automation = UIAutomation::new().unwrap();
-    let root = automation.get_root_element().unwrap();
-    let matcher = automation.create_matcher().from(root).timeout(10000).classname("Notepad");
-    if let Ok(notepad) = matcher.find_first() {
+    if let Ok(notepad) = process.main_window(&automation, 10000) {
         println!("Found: {} - {}", notepad.get_name().unwrap(), notepad.get_classname().unwrap());
 
         notepad.send_keys("Hello, Rust UIAutomation!", 10).unwrap();
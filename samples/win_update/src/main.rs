@@ -5,7 +5,6 @@ use uiautomation::UIAutomation;
 use uiautomation::UIElement;
 use uiautomation::actions::Invoke;
 use uiautomation::actions::SelectionItem;
-use uiautomation::actions::Toggle;
 use uiautomation::controls::ButtonControl;
 use uiautomation::controls::Control;
 use uiautomation::controls::ListItemControl;
@@ -15,6 +14,10 @@ use uiautomation::controls::WindowControl;
 use uiautomation::dialogs::show_error;
 use uiautomation::filters::NameFilter;
 use uiautomation::filters::OrFilter;
+use uiautomation::processes::Process;
+
+/// The AppUserModelID of the Settings app.
+const SETTINGS_AUMID: &str = "windows.immersivecontrolpanel_cw5n1h2txyewy!microsoft.windows.immersivecontrolpanel";
 
 fn main() {
     let ret = auto_update();
@@ -28,23 +31,8 @@ fn main() {
 fn auto_update() -> Result<()> {
     let automation = UIAutomation::new()?;
 
-    if let Ok(start) = automation.create_matcher().match_name("开始").classname("ToggleButton").find_first() {     // 尝试Win11
-        let button: ButtonControl = start.try_into()?;
-        button.toggle()?;
-    } else if let Ok(start) = automation.create_matcher().match_name("开始").classname("Start").find_first() {      // 尝试Win10
-        let button: ButtonControl = start.try_into()?;
-        button.invoke()?;
-    } else {
-        return Err("无法定位开始按钮".into());
-    }
-
-    let matcher = automation.create_matcher().match_name("设置").classname("GridViewItem");
-    let config = matcher.find_first()?;
-    let item: ListItemControl = config.try_into()?;
-    item.invoke()?;
-
-    let matcher = automation.create_matcher().match_name("设置").classname("ApplicationFrameWindow");
-    let settings = matcher.find_first()?;
+    let process = Process::launch_packaged(SETTINGS_AUMID, "")?;
+    let settings = process.main_window(&automation, 10000)?;
     
     let window: WindowControl = settings.clone().try_into()?;
     window.set_foregrand()?;